@@ -0,0 +1,33 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day01::generate;
+use day01::{find_first_digit, find_first_digit_naive, find_last_digit, find_last_digit_naive};
+
+fn find_digits(c: &mut Criterion) {
+    for (name, length, sparseness) in [("dense", 20, 4), ("sparse", 20, 40), ("long_sparse", 1000, 400), ("long_none", 1000, u64::MAX)] {
+        let lines = generate::lines(12345, 1000, length, sparseness);
+        let mut group = c.benchmark_group(format!("find_digits_{name}"));
+        group.bench_function("naive", |b| {
+            b.iter(|| {
+                lines
+                    .iter()
+                    .map(|line| find_first_digit_naive(black_box(line)).unwrap_or(0) + find_last_digit_naive(line).unwrap_or(0))
+                    .sum::<u32>()
+            })
+        });
+        group.bench_function("aho_corasick", |b| {
+            b.iter(|| {
+                lines
+                    .iter()
+                    .map(|line| find_first_digit(black_box(line)).unwrap_or(0) + find_last_digit(line).unwrap_or(0))
+                    .sum::<u32>()
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, find_digits);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Reading a 1 GiB generated document from a file: line by line into a
+//! `String` each, as from stdin; as `&str` lines of the mapped file; and
+//! scanning the mapped file as bytes, as for a file.
+
+use std::fs::{self, File};
+use std::hint::black_box;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use aoc_utils::lines::str_lines;
+use aoc_utils::source::InputSource;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day01::{generate, sum_calibration_lines, sum_calibration_values, CalibrationParser};
+
+const SIZE: u64 = 1 << 30;
+
+/// The generated document, written once to the temporary directory and kept
+/// for later runs.
+fn document() -> PathBuf {
+    let path = std::env::temp_dir().join("day01-mmap-bench-1g.txt");
+    if fs::metadata(&path).is_ok_and(|metadata| metadata.len() >= SIZE) {
+        return path;
+    }
+    let mut file = BufWriter::new(File::create(&path).unwrap());
+    let mut written = 0;
+    for seed in 0.. {
+        let block = generate::document(seed, 100_000, 30);
+        file.write_all(block.as_bytes()).unwrap();
+        written += block.len() as u64;
+        if written >= SIZE {
+            break;
+        }
+    }
+    file.flush().unwrap();
+    path
+}
+
+fn read_1g(c: &mut Criterion) {
+    let source = InputSource::File(document());
+    let parser = CalibrationParser::numerals();
+    let mut group = c.benchmark_group("read_1g");
+    group.sample_size(10).measurement_time(Duration::from_secs(40));
+    group.bench_function("lines", |b| {
+        b.iter(|| sum_calibration_lines(black_box(&source).lines().unwrap(), &parser, false).unwrap().sum)
+    });
+    group.bench_function("mmap_lines", |b| {
+        b.iter(|| {
+            let input = black_box(&source).map().unwrap();
+            sum_calibration_lines(str_lines(&input.text(false).unwrap()), &parser, false).unwrap().sum
+        })
+    });
+    group.bench_function("mmap_scan", |b| {
+        b.iter(|| sum_calibration_values(&black_box(&source).map().unwrap(), &parser, false).unwrap().sum)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, read_1g);
+criterion_main!(benches);
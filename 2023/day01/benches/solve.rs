@@ -0,0 +1,22 @@
+//! Parsing and solving each part on the real input and generated ones.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day01::{generate, Day01};
+
+/// The real input, then generated ones: documents of more and more 30
+/// fragment lines.
+fn inputs() -> Vec<(String, String)> {
+    let mut inputs = vec![("input".to_string(), include_str!("../input.txt").to_string())];
+    for lines in [1_000, 10_000, 100_000] {
+        inputs.push((format!("generated/{lines}"), generate::document(12345, lines, 30)));
+    }
+    inputs
+}
+
+fn solve(c: &mut Criterion) {
+    aoc_utils::benches::solver(c, &Day01, &inputs());
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
@@ -0,0 +1,52 @@
+//! Scramble a calibration document so it can be shared without sharing the
+//! puzzle input, keeping both parts' answers.
+
+use aoc_utils::random::Rng;
+
+use crate::ENGLISH;
+
+/// Letters that aren't in any digit word, so they can't start or finish one.
+const FILLER: &[u8] = b"abcdjklmpqyz";
+
+/// Replace every character that isn't part of a digit, numeric or spelled
+/// out, with a random filler letter, and shuffle the lines. The digits, and
+/// so the answers, stay the same.
+pub fn anonymize(input: &str, seed: u64) -> String {
+    let mut rng = Rng::new(seed);
+    let mut lines: Vec<String> = input
+        .lines()
+        .map(|line| {
+            let mut kept = vec![false; line.len()];
+            for found in ENGLISH.overlapping.find_overlapping_iter(line) {
+                kept[found.range()].fill(true);
+            }
+            line.char_indices()
+                .map(|(i, c)| if kept[i] { c } else { char::from(*rng.choose(FILLER)) })
+                .collect()
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::solver::DynSolver;
+
+    use super::*;
+    use crate::Day01;
+
+    #[test]
+    fn test_anonymize() {
+        let input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen\n";
+        let anonymized = anonymize(input, 1);
+        assert_ne!(anonymized, input);
+        assert_eq!(anonymized, anonymize(input, 1));
+        assert!(anonymized.contains("eightwothree\n"));
+        assert!(anonymized.lines().any(|line| line.starts_with('7') && &line[6..9] == "six" && !line.contains("teen")));
+        assert_eq!(Day01.solve(&anonymized, 2).unwrap(), Day01.solve(input, 2).unwrap());
+
+        let input = include_str!("../input.txt");
+        assert_eq!(Day01.solve_both(&anonymize(input, 2)).unwrap(), Day01.solve_both(input).unwrap());
+    }
+}
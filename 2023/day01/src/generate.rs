@@ -0,0 +1,70 @@
+//! Seeded calibration documents of any size, for benchmarks and stress tests.
+
+use aoc_utils::random::Rng;
+
+const DIGITS: [&str; 6] = ["one", "eight", "two", "3", "7", "nine"];
+const LETTERS: [&str; 8] = ["a", "b", "xq", "on", "tw", "th", "ei", "n"];
+
+/// `count` lines of `length` fragments. One in `sparseness` fragments is a
+/// digit, numeric or spelled out, and the rest are letters, some of which
+/// start digit words. Lines may have no digits at all.
+pub fn lines(seed: u64, count: usize, length: usize, sparseness: u64) -> Vec<String> {
+    let mut rng = Rng::new(seed);
+    (0..count)
+        .map(|_| {
+            (0..length)
+                .map(|_| if rng.one_in(sparseness) { *rng.choose(&DIGITS) } else { *rng.choose(&LETTERS) })
+                .collect()
+        })
+        .collect()
+}
+
+/// A document of `count` lines like `lines`, with a numeric digit put in
+/// every line so both parts can solve it.
+pub fn document(seed: u64, count: usize, length: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut document = String::new();
+    for mut line in lines(rng.next_u64(), count, length, 4) {
+        let at = rng.range(0..line.len() as u64 + 1) as usize;
+        line.insert(at, char::from(b'1' + rng.range(0..9) as u8));
+        document.push_str(&line);
+        document.push('\n');
+    }
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        find_first_digit, find_first_digit_naive, find_last_digit, find_last_digit_naive, sum_calibration_values,
+        sum_calibration_values_parallel, CalibrationParser,
+    };
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines(1, 50, 20, 4), lines(1, 50, 20, 4));
+        assert_ne!(lines(1, 50, 20, 4), lines(2, 50, 20, 4));
+        assert_eq!(lines(1, 50, 20, 4).len(), 50);
+        let numerals = CalibrationParser::numerals();
+        assert!(lines(1, 50, 20, u64::MAX).iter().all(|line| numerals.first(line.as_bytes()).is_none()));
+    }
+
+    /// A large document sums the same however it's read, and the naive
+    /// finders agree with the parser on every line.
+    #[test]
+    fn test_document() {
+        let document = document(7, 20_000, 30);
+        assert_eq!(document.lines().count(), 20_000);
+        for parser in [CalibrationParser::numerals(), CalibrationParser::english()] {
+            let sequential = sum_calibration_values(document.as_bytes(), &parser, false).unwrap();
+            let parallel = sum_calibration_values_parallel(document.as_bytes(), &parser, false).unwrap();
+            assert_eq!(sequential.sum, parallel.sum);
+            assert!(sequential.skipped.is_empty());
+        }
+        for line in document.lines().take(1000) {
+            assert_eq!(find_first_digit(line), find_first_digit_naive(line));
+            assert_eq!(find_last_digit(line), find_last_digit_naive(line));
+        }
+    }
+}
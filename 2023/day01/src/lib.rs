@@ -0,0 +1,566 @@
+//! Trebuchet calibration values: the first and last digits of each line, as
+//! a two digit number.
+//!
+//! ```
+//! use day01::CalibrationParser;
+//!
+//! let part1 = CalibrationParser::numerals();
+//! let part2 = CalibrationParser::english();
+//! assert_eq!(part1.calibration_value(b"two1nine3").unwrap(), 13);
+//! assert_eq!(part2.calibration_value(b"two1nine3").unwrap(), 23);
+//! ```
+
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use aho_corasick::{AhoCorasick, MatchKind};
+use aoc_utils::cancel::Cancel;
+use aoc_utils::error::{AocError, Context, Result};
+use aoc_utils::lenient::{self, Validation};
+use aoc_utils::solver::{Answer, Solver};
+use memchr::{memchr, memchr_iter};
+use rayon::prelude::*;
+
+pub mod anonymize;
+pub mod generate;
+
+const DIGITS: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+/// Part 2's parser, used by the free functions.
+static ENGLISH: LazyLock<CalibrationParser> = LazyLock::new(CalibrationParser::english);
+
+/// Parses calibration values out of lines by finding their first and last
+/// digits: '0' to '9', and words for them, which may overlap or contain each
+/// other. `numerals` gives part 1's rules and `english` part 2's.
+#[derive(Clone, Debug)]
+pub struct CalibrationParser {
+    /// Reports the leftmost match, the longest of those starting there.
+    leftmost: AhoCorasick,
+    /// Reports every match, including overlapping ones.
+    overlapping: AhoCorasick,
+    /// The value of each pattern.
+    values: Vec<u32>,
+}
+
+impl CalibrationParser {
+    /// Look for the numerals and the given words. Several words may have the
+    /// same value.
+    pub fn new<'a>(words: impl IntoIterator<Item = (&'a str, u32)>) -> Result<CalibrationParser> {
+        let mut values: HashMap<String, u32> = (0..10).map(|digit| (digit.to_string(), digit)).collect();
+        for (word, value) in words {
+            if value > 9 {
+                return Err(AocError::parse(format!("Word '{word}' must be for a digit, not {value}")));
+            }
+            if word.is_empty() {
+                return Err(AocError::parse("Words for digits can't be empty"));
+            }
+            match values.insert(word.to_string(), value) {
+                Some(other) if other != value => {
+                    return Err(AocError::parse(format!("Word '{word}' is for both {other} and {value}")));
+                }
+                _ => {}
+            }
+        }
+
+        let (patterns, values): (Vec<String>, Vec<u32>) = values.into_iter().unzip();
+        let leftmost = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .build(&patterns)
+            .map_err(|error| AocError::parse(error.to_string()))?;
+        let overlapping = AhoCorasick::new(&patterns).map_err(|error| AocError::parse(error.to_string()))?;
+        Ok(CalibrationParser { leftmost, overlapping, values })
+    }
+
+    /// Look for the numerals only, as in part 1.
+    pub fn numerals() -> CalibrationParser {
+        CalibrationParser::new([]).unwrap()
+    }
+
+    /// Look for the numerals and the English words "zero" to "nine", as in
+    /// part 2.
+    pub fn english() -> CalibrationParser {
+        CalibrationParser::new(DIGITS.iter().zip(0..).map(|(word, value)| (*word, value))).unwrap()
+    }
+
+    /// Look for the numerals and the words in a dictionary. Each line of the
+    /// dictionary is a word and the digit it stands for, separated by
+    /// whitespace, like "neuf 9". Blank lines and lines starting with '#' are
+    /// skipped.
+    pub fn from_dictionary(dictionary: &str) -> Result<CalibrationParser> {
+        let mut words = Vec::new();
+        for (i, line) in dictionary.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, value) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| AocError::parse(format!("Dictionary line {} '{line}' must be a word and a digit", i + 1)))?;
+            let value = value
+                .trim()
+                .parse()
+                .with_context(|| format!("Dictionary line {} '{line}' must end in a digit", i + 1))?;
+            words.push((word, value));
+        }
+        CalibrationParser::new(words)
+    }
+
+    /// Parse the calibration value out of a line: the first digit in it,
+    /// then the last.
+    pub fn calibration_value(&self, line: &[u8]) -> Result<u32> {
+        let no_digits = || AocError::parse(format!("Line '{}' contains no digits", String::from_utf8_lossy(line)));
+        let first_digit = self.first(line).ok_or_else(no_digits)?;
+        let last_digit = self.last(line).ok_or_else(no_digits)?;
+
+        Ok(first_digit * 10 + last_digit)
+    }
+
+    /// Find the first digit in the line, in one pass. Of the words starting
+    /// at the same place, the longest wins.
+    pub fn first(&self, line: &[u8]) -> Option<u32> {
+        self.first_match(line).map(|found| found.value)
+    }
+
+    /// Find the first digit in the line and where it is.
+    pub fn first_match(&self, line: &[u8]) -> Option<DigitMatch> {
+        self.leftmost.find(line).map(|found| DigitMatch {
+            range: found.range(),
+            value: self.values[found.pattern()],
+        })
+    }
+
+    /// Find the last digit in the line: the one starting last, or the longest
+    /// of those. Matches may overlap, as in "oneight".
+    pub fn last(&self, line: &[u8]) -> Option<u32> {
+        self.last_match(line).map(|found| found.value)
+    }
+
+    /// Find the last digit in the line and where it is.
+    ///
+    /// The matchers only search forwards, so this searches the end of the
+    /// line, doubling the length searched until it finds a digit. That
+    /// touches each byte at most twice overall, and stops early when there's
+    /// a digit near the end.
+    pub fn last_match(&self, line: &[u8]) -> Option<DigitMatch> {
+        let mut window = 16;
+        loop {
+            let start = line.len().saturating_sub(window);
+            let last = self
+                .overlapping
+                .find_overlapping_iter(&line[start..])
+                .max_by_key(|found| (found.start(), found.len()));
+            if let Some(found) = last {
+                return Some(DigitMatch {
+                    range: start + found.start()..start + found.end(),
+                    value: self.values[found.pattern()],
+                });
+            }
+            if start == 0 {
+                return None;
+            }
+            window *= 2;
+        }
+    }
+}
+
+/// A digit found in a line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigitMatch {
+    /// Where the digit is in the line, in bytes.
+    pub range: Range<usize>,
+    pub value: u32,
+}
+
+/// Parse the "calibration value" out of a line. The calibration value is a two
+/// digit number consisting of the first and last digits in the line.
+pub fn parse_calibration_line(line: &str) -> Result<u32> {
+    ENGLISH.calibration_value(line.as_bytes())
+}
+
+/// The sum of the calibration values, and the lines skipped for having no
+/// digits.
+#[derive(Debug, Default, PartialEq)]
+pub struct Calibration {
+    pub sum: u64,
+    /// The numbers of the skipped lines, counting from 1.
+    pub skipped: Vec<usize>,
+}
+
+impl Calibration {
+    /// Add a line's calibration value. An invalid line is skipped if
+    /// `skip_invalid` is set, and is otherwise an error naming the line.
+    fn add(&mut self, line_number: usize, value: Result<u32>, skip_invalid: bool) -> Result<()> {
+        match value {
+            Ok(value) => self.sum += value as u64,
+            Err(_) if skip_invalid => self.skipped.push(line_number),
+            Err(error) => return Err(error.at_line(line_number)),
+        }
+        Ok(())
+    }
+}
+
+/// Sum the calibration values of the lines.
+pub fn sum_calibration_lines(
+    lines: impl Iterator<Item = Result<impl AsRef<str>>>,
+    parser: &CalibrationParser,
+    skip_invalid: bool,
+) -> Result<Calibration> {
+    let mut calibration = Calibration::default();
+    for (i, line) in lines.enumerate() {
+        calibration.add(i + 1, parser.calibration_value(line?.as_ref().as_bytes()), skip_invalid)?;
+    }
+    Ok(calibration)
+}
+
+/// Check that `parser` finds a digit on every line, collecting all the
+/// errors.
+pub fn validate(reader: impl BufRead, parser: &CalibrationParser) -> Validation {
+    lenient::validate(|policy| {
+        for line in aoc_utils::lines::lines(reader) {
+            let line = line?;
+            policy.next_line();
+            policy.check(parser.calibration_value(line.as_bytes()))?;
+        }
+        Ok(())
+    })
+}
+
+/// Sum the calibration values of the lines in `input`, finding the lines
+/// with memchr rather than copying each into a `String`. Lines may end in
+/// "\r\n", and the last line needn't end in a newline.
+pub fn sum_calibration_values(input: &[u8], parser: &CalibrationParser, skip_invalid: bool) -> Result<Calibration> {
+    sum_chunk(input, parser, skip_invalid, 1)
+}
+
+/// The number of bytes of input each parallel task sums, roughly.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Sum the calibration values of the lines in `input` like
+/// `sum_calibration_values`, splitting it into chunks summed in parallel.
+/// The result is the same, including which line an error is for.
+pub fn sum_calibration_values_parallel(input: &[u8], parser: &CalibrationParser, skip_invalid: bool) -> Result<Calibration> {
+    sum_parallel(input, parser, skip_invalid, CHUNK_SIZE)
+}
+
+fn sum_parallel(input: &[u8], parser: &CalibrationParser, skip_invalid: bool, chunk_size: usize) -> Result<Calibration> {
+    // Split after the first newline at or past each multiple of the chunk
+    // size, and number each chunk's first line.
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut first_line = 1;
+    while start < input.len() {
+        let end = match memchr(b'\n', &input[(start + chunk_size).min(input.len())..]) {
+            Some(offset) => start + chunk_size + offset + 1,
+            None => input.len(),
+        };
+        let chunk = &input[start..end];
+        chunks.push((chunk, first_line));
+        first_line += memchr_iter(b'\n', chunk).count();
+        start = end;
+    }
+
+    let sums: Vec<Result<Calibration>> = chunks
+        .into_par_iter()
+        .map(|(chunk, first_line)| sum_chunk(chunk, parser, skip_invalid, first_line))
+        .collect();
+    let mut calibration = Calibration::default();
+    for sum in sums {
+        let sum = sum?;
+        calibration.sum += sum.sum;
+        calibration.skipped.extend(sum.skipped);
+    }
+    Ok(calibration)
+}
+
+/// Sum the calibration values of the lines in `input`, the first of which is
+/// line `first_line`.
+fn sum_chunk(input: &[u8], parser: &CalibrationParser, skip_invalid: bool, first_line: usize) -> Result<Calibration> {
+    let mut calibration = Calibration::default();
+    let mut start = 0;
+    let ends = memchr_iter(b'\n', input).chain((!input.ends_with(b"\n") && !input.is_empty()).then_some(input.len()));
+    for (i, end) in ends.enumerate() {
+        let line = &input[start..end];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        calibration.add(first_line + i, parser.calibration_value(line), skip_invalid)?;
+        start = end + 1;
+    }
+    Ok(calibration)
+}
+
+/// Day 1's solution: part 1 finds numerals, part 2 words for them too.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Day01;
+
+impl Solver for Day01 {
+    /// The input itself, since the parts find digits differently.
+    type Parsed = String;
+
+    fn parse(&self, input: &str) -> Result<String> {
+        Ok(aoc_utils::lines::strip_bom(input).to_string())
+    }
+
+    fn part1(&self, input: &String, _: &Cancel) -> Result<Answer> {
+        let parser = CalibrationParser::numerals();
+        Ok(sum_calibration_values(input.as_bytes(), &parser, false)?.sum.into())
+    }
+
+    fn part2(&self, input: &String, _: &Cancel) -> Result<Answer> {
+        Ok(sum_calibration_values(input.as_bytes(), &ENGLISH, false)?.sum.into())
+    }
+}
+
+/// Find the first digit in the line, either numeric or spelled out, in one
+/// pass.
+pub fn find_first_digit(line: &str) -> Option<u32> {
+    ENGLISH.first(line.as_bytes())
+}
+
+/// Find the last digit in the line, either numeric or spelled out. Matches
+/// may overlap, as in "oneight".
+pub fn find_last_digit(line: &str) -> Option<u32> {
+    ENGLISH.last(line.as_bytes())
+}
+
+/// Find the first digit in the line by trying each position in turn. This is
+/// the reference for `find_first_digit`, kept for tests and benchmarks.
+pub fn find_first_digit_naive(line: &str) -> Option<u32> {
+    for (i, _) in line.char_indices() {
+        if let Some(digit) = parse_starts_with_digit(&line[i..]) {
+            return Some(digit);
+        }
+    }
+
+    None
+}
+
+/// Find the last digit in the line by trying each position in turn, from the
+/// end. This is the reference for `find_last_digit`.
+pub fn find_last_digit_naive(line: &str) -> Option<u32> {
+    for (i, _) in line.char_indices().rev() {
+        if let Some(digit) = parse_starts_with_digit(&line[i..]) {
+            return Some(digit);
+        }
+    }
+
+    None
+}
+
+/// Parse either a numeric or spelled out digit from the start of the string.
+fn parse_starts_with_digit(s: &str) -> Option<u32> {
+    let c = s.chars().next()?;
+    if c.is_ascii_digit() {
+        return c.to_digit(10);
+    }
+
+    for (i, digit) in DIGITS.iter().enumerate() {
+        if s.starts_with(digit) {
+            return Some(i as u32);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_first_num() {
+        assert_eq!(find_first_digit("69"), Some(6));
+        assert_eq!(find_first_digit("foo4"), Some(4));
+        assert_eq!(find_first_digit(""), None);
+        assert_eq!(find_first_digit("foo"), None);
+    }
+
+    #[test]
+    fn test_parse_first_str() {
+        assert_eq!(find_first_digit("sixnine"), Some(6));
+        assert_eq!(find_first_digit("foofour"), Some(4));
+        assert_eq!(find_first_digit("four20"), Some(4));
+    }
+
+    #[test]
+    fn test_parse_last_num() {
+        assert_eq!(find_last_digit("69"), Some(9));
+        assert_eq!(find_last_digit("foo4"), Some(4));
+        assert_eq!(find_last_digit(""), None);
+        assert_eq!(find_last_digit("foo"), None);
+    }
+
+    #[test]
+    fn test_parse_last_str() {
+        assert_eq!(find_last_digit("sixnine"), Some(9));
+        assert_eq!(find_last_digit("foofour"), Some(4));
+        assert_eq!(find_last_digit("42zero"), Some(0));
+    }
+
+    #[test]
+    fn test_parse_overlapping() {
+        assert_eq!(find_first_digit("oneight"), Some(1));
+        assert_eq!(find_last_digit("oneight"), Some(8));
+        assert_eq!(find_last_digit("twoneighthreeightwo"), Some(2));
+        assert_eq!(find_first_digit("xtwone3"), Some(2));
+        assert_eq!(find_first_digit("eightwothree"), Some(8));
+        let long = format!("one{}seven{}", "x".repeat(100), "y".repeat(15));
+        assert_eq!(find_last_digit(&long), Some(7));
+        assert_eq!(find_last_digit(&format!("three{}", "x".repeat(100))), Some(3));
+    }
+
+    #[test]
+    fn test_matches_naive() {
+        let fragments = ["one", "tw", "o", "eight", "thr", "ee", "nin", "e", "7", "x", "zero", "sev"];
+        for a in fragments {
+            for b in fragments {
+                for c in fragments {
+                    let line = format!("{a}{b}{c}");
+                    assert_eq!(find_first_digit(&line), find_first_digit_naive(&line), "{line}");
+                    assert_eq!(find_last_digit(&line), find_last_digit_naive(&line), "{line}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(parse_calibration_line("sixnine").unwrap(), 69);
+        assert_eq!(parse_calibration_line("foofour").unwrap(), 44);
+        assert_eq!(parse_calibration_line("42zero").unwrap(), 40);
+        let error = parse_calibration_line("abc").unwrap_err();
+        assert_eq!(error.to_string(), "Line 'abc' contains no digits");
+    }
+
+    #[test]
+    fn test_sum_calibration_values() {
+        let parser = CalibrationParser::english();
+        let sum = |input: &[u8]| sum_calibration_values(input, &parser, false).map(|calibration| calibration.sum);
+        assert_eq!(sum(b"sixnine\nfoofour\n42zero\n").unwrap(), 69 + 44 + 40);
+        assert_eq!(sum(b"sixnine\r\nfoofour\r\n42zero").unwrap(), 69 + 44 + 40);
+        assert_eq!(sum(b"").unwrap(), 0);
+        assert!(sum(b"1\n\n2\n").is_err());
+        let error = sum(b"1\nabc").unwrap_err();
+        assert_eq!(format!("{error:#}"), "Error on line 2: Line 'abc' contains no digits");
+    }
+
+    #[test]
+    fn test_skip_invalid() {
+        let parser = CalibrationParser::english();
+        let input = "1\nabc\n23\n\nfour\n";
+        let expected = Calibration { sum: 11 + 23 + 44, skipped: vec![2, 4] };
+        assert_eq!(sum_calibration_values(input.as_bytes(), &parser, true).unwrap(), expected);
+        let lines = input.lines().map(|line| Ok(line.to_string()));
+        assert_eq!(sum_calibration_lines(lines, &parser, true).unwrap(), expected);
+
+        let lines = input.lines().map(|line| Ok(line.to_string()));
+        let error = sum_calibration_lines(lines, &parser, false).unwrap_err();
+        assert_eq!(format!("{error:#}"), "Error on line 2: Line 'abc' contains no digits");
+    }
+
+    #[test]
+    fn test_crlf_input() {
+        let parser = CalibrationParser::english();
+        let input = "\u{feff}two1nine\r\neightwothree  \r\n\r\n7pqrstsixteen\t\r\n";
+        let expected = Calibration { sum: 29 + 83 + 76, skipped: vec![3] };
+        let lines = aoc_utils::lines::lines(input.as_bytes());
+        assert_eq!(sum_calibration_lines(lines, &parser, true).unwrap(), expected);
+        assert_eq!(sum_calibration_values(input.as_bytes(), &parser, true).unwrap(), expected);
+        assert_eq!(sum_calibration_values_parallel(input.as_bytes(), &parser, true).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_sum_parallel() {
+        let parser = CalibrationParser::english();
+        let mut input = String::new();
+        for i in 0..1000 {
+            input.push_str(&format!("x{i}oneight\n"));
+        }
+        let expected = sum_calibration_values(input.as_bytes(), &parser, false).unwrap();
+        for chunk_size in [1, 7, 100, 1 << 20] {
+            assert_eq!(sum_parallel(input.as_bytes(), &parser, false, chunk_size).unwrap(), expected);
+            let unterminated = input.trim_end().as_bytes();
+            assert_eq!(sum_parallel(unterminated, &parser, false, chunk_size).unwrap(), expected);
+        }
+        assert_eq!(sum_calibration_values_parallel(b"", &parser, false).unwrap(), Calibration::default());
+
+        // Errors and skipped lines keep their line numbers.
+        let input = input.replace("x500oneight", "bad").replace("x900oneight", "bad").replace("x20oneight", "\r");
+        for chunk_size in [1, 7, 100, 1 << 20] {
+            let error = sum_parallel(input.as_bytes(), &parser, false, chunk_size).unwrap_err();
+            assert_eq!(format!("{error:#}"), "Error on line 21: Line '' contains no digits");
+            let calibration = sum_parallel(input.as_bytes(), &parser, true, chunk_size).unwrap();
+            assert_eq!(calibration.skipped, [21, 501, 901]);
+        }
+    }
+
+    #[test]
+    fn test_solver() {
+        let parsed = Day01.parse("\u{feff}two1nine\n7eightwothree\nabc123xyz\n").unwrap();
+        assert_eq!(Day01.part1(&parsed, &Cancel::new()).unwrap(), Answer::Unsigned(11 + 77 + 13));
+        assert!(Day01.part1(&Day01.parse("two\n").unwrap(), &Cancel::new()).is_err());
+        assert_eq!(Day01.part2(&parsed, &Cancel::new()).unwrap(), Answer::Unsigned(29 + 73 + 13));
+    }
+
+    aoc_utils::aoc_example_tests!(Day01, part1 = 142, input = "fixtures/example-part1.txt");
+    aoc_utils::aoc_example_tests!(Day01, part2 = 281, input = "fixtures/example-part2.txt");
+
+    #[test]
+    fn test_matches() {
+        let parser = CalibrationParser::english();
+        let line = format!("x{}oneight", "y".repeat(40));
+        assert_eq!(parser.first_match(line.as_bytes()), Some(DigitMatch { range: 41..44, value: 1 }));
+        assert_eq!(parser.last_match(line.as_bytes()), Some(DigitMatch { range: 43..48, value: 8 }));
+        assert_eq!(parser.first_match(b"7"), Some(DigitMatch { range: 0..1, value: 7 }));
+        assert_eq!(parser.last_match(b"7"), Some(DigitMatch { range: 0..1, value: 7 }));
+        assert_eq!(parser.last_match(b"abc"), None);
+    }
+
+    #[test]
+    fn test_numerals() {
+        let parser = CalibrationParser::numerals();
+        assert_eq!(parser.calibration_value(b"1abc2").unwrap(), 12);
+        assert_eq!(parser.calibration_value(b"pqr3stu8vwx").unwrap(), 38);
+        assert_eq!(parser.calibration_value(b"treb7uchet").unwrap(), 77);
+        assert_eq!(parser.calibration_value(b"one2three4five").unwrap(), 24);
+        assert!(parser.calibration_value(b"onetwo").is_err());
+    }
+
+    #[test]
+    fn test_dictionary() {
+        let parser = CalibrationParser::from_dictionary("# German\nein 1\neins 1\nzwei 2\n\nacht   8\nneun\t9\nnull 0\n").unwrap();
+        assert_eq!(parser.calibration_value(b"xeinsacht").unwrap(), 18);
+        assert_eq!(parser.calibration_value(b"zweins").unwrap(), 21);
+        assert_eq!(parser.calibration_value(b"7neunull").unwrap(), 70);
+        assert!(parser.calibration_value(b"one").is_err());
+
+        // Words containing each other, for different digits.
+        let parser = CalibrationParser::from_dictionary("ab 1\nabc 2\nbc 3").unwrap();
+        assert_eq!(parser.first(b"xabc"), Some(2));
+        assert_eq!(parser.last(b"abcx"), Some(3));
+        assert_eq!(parser.last(b"xab"), Some(1));
+
+        assert!(CalibrationParser::from_dictionary("one").is_err());
+        assert!(CalibrationParser::from_dictionary("one x").is_err());
+        assert!(CalibrationParser::from_dictionary("ten 10").is_err());
+        assert!(CalibrationParser::from_dictionary("one 1\none 2").is_err());
+        assert!(CalibrationParser::from_dictionary("one 1\none 1").is_ok());
+        assert!(CalibrationParser::from_dictionary("7 1").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_digit_finders_any_line(line in "\\PC{0,40}") {
+            prop_assert_eq!(find_first_digit(&line), find_first_digit_naive(&line));
+            prop_assert_eq!(find_last_digit(&line), find_last_digit_naive(&line));
+        }
+
+        #[test]
+        fn test_digit_finders_near_valid(line in "(one|two|three|four|five|six|seven|eight|nine|[a-z]|[0-9]|é){0,12}") {
+            prop_assert_eq!(find_first_digit(&line), find_first_digit_naive(&line));
+            prop_assert_eq!(find_last_digit(&line), find_last_digit_naive(&line));
+        }
+    }
+}
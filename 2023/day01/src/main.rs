@@ -1,41 +1,189 @@
-use std::io::{self, BufRead};
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
 
-use anyhow::{anyhow, Result};
+use anstyle::{AnsiColor, Style};
+use anyhow::{Context, Result};
+use aoc_utils::lines::check_utf8;
+use aoc_utils::output::{ColorChoice, Format, Output, Timings};
+use aoc_utils::source::{InputArg, InputSource};
+use clap::Parser;
 
-fn main() -> Result<()> {
-    let stdin = io::stdin();
+use day01::{
+    sum_calibration_lines, sum_calibration_values, sum_calibration_values_parallel, validate, CalibrationParser,
+};
 
-    let mut sum = 0;
-    for line in stdin.lock().lines() {
-        sum += parse_calibration_line(&line.unwrap())?;
+#[cfg(feature = "fast-alloc")]
+#[global_allocator]
+static ALLOC: aoc_utils::alloc::MiMalloc = aoc_utils::alloc::MiMalloc;
+
+#[derive(Parser)]
+struct Args {
+    #[command(flatten)]
+    input: InputArg,
+    /// Scan stdin in place as bytes too, like any other input, mapping it
+    /// when it's redirected from a file and otherwise reading it whole,
+    /// rather than reading it line by line. Much faster for large inputs.
+    #[arg(long)]
+    mmap: bool,
+    /// Also recognize digits spelled out as words, as in part 2.
+    #[arg(long)]
+    words: bool,
+    /// Read the words for digits from this file instead of using English.
+    /// Each line is a word and its digit, like "neuf 9". Implies --words.
+    #[arg(long, value_name = "FILE")]
+    dictionary: Option<PathBuf>,
+    /// Skip lines with no digits, reporting them at the end, rather than
+    /// failing. The only lines day 1 can't parse, so also --lenient.
+    #[arg(long, visible_alias = "lenient")]
+    skip_invalid: bool,
+    /// Sum the input in chunks on all cores.
+    #[arg(long, requires = "input")]
+    parallel: bool,
+    /// Print each line with its first and last digits highlighted, and its
+    /// calibration value.
+    #[arg(long, conflicts_with = "parallel")]
+    debug: bool,
+    /// Check the whole input, reporting every line with no digits, instead
+    /// of solving.
+    #[arg(long, conflicts_with_all = ["parallel", "debug"])]
+    validate_only: bool,
+    /// Fail on input that isn't UTF-8, giving the offset of the first bad
+    /// byte, instead of replacing the bad bytes.
+    #[arg(long)]
+    strict_utf8: bool,
+    /// Print only the answer, for scripts.
+    #[arg(short, long, conflicts_with = "format")]
+    quiet: bool,
+    /// How to print the answer.
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Print how long reading and solving took.
+    #[arg(long)]
+    time: bool,
+    /// When to color the output.
+    #[arg(long, value_enum, default_value_t, value_name = "WHEN")]
+    color: ColorChoice,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let output = Output::new(args.quiet).show_time(args.time).color(args.color).format(args.format);
+    output.exit(run(&args, output))
+}
+
+fn run(args: &Args, output: Output) -> Result<()> {
+    let parser = match &args.dictionary {
+        Some(path) => {
+            let dictionary = fs::read_to_string(path).with_context(|| format!("Error reading '{}'", path.display()))?;
+            CalibrationParser::from_dictionary(&dictionary).with_context(|| format!("Error in '{}'", path.display()))?
+        }
+        None if args.words => CalibrationParser::english(),
+        None => CalibrationParser::numerals(),
+    };
+
+    let mut timings = Timings::new();
+    let source = &args.input.source;
+    if args.validate_only {
+        return validate(source.open()?, &parser).print();
+    }
+
+    if args.debug {
+        let input = timings.time("read", || source.read_to_string(args.strict_utf8))?;
+        let color = output.stdout_color();
+        for (i, line) in input.lines().enumerate() {
+            output.detail(debug_line(i + 1, line, &parser, color));
+        }
+        let calibration =
+            timings.time("solve", || sum_calibration_values(input.as_bytes(), &parser, args.skip_invalid))?;
+        output.answer("Calibration value", calibration.sum);
+        output.timings(&timings);
+        return Ok(());
     }
 
-    println!("Calibration value: {sum}");
+    let calibration = if *source == InputSource::Stdin && !args.parallel && !args.mmap {
+        let lines = source.lines()?.strict_utf8(args.strict_utf8);
+        timings.time("read and solve", || sum_calibration_lines(lines, &parser, args.skip_invalid))?
+    } else {
+        let start = Instant::now();
+        let input = source.map()?;
+        if args.strict_utf8 {
+            check_utf8(&input)?;
+        }
+        timings.record("read", start.elapsed());
+        timings.time("solve", || {
+            if args.parallel {
+                sum_calibration_values_parallel(&input, &parser, args.skip_invalid)
+            } else {
+                sum_calibration_values(&input, &parser, args.skip_invalid)
+            }
+        })?
+    };
+
+    output.answer("Calibration value", calibration.sum);
+    output.timings(&timings);
+    if !calibration.skipped.is_empty() {
+        eprintln!("Skipped {} line(s) with no digits: {}", calibration.skipped.len(), line_list(&calibration.skipped));
+    }
     Ok(())
 }
 
-/// Parse the "calibration value" out of a line. The calibration value is a two
-/// digit number consisting of the first and last digits in the line.
-fn parse_calibration_line(line: &str) -> Result<u32> {
-    let first_digit = find_first_digit(line).ok_or(anyhow!("Line '{line}' contains no digits"))?;
-    let last_digit = find_last_digit(line).unwrap();
+/// Show a line with its first digit in green and its last in cyan, or yellow
+/// where they overlap, followed by the digits and the calibration value.
+fn debug_line(number: usize, line: &str, parser: &CalibrationParser, color: bool) -> String {
+    let (Some(first), Some(last)) = (parser.first_match(line.as_bytes()), parser.last_match(line.as_bytes())) else {
+        return format!("{number:>4}: {line}  no digits");
+    };
+
+    let mut shown = String::new();
+    let mut bounds = vec![0, first.range.start, first.range.end, last.range.start, last.range.end, line.len()];
+    bounds.sort();
+    bounds.dedup();
+    for segment in bounds.windows(2) {
+        let text = &line[segment[0]..segment[1]];
+        let in_first = first.range.contains(&segment[0]);
+        let in_last = last.range.contains(&segment[0]);
+        let style = match (color, in_first, in_last) {
+            (true, true, true) => AnsiColor::Yellow.on_default().bold(),
+            (true, true, false) => AnsiColor::Green.on_default().bold(),
+            (true, false, true) => AnsiColor::Cyan.on_default().bold(),
+            _ => Style::new(),
+        };
+        shown.push_str(&format!("{style}{text}{style:#}"));
+    }
 
-    Ok(first_digit * 10 + last_digit)
+    format!(
+        "{number:>4}: {shown}  {}, {} => {}",
+        &line[first.range],
+        &line[last.range],
+        first.value * 10 + last.value,
+    )
 }
 
-/// Find the first digit in the line.
-fn find_first_digit(line: &str) -> Option<u32> {
-    line
-        .chars()
-        .find(|c| c.is_ascii_digit())
-        .map(|c| c.to_digit(10).unwrap())
+/// List line numbers, eliding all but the first few.
+fn line_list(lines: &[usize]) -> String {
+    const SHOWN: usize = 10;
+    let mut list: Vec<String> = lines.iter().take(SHOWN).map(|line| line.to_string()).collect();
+    if lines.len() > SHOWN {
+        list.push("...".to_string());
+    }
+    list.join(", ")
 }
 
-/// Find the last digit in the line.
-fn find_last_digit(line: &str) -> Option<u32> {
-    line
-        .chars()
-        .rev()
-        .find(|c| c.is_ascii_digit())
-        .map(|c| c.to_digit(10).unwrap())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_line() {
+        let parser = CalibrationParser::english();
+        assert_eq!(debug_line(3, "xtwone3four", &parser, false), "   3: xtwone3four  two, four => 24");
+        assert_eq!(debug_line(4, "abc", &parser, false), "   4: abc  no digits");
+        assert_eq!(
+            debug_line(5, "xoneight", &parser, true),
+            "   5: x\u{1b}[1m\u{1b}[32mon\u{1b}[0m\u{1b}[1m\u{1b}[33me\u{1b}[0m\u{1b}[1m\u{1b}[36might\u{1b}[0m  one, eight => 18",
+        );
+        assert_eq!(debug_line(6, "7", &CalibrationParser::numerals(), false), "   6: 7  7, 7 => 77");
+    }
 }
@@ -0,0 +1,83 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+use std::io::BufRead;
+
+use aoc_utils::hash::FxBuildHasher;
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::lines::lines;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day04::generate;
+use day04::scoring::Points;
+use day04::{process_card, process_card_hashset, total_score, total_score_parallel, Card};
+
+/// Cards shaped like the puzzle input: 10 winning numbers and 25 you have.
+fn synthetic_cards(count: u32) -> Vec<String> {
+    generate::cards(12345, count, 10, 25).lines().map(String::from).collect()
+}
+
+fn score_cards(c: &mut Criterion) {
+    let cards = synthetic_cards(10_000);
+    let mut group = c.benchmark_group("score_10k_cards");
+    group.bench_function("hashset", |b| {
+        b.iter(|| cards.iter().map(|card| process_card_hashset(black_box(card)).unwrap()).sum::<u32>())
+    });
+    group.bench_function("bitmask", |b| {
+        b.iter(|| cards.iter().map(|card| process_card(black_box(card)).unwrap()).sum::<u32>())
+    });
+    group.finish();
+}
+
+/// Count the numbers you have that win, with a set of the winning numbers
+/// built with the hasher `S`.
+fn count_matches<S: BuildHasher + Default>(cards: &[Card]) -> usize {
+    cards
+        .iter()
+        .map(|card| {
+            let winning: HashSet<u32, S> = card.winning.iter().copied().collect();
+            card.have.iter().filter(|number| winning.contains(number)).count()
+        })
+        .sum()
+}
+
+/// The hashset strategy's sets with the standard SipHash and with FxHash.
+fn hashers(c: &mut Criterion) {
+    let cards: Vec<Card> = synthetic_cards(10_000).iter().map(|card| card.parse().unwrap()).collect();
+    let mut group = c.benchmark_group("hash_10k_cards");
+    group.bench_function("siphash", |b| b.iter(|| count_matches::<RandomState>(black_box(&cards))));
+    group.bench_function("fxhash", |b| b.iter(|| count_matches::<FxBuildHasher>(black_box(&cards))));
+    group.finish();
+}
+
+/// Parsing cards into their lists of numbers.
+fn parse_cards(c: &mut Criterion) {
+    let cards = synthetic_cards(10_000);
+    c.bench_function("parse_10k_cards", |b| {
+        b.iter(|| cards.iter().map(|card| black_box(card).parse::<Card>().unwrap().have.len()).sum::<usize>())
+    });
+}
+
+fn read_cards(c: &mut Criterion) {
+    let input = synthetic_cards(10_000).join("\n");
+    let mut group = c.benchmark_group("read_10k_cards");
+    group.bench_function("lines", |b| {
+        b.iter(|| {
+            black_box(input.as_bytes())
+                .lines()
+                .map(|line| process_card(&line.unwrap()).unwrap())
+                .sum::<u32>()
+        })
+    });
+    group.bench_function("reused_buffer", |b| {
+        b.iter(|| {
+            total_score(lines(black_box(input.as_bytes())), &mut Points::default(), &mut LinePolicy::strict()).unwrap()
+        })
+    });
+    group.bench_function("parallel", |b| b.iter(|| total_score_parallel(black_box(&input)).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, score_cards, hashers, parse_cards, read_cards);
+criterion_main!(benches);
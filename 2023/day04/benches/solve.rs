@@ -0,0 +1,22 @@
+//! Parsing and solving each part on the real input and generated ones.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day04::{generate, Day04};
+
+/// The real input, then generated ones: piles of more and more cards, with
+/// fewer numbers than the puzzle's so part 2's total doesn't overflow.
+fn inputs() -> Vec<(String, String)> {
+    let mut inputs = vec![("input".to_string(), include_str!("../input.txt").to_string())];
+    for cards in [1_000, 10_000, 100_000] {
+        inputs.push((format!("generated/{cards}"), generate::cards(12345, cards, 5, 8)));
+    }
+    inputs
+}
+
+fn solve(c: &mut Criterion) {
+    aoc_utils::benches::solver(c, &Day04, &inputs());
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
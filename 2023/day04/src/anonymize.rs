@@ -0,0 +1,64 @@
+//! Scramble a pile of scratchcards so it can be shared without sharing the
+//! puzzle input, keeping both parts' answers.
+
+use std::collections::BTreeSet;
+
+use aoc_utils::error::{AocError, Result};
+use aoc_utils::hash::HashMap;
+use aoc_utils::lines::strip_bom;
+use aoc_utils::random::Rng;
+
+use crate::Card;
+
+/// Swap the numbers on the cards for others, the same swap on every card so
+/// the matches stay the same, and shuffle each card's lists. The cards keep
+/// their IDs, since part 2 needs them in order.
+pub fn anonymize(input: &str, seed: u64) -> Result<String> {
+    let mut rng = Rng::new(seed);
+    let cards: Vec<Card> = strip_bom(input)
+        .lines()
+        .enumerate()
+        .map(|(i, line)| line.parse().map_err(|error: AocError| error.at_line(i + 1)))
+        .collect::<Result<_>>()?;
+
+    let numbers: BTreeSet<u32> = cards.iter().flat_map(|card| card.winning.iter().chain(&card.have)).copied().collect();
+    let numbers: Vec<u32> = numbers.into_iter().collect();
+    let mut swapped = numbers.clone();
+    rng.shuffle(&mut swapped);
+    let swap: HashMap<u32, u32> = numbers.into_iter().zip(swapped).collect();
+
+    let id_width = cards.iter().map(|card| card.id.to_string().len()).max().unwrap_or(1);
+    let width = swap.keys().map(|number| number.to_string().len()).max().unwrap_or(1);
+    let mut list = |numbers: &[u32]| {
+        let mut numbers: Vec<String> = numbers.iter().map(|number| format!("{:>width$}", swap[number])).collect();
+        rng.shuffle(&mut numbers);
+        numbers.join(" ")
+    };
+    Ok(cards
+        .iter()
+        .map(|card| format!("Card {:>id_width$}: {} | {}\n", card.id, list(&card.winning), list(&card.have)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::solver::DynSolver;
+
+    use super::*;
+    use crate::Day04;
+
+    #[test]
+    fn test_anonymize() {
+        let input = include_str!("../fixtures/example.txt");
+        let anonymized = anonymize(input, 1).unwrap();
+        assert_ne!(anonymized, input);
+        assert_eq!(anonymized, anonymize(input, 1).unwrap());
+        assert!(anonymized.starts_with("Card 1: "));
+        assert_eq!(Day04.solve_both(&anonymized).unwrap(), Day04.solve_both(input).unwrap());
+
+        let input = include_str!("../input.txt");
+        assert_eq!(Day04.solve_both(&anonymize(input, 2).unwrap()).unwrap(), Day04.solve_both(input).unwrap());
+        let error = anonymize("Card 1: 1 | x\n", 1).unwrap_err();
+        assert!(error.to_string().starts_with("Error on line 1: Card 1 numbers you have error"), "{error}");
+    }
+}
@@ -0,0 +1,64 @@
+//! Seeded piles of scratchcards of any size, for benchmarks and stress
+//! tests.
+
+use aoc_utils::random::Rng;
+
+/// `count` cards with `winning` winning numbers and `have` numbers you have,
+/// each list distinct and below 100 as in the puzzle input, so at most 99 of
+/// each. The puzzle input has 10 and 25; with that many matches part 2's
+/// total overflows after a few thousand cards, so stress tests use fewer.
+pub fn cards(seed: u64, count: u32, winning: usize, have: usize) -> String {
+    assert!(winning < 100 && have < 100, "A card can't have more than 99 distinct numbers below 100");
+    let mut rng = Rng::new(seed);
+    let width = count.to_string().len();
+    let mut numbers: Vec<u32> = (1..100).collect();
+    let mut pick = |rng: &mut Rng, n: usize| {
+        rng.shuffle(&mut numbers);
+        numbers[..n].iter().map(|number| format!("{number:>2}")).collect::<Vec<_>>().join(" ")
+    };
+    let mut cards = String::new();
+    for id in 1..=count {
+        let winning = pick(&mut rng, winning);
+        let have = pick(&mut rng, have);
+        cards.push_str(&format!("Card {id:>width$}: {winning} | {have}\n"));
+    }
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::cancel::Cancel;
+    use aoc_utils::lenient::LinePolicy;
+    use aoc_utils::solver::{Answer, Solver};
+
+    use super::*;
+    use crate::{explain_cards, process_card_hashset, total_score_parallel, Card, Day04};
+
+    #[test]
+    fn test_cards() {
+        let text = cards(1, 3, 10, 25);
+        assert_eq!(text, cards(1, 3, 10, 25));
+        assert_ne!(text, cards(2, 3, 10, 25));
+        for (i, line) in text.lines().enumerate() {
+            let card: Card = line.parse().unwrap();
+            assert_eq!(card.id, i as u32 + 1);
+            assert_eq!((card.winning.len(), card.have.len()), (10, 25));
+        }
+        assert!(cards(1, 1000, 10, 25).starts_with("Card    1: "));
+    }
+
+    /// A large pile totals the same whichever way it's scored.
+    #[test]
+    fn test_many_cards() {
+        let text = cards(7, 50_000, 5, 8);
+        let parsed = Day04.parse(&text).unwrap();
+        let Answer::Unsigned(points) = Day04.part1(&parsed, &Cancel::new()).unwrap() else { panic!() };
+        assert_eq!(total_score_parallel(&text).unwrap(), points);
+        let hashset: u32 = text.lines().map(|line| process_card_hashset(line).unwrap()).sum();
+        assert_eq!(u64::from(hashset), points);
+
+        let Answer::Unsigned(copies) = Day04.part2(&parsed, &Cancel::new()).unwrap() else { panic!() };
+        let explained = explain_cards(text.lines().map(|line| Ok(line.to_string())), &mut LinePolicy::strict()).unwrap();
+        assert_eq!(explained.iter().map(|card| card.copies).sum::<u64>(), copies);
+    }
+}
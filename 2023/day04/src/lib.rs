@@ -0,0 +1,515 @@
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use aoc_utils::cancel::Cancel;
+use aoc_utils::error::{AocError, Context, Result};
+use aoc_utils::hash::HashSet;
+use aoc_utils::lenient::{self, LinePolicy, Validation};
+use aoc_utils::lines::{normalize_line, strip_bom, ReadLine};
+use aoc_utils::small::SmallVec;
+use aoc_utils::solver::{Answer, Solver};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub mod anonymize;
+pub mod generate;
+pub mod scoring;
+
+use scoring::{Copies, Points, Scoring};
+
+/// Process the card string and return its score. The winning numbers are
+/// kept as a bitmask, so they must be below 128.
+pub fn process_card(line: &str) -> Result<u32> {
+    let mut matches = 0;
+    let id = for_each_match(line, |_| matches += 1)?;
+    score(id, matches)
+}
+
+/// Call `f` with each number you have that is a winning number, and return
+/// the card ID.
+fn for_each_match(line: &str, mut f: impl FnMut(u32)) -> Result<u32> {
+    let (id, winning_str, have_str) = split_card(line)?;
+
+    let winning = parse_number_mask(winning_str)
+        .with_context(|| format!("Card {id} winning numbers error"))?;
+
+    for number in parse_numbers(have_str) {
+        let number = number.with_context(|| format!("Card {id} numbers you have error"))?;
+        if number < 128 && winning & (1 << number) != 0 {
+            f(number);
+        }
+    }
+    Ok(id)
+}
+
+/// Check that every card parses and that the cards are in order, collecting
+/// all the errors.
+pub fn validate(reader: impl BufRead) -> Validation {
+    lenient::validate(|policy| total_score(aoc_utils::lines::lines(reader), &mut Copies::default(), policy).map(drop))
+}
+
+/// Process the card string and return the card ID and the number of
+/// matches.
+pub fn card_matches(line: &str) -> Result<(u32, usize)> {
+    let mut matches = 0;
+    let id = for_each_match(line, |_| matches += 1)?;
+    Ok((id, matches))
+}
+
+/// Total the cards in `lines` with `scoring`, a line at a time, read into
+/// one buffer reused for every line or borrowed from an input in memory.
+/// Cards that don't parse are handled by `policy`; a card that can't be
+/// scored is always an error.
+pub fn total_score(mut lines: impl ReadLine, scoring: &mut dyn Scoring, policy: &mut LinePolicy) -> Result<u64> {
+    while let Some(line) = lines.read_line() {
+        let line = line?;
+        let line_number = policy.next_line();
+        if let Some((id, matches)) = policy.check(card_matches(line))? {
+            scoring.add_card(id, matches).map_err(|error| error.at_line(line_number))?;
+        }
+    }
+    Ok(scoring.total())
+}
+
+/// Sum the part 1 scores of the cards in `input`, scoring them in parallel.
+/// If any cards are invalid, the error is for the first of them, whichever
+/// thread finds it.
+pub fn total_score_parallel(input: &str) -> Result<u64> {
+    let lines: Vec<&str> = strip_bom(input).lines().collect();
+    lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, line)| process_card(line).map(u64::from).map_err(|error| (i, error)))
+        .reduce(
+            || Ok(0),
+            |a, b| match (a, b) {
+                (Ok(a), Ok(b)) => Ok(a + b),
+                (Err(a), Err(b)) => Err(if a.0 < b.0 { a } else { b }),
+                (Err(error), Ok(_)) | (Ok(_), Err(error)) => Err(error),
+            },
+        )
+        .map_err(|(i, error)| error.at_line(i + 1))
+}
+
+/// Day 4's solution: part 1 totals the cards' points, part 2 their copies.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Day04;
+
+impl Solver for Day04 {
+    /// Each card's ID and number of matches.
+    type Parsed = Vec<(u32, usize)>;
+
+    fn parse(&self, input: &str) -> Result<Vec<(u32, usize)>> {
+        strip_bom(input)
+            .lines()
+            .enumerate()
+            .map(|(i, line)| card_matches(normalize_line(line, false)).map_err(|error| error.at_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(&self, cards: &Vec<(u32, usize)>, cancel: &Cancel) -> Result<Answer> {
+        tally(cards, &mut Points::default(), cancel)
+    }
+
+    fn part2(&self, cards: &Vec<(u32, usize)>, cancel: &Cancel) -> Result<Answer> {
+        tally(cards, &mut Copies::default(), cancel)
+    }
+}
+
+fn tally(cards: &[(u32, usize)], scoring: &mut dyn Scoring, cancel: &Cancel) -> Result<Answer> {
+    for (i, &(id, matches)) in cards.iter().enumerate() {
+        cancel.check()?;
+        scoring.add_card(id, matches).map_err(|error| error.at_line(i + 1))?;
+    }
+    Ok(scoring.total().into())
+}
+
+/// A card's row in the `--explain` table.
+#[derive(Debug, PartialEq)]
+pub struct Explanation {
+    pub id: u32,
+    /// The numbers you have that are winning numbers, in order.
+    pub matched: Vec<u32>,
+    /// The card's points in part 1.
+    pub score: u32,
+    /// How many of the card you end up with in part 2.
+    pub copies: u64,
+}
+
+impl Explanation {
+    /// The IDs of the cards each copy of this card wins a copy of.
+    pub fn wins(&self, last_id: u32) -> RangeInclusive<u32> {
+        match self.id.checked_add(1) {
+            Some(next) => next..=self.id.saturating_add(self.matched.len() as u32).min(last_id),
+            // No card comes after the largest ID there is.
+            None => RangeInclusive::new(1, 0),
+        }
+    }
+}
+
+/// Work out each card's matches, score, and copies. Cards that don't parse
+/// are handled by `policy`. The copies are counted as part 2 counts them, so
+/// the cards must be in order as there.
+pub fn explain_cards(
+    lines: impl Iterator<Item = Result<impl AsRef<str>>>,
+    policy: &mut LinePolicy,
+) -> Result<Vec<Explanation>> {
+    let mut cards = Vec::new();
+    let mut copies = Copies::default();
+    for line in lines {
+        let line = line?;
+        let line_number = policy.next_line();
+        let mut matched = Vec::new();
+        let Some(id) = policy.check(for_each_match(line.as_ref(), |number| matched.push(number)))? else {
+            continue;
+        };
+        let score = score(id, matched.len() as u32)?;
+        let copies = copies.add(id, matched.len()).map_err(|error| error.at_line(line_number))?;
+        cards.push(Explanation { id, matched, score, copies });
+    }
+    Ok(cards)
+}
+
+/// Process the card string and return its score, using a `HashSet` of the
+/// winning numbers. This is the reference for `process_card`, kept for tests
+/// and benchmarks.
+pub fn process_card_hashset(line: &str) -> Result<u32> {
+    let (id, winning_str, have_str) = split_card(line)?;
+
+    let winning = parse_number_set(winning_str)
+        .with_context(|| format!("Card {id} winning numbers error"))?;
+    let have = parse_number_list(have_str)
+        .with_context(|| format!("Card {id} numbers you have error"))?;
+
+    let mut matches = 0;
+    for number in have {
+        if winning.contains(&number) {
+            matches += 1;
+        }
+    }
+    score(id, matches)
+}
+
+/// A card's list of numbers, inline up to the 25 a puzzle input card has
+/// and then some.
+pub type Numbers = SmallVec<[u32; 32]>;
+
+/// A scratchcard: its ID, the winning numbers, and the numbers you have.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Card {
+    pub id: u32,
+    pub winning: Numbers,
+    pub have: Numbers,
+}
+
+impl Card {
+    /// The number of numbers you have that are winning numbers.
+    pub fn matches(&self) -> usize {
+        self.have.iter().filter(|number| self.winning.contains(number)).count()
+    }
+
+    /// The card's points in part 1.
+    pub fn score(&self) -> Result<u32> {
+        score(self.id, self.matches() as u32)
+    }
+}
+
+impl FromStr for Card {
+    type Err = AocError;
+
+    /// Parse a card of the form "Card <id>: <winning> | <have>".
+    fn from_str(line: &str) -> Result<Card> {
+        let (id, winning_str, have_str) = split_card(line)?;
+        let winning = parse_number_list(winning_str)
+            .with_context(|| format!("Card {id} winning numbers error"))?;
+        let have = parse_number_list(have_str)
+            .with_context(|| format!("Card {id} numbers you have error"))?;
+        Ok(Card { id, winning, have })
+    }
+}
+
+/// The score for a card with the number of matches: 1 for the first match,
+/// doubled for each after it.
+fn score(id: u32, matches: u32) -> Result<u32> {
+    if matches == 0 {
+        Ok(0)
+    } else {
+        1u32.checked_shl(matches - 1)
+            .ok_or_else(|| AocError::overflow(format!("Card {id} has too many matches ({matches}) to score")))
+    }
+}
+
+/// Split a line of the form "Card <id>: <winning> | <have>" into the card ID
+/// and the two lists of numbers.
+fn split_card(line: &str) -> Result<(u32, &str, &str)> {
+    let (id, numbers) = parse_card_header(line)?;
+    let (winning_str, have_str) = numbers
+        .split_once('|')
+        .ok_or_else(|| AocError::parse(format!("Card {id} is missing a '|'")))?;
+    Ok((id, winning_str, have_str))
+}
+
+/// Split a line of the form "Card <id>: <numbers>" into the card ID and the
+/// numbers.
+fn parse_card_header(line: &str) -> Result<(u32, &str)> {
+    let rest = line
+        .strip_prefix("Card")
+        .ok_or_else(|| AocError::parse(format!("Line '{line}' must start with 'Card'")))?;
+    let (id, numbers) = rest
+        .split_once(':')
+        .ok_or_else(|| AocError::parse(format!("Line '{line}' is missing the ':' after the card ID")))?;
+    let id = id.trim();
+    let id = id
+        .parse()
+        .with_context(|| format!("Line '{line}' card ID '{id}' must be a number"))?;
+    Ok((id, numbers))
+}
+
+fn parse_number(number: &str) -> Result<u32> {
+    number
+        .parse()
+        .with_context(|| format!("invalid number '{number}'"))
+}
+
+/// Parse the whitespace separated numbers in a string as they are iterated
+/// over, without allocating.
+fn parse_numbers(numbers: &str) -> impl Iterator<Item = Result<u32>> + '_ {
+    numbers.split_ascii_whitespace().map(parse_number)
+}
+
+/// Parse a string of whitespace separated numbers below 128 into a bitmask
+/// with a bit set for each number.
+fn parse_number_mask(numbers: &str) -> Result<u128> {
+    let mut mask = 0;
+    for number in parse_numbers(numbers) {
+        let number = number?;
+        if number >= 128 {
+            return Err(AocError::parse(format!("number {number} must be below 128")));
+        }
+        mask |= 1 << number;
+    }
+
+    Ok(mask)
+}
+
+fn parse_number_list(numbers: &str) -> Result<Numbers> {
+    parse_numbers(numbers).collect()
+}
+
+fn parse_number_set(numbers: &str) -> Result<HashSet<u32>> {
+    parse_number_list(numbers).map(HashSet::from_iter)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc_utils::lines::lines;
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::scoring::{Copies, Points};
+
+    #[test]
+    fn test_cards() {
+        assert_eq!(process_card("Card   1: 1 2 3 | 4 5 6").unwrap(), 0);
+        assert_eq!(process_card("Card   2: 1 2 3 | 1 5 6").unwrap(), 1);
+        assert_eq!(process_card("Card   3: 1 2 3 | 1 1 1").unwrap(), 4);
+        assert_eq!(process_card("Card   4: 1 1 1 | 1 2 3").unwrap(), 1);
+        assert_eq!(process_card("Card 123: 1 2 | 1 2").unwrap(), 2);
+        assert_eq!(process_card("Card 5:1 2|2").unwrap(), 1);
+        assert_eq!(card_matches("Card   6: 0 127 | 127 128 0").unwrap(), (6, 2));
+    }
+
+    #[test]
+    fn test_parse_card_header() {
+        assert_eq!(parse_card_header("Card   1: 1 2 | 3").unwrap(), (1, " 1 2 | 3"));
+        assert_eq!(parse_card_header("Card 1234: 5 | 6").unwrap(), (1234, " 5 | 6"));
+        assert!(parse_card_header("Card: 1 | 2").is_err());
+        assert!(parse_card_header("Card 1 1 | 2").is_err());
+        assert!(parse_card_header("Cärd 1: 1 | 2").is_err());
+        assert!(parse_card_header("Card").is_err());
+        let error = process_card("Card 7: 1 x | 2").unwrap_err();
+        assert_eq!(error.to_string(), "Card 7 winning numbers error: invalid number 'x': invalid digit found in string");
+    }
+
+    #[test]
+    fn test_parse_number_mask() {
+        assert_eq!(parse_number_mask(" 0 3  127 3 ").unwrap(), 1 | 1 << 3 | 1 << 127);
+        assert_eq!(parse_number_mask("").unwrap(), 0);
+        assert!(parse_number_mask("128").is_err());
+        assert!(process_card("Card 1: 200 | 200").is_err());
+        assert_eq!(process_card("Card 1: 1 | 200 1").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_parse_numbers() {
+        let numbers: Vec<u32> = parse_numbers("  1 22\t333  4294967295\n").map(Result::unwrap).collect();
+        assert_eq!(numbers, [1, 22, 333, u32::MAX]);
+        assert_eq!(parse_numbers("   ").count(), 0);
+        assert_eq!(parse_numbers("1 x2 3").nth(1).unwrap().unwrap_err().to_string(), "invalid number 'x2': invalid digit found in string");
+        assert!(parse_numbers("-1").next().unwrap().is_err());
+        assert!(parse_numbers("4294967296").next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_total_score() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\r\nCard 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n";
+        assert_eq!(total_score(lines(input.as_bytes()), &mut Points::default(), &mut LinePolicy::strict()).unwrap(), 10);
+        assert_eq!(total_score(lines(input.as_bytes()), &mut Copies::default(), &mut LinePolicy::strict()).unwrap(), 3);
+        assert_eq!(total_score(lines("".as_bytes()), &mut Points::default(), &mut LinePolicy::strict()).unwrap(), 0);
+        let error = total_score(lines("Card 1: 1 | 1\nCard 2 1 | 1\n".as_bytes()), &mut Points::default(), &mut LinePolicy::strict()).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2: Line 'Card 2 1 | 1' is missing the ':' after the card ID");
+    }
+
+    #[test]
+    fn test_solver() {
+        let input = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53\nCard 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\n";
+        let cards = Day04.parse(input).unwrap();
+        assert_eq!(cards, [(1, 4), (2, 2)]);
+        assert_eq!(Day04.part1(&cards, &Cancel::new()).unwrap(), Answer::Unsigned(10));
+        assert_eq!(Day04.part2(&cards, &Cancel::new()).unwrap(), Answer::Unsigned(3));
+        let error = Day04.part2(&vec![(1, 0), (3, 0)], &Cancel::new()).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2: Card 3 is out of order, expected card 2");
+        assert!(matches!(Day04.part1(&cards, &Cancel::with_timeout(Duration::ZERO)), Err(AocError::Cancelled)));
+    }
+
+    aoc_utils::aoc_example_tests!(Day04, part1 = 13, part2 = 30, input = "fixtures/example.txt");
+
+    #[test]
+    fn test_crlf_input() {
+        let input = "\u{feff}Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53 \r\nCard 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19\t\r\n";
+        assert_eq!(total_score(lines(input.as_bytes()), &mut Points::default(), &mut LinePolicy::strict()).unwrap(), 10);
+        assert_eq!(total_score_parallel(input).unwrap(), 10);
+        let cards = explain_cards(aoc_utils::lines::lines(input.as_bytes()), &mut LinePolicy::strict()).unwrap();
+        assert_eq!(cards.iter().map(|card| card.copies).sum::<u64>(), 3);
+    }
+
+    #[test]
+    fn test_total_score_parallel() {
+        let mut input = String::new();
+        for id in 1..=1000 {
+            input.push_str(&format!("Card {id}: 1 2 3 | {} 2\n", id % 4));
+        }
+        assert_eq!(
+            total_score_parallel(&input).unwrap(),
+            total_score(lines(input.as_bytes()), &mut Points::default(), &mut LinePolicy::strict()).unwrap()
+        );
+        assert_eq!(total_score_parallel("").unwrap(), 0);
+
+        // Always the first bad line, however the work is split.
+        let input = input.replace("Card 500:", "Card 500").replace("Card 900:", "Card 900");
+        for _ in 0..10 {
+            let error = total_score_parallel(&input).unwrap_err();
+            assert_eq!(error.to_string(), "Error on line 500: Line 'Card 500 1 2 3 | 0 2' is missing the ':' after the card ID");
+        }
+    }
+
+    #[test]
+    fn test_matches_hashset() {
+        for line in [
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+            "Card 4: 1 1 1 | 1 1 1 1",
+        ] {
+            assert_eq!(process_card(line).unwrap(), process_card_hashset(line).unwrap());
+        }
+        assert!(process_card(&format!("Card 1: 1 | {}", "1 ".repeat(33))).is_err());
+    }
+
+    #[test]
+    fn test_card() {
+        let card: Card = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".parse().unwrap();
+        assert_eq!(card.id, 1);
+        assert_eq!(card.winning[..], [41, 48, 83, 86, 17]);
+        assert_eq!(card.have[..], [83, 86, 6, 31, 17, 9, 48, 53]);
+        assert_eq!(card.matches(), 4);
+        assert_eq!(card.score().unwrap(), 8);
+
+        let card: Card = "Card 2: 1 2 3 | 1 1 200".parse().unwrap();
+        assert_eq!((card.matches(), card.score().unwrap()), (2, 2));
+        let card: Card = "Card 3: 1 | 2".parse().unwrap();
+        assert_eq!((card.matches(), card.score().unwrap()), (0, 0));
+
+        let error = "Card 7: 1 x | 2".parse::<Card>().unwrap_err();
+        assert_eq!(error.to_string(), "Card 7 winning numbers error: invalid number 'x': invalid digit found in string");
+        assert!("Card 7: 1 2".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn test_card_serde() {
+        let card: Card = "Card 5: 1 2 | 3 2".parse().unwrap();
+        let json = serde_json::to_string(&card).unwrap();
+        assert_eq!(json, r#"{"id":5,"winning":[1,2],"have":[3,2]}"#);
+        assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), card);
+    }
+
+    #[test]
+    fn test_explain_cards() {
+        let lines = [
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ];
+        let cards = explain_cards(lines.iter().map(|line| Ok(line.to_string())), &mut LinePolicy::strict()).unwrap();
+        assert_eq!(cards[0], Explanation { id: 1, matched: vec![83, 86, 17, 48], score: 8, copies: 1 });
+        assert_eq!(cards.iter().map(|card| card.score).collect::<Vec<_>>(), [8, 2, 2, 1, 0, 0]);
+        assert_eq!(cards.iter().map(|card| card.copies).collect::<Vec<_>>(), [1, 2, 4, 8, 14, 1]);
+        assert_eq!(cards[0].wins(6), 2..=5);
+        assert_eq!(cards[3].wins(6), 5..=5);
+        assert!(cards[4].wins(6).is_empty());
+
+        let cards = explain_cards(["Card 1: 1 2 | 1 2"].iter().map(|line| Ok(line.to_string())), &mut LinePolicy::strict()).unwrap();
+        assert!(cards[0].wins(1).is_empty());
+
+        // Out of order, as part 2 rejects it.
+        let lines = ["Card 1: 1 2 | 1 2", "Card 3: 1 | 2"];
+        let error = explain_cards(lines.iter().map(|line| Ok(line.to_string())), &mut LinePolicy::strict()).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2: Card 3 is out of order, expected card 2");
+
+        let card = Explanation { id: u32::MAX, matched: vec![1], score: 1, copies: 1 };
+        assert!(card.wins(u32::MAX).is_empty());
+        let card = Explanation { id: u32::MAX - 1, matched: vec![1, 2], score: 2, copies: 1 };
+        assert_eq!(card.wins(u32::MAX), u32::MAX..=u32::MAX);
+    }
+
+    /// A card with numbers low enough for `process_card`'s bitmask.
+    fn card() -> impl Strategy<Value = Card> {
+        (0..1000u32, prop::collection::vec(0..100u32, 0..10), prop::collection::vec(0..100u32, 0..25))
+            .prop_map(|(id, winning, have)| Card { id, winning: winning.into(), have: have.into() })
+    }
+
+    fn format_card(card: &Card) -> String {
+        let numbers = |numbers: &[u32]| numbers.iter().map(|n| format!("{n:>2}")).collect::<Vec<_>>().join(" ");
+        format!("Card {:>3}: {} | {}", card.id, numbers(&card.winning), numbers(&card.have))
+    }
+
+    proptest! {
+        #[test]
+        fn test_process_card_any_line(line in "\\PC{0,60}") {
+            let _ = process_card(&line);
+            let _ = line.parse::<Card>();
+        }
+
+        #[test]
+        fn test_process_card_near_valid(line in "Card {0,2}[0-9]{0,3}:?( {1,2}[0-9]{1,3}){0,5} ?\\|?( {1,2}[0-9]{1,3}){0,8}") {
+            let card = line.parse::<Card>();
+            prop_assert_eq!(process_card(&line).is_ok(), card.is_ok_and(|card| card.winning.iter().all(|&n| n < 128)));
+        }
+
+        #[test]
+        fn test_process_card_valid(card in card()) {
+            let line = format_card(&card);
+            prop_assert_eq!(line.parse::<Card>().unwrap(), card.clone());
+            prop_assert_eq!(process_card(&line).unwrap(), card.score().unwrap());
+            prop_assert_eq!(card_matches(&line).unwrap(), (card.id, card.matches()));
+            let json = serde_json::to_string(&card).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Card>(&json).unwrap(), card);
+        }
+    }
+}
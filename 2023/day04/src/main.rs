@@ -1,74 +1,164 @@
-use std::collections::HashSet;
-use std::io::{self, BufRead};
+use std::process::ExitCode;
+use std::time::Instant;
 
-use anyhow::{anyhow, Result, Context};
+use anyhow::{anyhow, Result};
+use aoc_utils::arena::Bump;
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::lines::{lines, str_lines};
+use aoc_utils::output::{ColorChoice, Format, Output, Timings};
+use aoc_utils::source::{InputArg, InputSource};
+use clap::Parser;
 
-fn main() -> Result<()> {
-    let stdin = io::stdin();
+use day04::scoring::{Copies, Points, Scoring};
+use day04::{explain_cards, total_score, total_score_parallel, validate, Explanation};
 
-    let mut score: u32 = 0;
-    for line in stdin.lock().lines() {
-        score += process_card(&line.unwrap())?;
-    }
-    println!("Total score: {score}");
+#[cfg(feature = "fast-alloc")]
+#[global_allocator]
+static ALLOC: aoc_utils::alloc::MiMalloc = aoc_utils::alloc::MiMalloc;
 
-    Ok(())
+#[derive(Parser)]
+struct Args {
+    /// Print each card's matched numbers, score, and copies instead of the
+    /// total score.
+    #[arg(long)]
+    explain: bool,
+    /// Score the cards on all cores. This maps or reads the whole input
+    /// first, and only works for part 1.
+    #[arg(long)]
+    parallel: bool,
+    /// Which part's scoring rule to use.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+    /// Skip cards that don't parse, with a warning, instead of failing.
+    #[arg(long, conflicts_with = "parallel")]
+    lenient: bool,
+    /// Check the whole input, reporting every error, instead of solving.
+    #[arg(long)]
+    validate_only: bool,
+    /// Fail on input that isn't UTF-8, giving the offset of the first bad
+    /// byte, instead of replacing the bad bytes.
+    #[arg(long)]
+    strict_utf8: bool,
+    /// Print only the answer, for scripts.
+    #[arg(short, long, conflicts_with = "format")]
+    quiet: bool,
+    /// How to print the answer.
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Print how long reading and scoring the cards took.
+    #[arg(long)]
+    time: bool,
+    /// When to color the output.
+    #[arg(long, value_enum, default_value_t, value_name = "WHEN")]
+    color: ColorChoice,
+    /// Map the input file into memory, or read the whole input, and score
+    /// its lines in place rather than reading them one at a time.
+    #[arg(long)]
+    mmap: bool,
+    #[command(flatten)]
+    input: InputArg,
 }
 
-fn process_card(line: &str) -> Result<u32> {
-    if line.len() < 10 {
-        return Err(anyhow!("Line '{line}' is missing the 'Card   #: ' prefix"));
-    }
-    // Trim off the "Card #: " prefix.
-    let trimmed_line = &line[10..];
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let output = Output::new(args.quiet).show_time(args.time).color(args.color).format(args.format);
+    output.exit(run(&args, output))
+}
 
-    let (winning_str, have_str) = trimmed_line
-        .split_once('|')
-        .ok_or_else(|| anyhow!("Line '{line}' is missing a '|'"))?;
+fn run(args: &Args, output: Output) -> Result<()> {
+    let source = &args.input.source;
+    if args.validate_only {
+        return validate(source.open()?).print();
+    }
+    let mut policy = LinePolicy::new(args.lenient);
+    let mut timings = Timings::new();
 
-    let winning = parse_number_set(winning_str)
-        .with_context(|| format!("Line '{line}' winning numbers error"))?;
-    let have = parse_number_list(have_str)
-        .with_context(|| format!("Line '{line}' numbers you have error"))?;
+    if args.explain {
+        let arena = Bump::new();
+        let cards = if args.mmap {
+            let input = source.map()?;
+            explain_cards(str_lines(&input.text(args.strict_utf8)?), &mut policy)?
+        } else {
+            explain_cards(lines(source.open()?).strict_utf8(args.strict_utf8).in_arena(&arena), &mut policy)?
+        };
+        print!("{}", explain_table(&cards));
+        policy.report();
+        return Ok(());
+    }
 
-    let mut matches: usize = 0;
-    for number in have {
-        if winning.contains(&number) {
-            matches += 1;
+    if args.part == 2 {
+        if args.parallel {
+            return Err(anyhow!("--parallel only works for part 1"));
         }
+        let total_cards =
+            timings.time("read and solve", || score_cards(args, source, &mut Copies::default(), &mut policy))?;
+        output.answer("Number of scratchcards", total_cards);
+        output.timings(&timings);
+        policy.report();
+        return Ok(());
     }
-    if matches == 0 {
-        Ok(0)
+
+    let score = if args.parallel {
+        let start = Instant::now();
+        let input = source.map()?;
+        let cards = input.text(args.strict_utf8)?;
+        timings.record("read", start.elapsed());
+        timings.time("solve", || total_score_parallel(&cards))?
     } else {
-        Ok(1 << (matches - 1))
-    }
+        timings.time("read and solve", || score_cards(args, source, &mut Points::default(), &mut policy))?
+    };
+    output.answer("Total score", score);
+    output.timings(&timings);
+    policy.report();
+
+    Ok(())
 }
 
-fn parse_number_list(numbers: &str) -> Result<Vec<u32>> {
-    let mut vec = Vec::new();
-    for number in numbers.split_ascii_whitespace() {
-        let number = number
-            .parse()
-            .with_context(|| format!("invalid number '{number}"))?;
-        vec.push(number);
+/// Total the cards with `scoring`, from the mapped input with `--mmap` or
+/// else read a line at a time.
+fn score_cards(args: &Args, source: &InputSource, scoring: &mut dyn Scoring, policy: &mut LinePolicy) -> Result<u64> {
+    if args.mmap {
+        let input = source.map()?;
+        Ok(total_score(str_lines(&input.text(args.strict_utf8)?), scoring, policy)?)
+    } else {
+        Ok(total_score(lines(source.open()?).strict_utf8(args.strict_utf8), scoring, policy)?)
     }
-
-    Ok(vec)
 }
 
-fn parse_number_set(numbers: &str) -> Result<HashSet<u32>> {
-    parse_number_list(numbers).map(HashSet::from_iter)
-}
+/// Lay the cards out as a table, one row per card.
+fn explain_table(cards: &[Explanation]) -> String {
+    let last_id = cards.last().map_or(0, |card| card.id);
+    let rows: Vec<[String; 5]> = cards
+        .iter()
+        .map(|card| {
+            let matched: Vec<String> = card.matched.iter().map(|number| number.to_string()).collect();
+            let wins = card.wins(last_id);
+            let wins = if wins.is_empty() {
+                "-".to_string()
+            } else if wins.start() == wins.end() {
+                wins.start().to_string()
+            } else {
+                format!("{}-{}", wins.start(), wins.end())
+            };
+            [card.id.to_string(), matched.join(" "), card.score.to_string(), card.copies.to_string(), wins]
+        })
+        .collect();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    let header = ["Card", "Matched", "Score", "Copies", "Wins"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
 
-    #[test]
-    fn test_cards() {
-        assert_eq!(process_card("Card   1: 1 2 3 | 4 5 6").unwrap(), 0);
-        assert_eq!(process_card("Card   2: 1 2 3 | 1 5 6").unwrap(), 1);
-        assert_eq!(process_card("Card   3: 1 2 3 | 1 1 1").unwrap(), 4);
-        assert_eq!(process_card("Card   4: 1 1 1 | 1 2 3").unwrap(), 1);
+    let mut table = String::new();
+    for row in std::iter::once(header.map(String::from)).chain(rows) {
+        let [id, matched, score, copies, wins] = row;
+        table.push_str(&format!(
+            "{id:>0$}  {matched:<1$}  {score:>2$}  {copies:>3$}  {wins}\n",
+            widths[0], widths[1], widths[2], widths[3],
+        ));
     }
+    table
 }
@@ -0,0 +1,170 @@
+//! The rules for totting up a pile of scratchcards: points in part 1, and
+//! won copies of cards in part 2.
+
+use std::collections::VecDeque;
+
+use aoc_utils::error::{AocError, Result};
+
+use crate::score;
+
+/// A rule for totting up scratchcards, fed the cards in order.
+pub trait Scoring {
+    /// Add a card with its number of matches.
+    fn add_card(&mut self, id: u32, matches: usize) -> Result<()>;
+
+    /// The total for the cards added so far.
+    fn total(&self) -> u64;
+}
+
+/// Part 1: each card is worth 1 point for its first match, doubled for each
+/// match after it.
+#[derive(Debug, Default)]
+pub struct Points {
+    total: u64,
+}
+
+impl Scoring for Points {
+    fn add_card(&mut self, id: u32, matches: usize) -> Result<()> {
+        let points = score(id, matches as u32)?;
+        self.total = self
+            .total
+            .checked_add(points as u64)
+            .ok_or_else(|| AocError::overflow(format!("Too many points to count at card {id}")))?;
+        Ok(())
+    }
+
+    fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+/// Part 2: each copy of a card wins a copy of each of the next cards, one per
+/// match, and the total is the number of cards you end up with.
+///
+/// Wins only reach the next few cards, so only the copies won of those are
+/// kept, in a ring buffer as long as the most matches on a card. Copies won
+/// of cards past the last card are dropped.
+#[derive(Debug, Default)]
+pub struct Copies {
+    /// The copies won so far of each of the next cards.
+    won: VecDeque<u64>,
+    cards: u32,
+    total: u64,
+}
+
+impl Copies {
+    /// Add a card, as by [`Scoring::add_card`], returning how many of it you
+    /// end up with.
+    pub fn add(&mut self, id: u32, matches: usize) -> Result<u64> {
+        if id != self.cards + 1 {
+            return Err(AocError::parse(format!("Card {id} is out of order, expected card {}", self.cards + 1)));
+        }
+        self.cards = id;
+        let copies = 1 + self.won.pop_front().unwrap_or(0);
+        self.total = self
+            .total
+            .checked_add(copies)
+            .ok_or_else(|| AocError::overflow(format!("Too many cards to count at card {id}")))?;
+
+        // Win one copy of the next `matches` cards for each copy of this card.
+        if self.won.len() < matches {
+            self.won.resize(matches, 0);
+        }
+        for (j, count) in self.won.iter_mut().take(matches).enumerate() {
+            *count = count
+                .checked_add(copies)
+                .ok_or_else(|| {
+                    AocError::overflow(format!("Card {} would have too many copies to count", id as usize + j + 1))
+                })?;
+        }
+        Ok(copies)
+    }
+}
+
+impl Scoring for Copies {
+    fn add_card(&mut self, id: u32, matches: usize) -> Result<()> {
+        self.add(id, matches).map(|_| ())
+    }
+
+    fn total(&self) -> u64 {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::lenient::LinePolicy;
+
+    use super::*;
+    use crate::total_score;
+
+    fn copies(lines: &[&str]) -> Result<u64> {
+        total_score(aoc_utils::lines::lines(lines.join("\n").as_bytes()), &mut Copies::default(), &mut LinePolicy::strict())
+    }
+
+    #[test]
+    fn test_points() {
+        let mut points = Points::default();
+        for (id, matches) in [(1, 4), (2, 0), (3, 1)] {
+            points.add_card(id, matches).unwrap();
+        }
+        assert_eq!(points.total(), 9);
+        assert!(points.add_card(4, 40).is_err());
+    }
+
+    #[test]
+    fn test_copies() {
+        let lines = [
+            "Card   1: 1 2 3 | 1 2 3", // 3 matches => 1 card
+            "Card   2: 1 2 3 | 1 5 6", // 1 match   => 2 card
+            "Card   3: 1 2 3 | 4 5 6", // 0 matches => 4 cards
+            "Card   4: 1 1 1 | 3 4 5", // 0 matches => 2 card
+            "Card   5: 1 1 1 | 3 4 5", // 0 matches => 1 card
+        ];
+        assert_eq!(copies(&lines).unwrap(), 10);
+
+        let lines = [
+            "Card   1: 1 2 3 4 | 1 2 3 4", // 4 matches
+            "Card   2: 1 2 3 4 | 1 2 5 6", // 2 matches
+            "Card   3: 1 2 3 4 | 1 2 5 6", // 2 matches
+            "Card   4: 1 2 3 4 | 1 5 6 7", // 1 match
+            "Card   5: 1 2 3 4 | 5 6 7 8", // 0 matches
+            "Card   6: 1 2 3 4 | 5 6 7 8", // 0 matches
+        ];
+        assert_eq!(copies(&lines).unwrap(), 30);
+
+        assert_eq!(copies(&["Card   1: 1 2 3 | 4 5 6"]).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_copies_out_of_order() {
+        let error = copies(&["Card   1: 1 2 3 | 1 5 6", "Card   3: 1 2 3 | 4 5 6"]).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2: Card 3 is out of order, expected card 2");
+    }
+
+    #[test]
+    fn test_copies_win_past_end() {
+        assert_eq!(copies(&["Card   1: 1 2 3 | 1 2 3", "Card   2: 1 2 3 | 1 2 3"]).unwrap(), 3);
+        assert_eq!(copies(&["Card   1: 1 2 3 | 1 2 3"]).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_copies_long_input() {
+        // Card 1 wins card 2, then every card is on its own.
+        let mut copies = Copies::default();
+        copies.add_card(1, 1).unwrap();
+        for id in 2..=100_000 {
+            copies.add_card(id, 0).unwrap();
+        }
+        assert_eq!(copies.total(), 100_001);
+    }
+
+    #[test]
+    fn test_copies_overflow() {
+        // Each card wins a copy of the next 25, so the copies nearly double
+        // with each card.
+        let mut copies = Copies::default();
+        let error = (1..=100).try_for_each(|id| copies.add_card(id, 25)).unwrap_err();
+        assert_eq!(error.to_string(), "Too many cards to count at card 65");
+    }
+}
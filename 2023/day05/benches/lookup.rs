@@ -0,0 +1,150 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::hint::black_box;
+use std::io::Cursor;
+
+use aoc_utils::hash::FxBuildHasher;
+use aoc_utils::intern::Interner;
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::lines::lines;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use day05::{brute_force, generate};
+use day05::{read_all_maps, read_map, read_seeds, seed_ranges, Map};
+
+/// A map with `count` adjacent mappings of 1000 values each.
+fn synthetic_map(count: u64) -> Map {
+    let mut text = String::from("seed-to-location map:\n");
+    for i in 0..count {
+        text.push_str(&format!("{} {} 1000\n", (count - i) * 1000, i * 1000));
+    }
+    let mut line_iter = lines(Cursor::new(text));
+    read_map(&mut line_iter, false, &mut LinePolicy::strict()).unwrap().unwrap()
+}
+
+fn map_lookup(c: &mut Criterion) {
+    let mut group = c.benchmark_group("map_lookup");
+    for count in [10, 100, 1000] {
+        let map = synthetic_map(count);
+        let values: Vec<u64> = (0..1000).map(|i| i * count + 7).collect();
+        group.bench_with_input(BenchmarkId::new("linear", count), &values, |b, values| {
+            b.iter(|| values.iter().map(|&v| map.lookup_linear(black_box(v))).sum::<u64>())
+        });
+        group.bench_with_input(BenchmarkId::new("binary_search", count), &values, |b, values| {
+            b.iter(|| values.iter().map(|&v| map.lookup(black_box(v))).sum::<u64>())
+        });
+    }
+    group.finish();
+}
+
+fn seed_location(c: &mut Criterion) {
+    let input = include_str!("../input.txt");
+    let mut line_iter = lines(Cursor::new(input));
+    let seeds = read_seeds(&line_iter.next().unwrap().unwrap()).unwrap();
+    line_iter.next();
+    let maps = read_all_maps(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+
+    c.bench_function("lookup_seed_location", |b| {
+        b.iter(|| {
+            seeds
+                .iter()
+                .map(|&seed| maps.lookup_seed_location(black_box(seed)).unwrap())
+                .min()
+        })
+    });
+}
+
+/// Part 2 workloads: mapping the seed ranges as intervals, and looking up
+/// seeds one at a time as the brute force does.
+fn seed_ranges_location(c: &mut Criterion) {
+    let input = include_str!("../input.txt");
+    let mut line_iter = lines(Cursor::new(input));
+    let seeds = read_seeds(&line_iter.next().unwrap().unwrap()).unwrap();
+    line_iter.next();
+    let maps = read_all_maps(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+    let ranges = seed_ranges(&seeds).unwrap();
+
+    c.bench_function("lookup_seed_location_ranges", |b| {
+        b.iter(|| maps.lookup_seed_location_ranges(black_box(&ranges)).unwrap())
+    });
+    let start = ranges[0].start;
+    c.bench_function("lookup_seed_location_10k", |b| {
+        b.iter(|| {
+            (start..start + 10_000)
+                .map(|seed| maps.lookup_seed_location(black_box(seed)).unwrap())
+                .min()
+        })
+    });
+    // One of the brute force's chunks, 8 seeds at a time with `--features simd`.
+    c.bench_function("brute_force_1m", |b| {
+        b.iter(|| brute_force::smallest_location(&[black_box(start..start + (1 << 20))], &maps, &|_| {}).unwrap())
+    });
+}
+
+/// Part 2 on generated almanacs with more and more mappings per map.
+fn generated_seed_ranges(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generated_seed_ranges");
+    for mappings in [10, 100, 1000] {
+        let input = generate::almanac(12345, 10, 7, mappings, 1 << 32);
+        let mut line_iter = lines(Cursor::new(input));
+        let seeds = read_seeds(&line_iter.next().unwrap().unwrap()).unwrap();
+        line_iter.next();
+        let maps = read_all_maps(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+        let ranges = seed_ranges(&seeds).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(mappings), &ranges, |b, ranges| {
+            b.iter(|| maps.lookup_seed_location_ranges(black_box(ranges)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// Number each category the first time it's seen, as `Mappings::new` does,
+/// with the hasher `S`.
+fn intern<S: BuildHasher + Default>(names: &[(&str, &str)]) -> usize {
+    let mut ids: HashMap<String, usize, S> = HashMap::default();
+    for (source, destination) in names {
+        for name in [source, destination] {
+            let next = ids.len();
+            ids.entry(name.to_string()).or_insert(next);
+        }
+    }
+    ids.len()
+}
+
+/// Number each category with an `Interner`, which copies a name only the
+/// first time.
+fn interner(names: &[(&str, &str)]) -> usize {
+    let mut interner = Interner::new();
+    for (source, destination) in names {
+        interner.intern(source);
+        interner.intern(destination);
+    }
+    interner.len()
+}
+
+/// Indexing the categories of almanacs with more and more maps, with the
+/// standard SipHash, with FxHash, and with the shared interner.
+fn hashers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("intern_categories");
+    for maps in [7, 100, 1000] {
+        let input = generate::almanac(12345, 1, maps, 1, 1 << 32);
+        let names: Vec<(&str, &str)> = input
+            .lines()
+            .filter_map(|line| line.strip_suffix(" map:")?.split_once("-to-"))
+            .collect();
+        group.bench_with_input(BenchmarkId::new("siphash", maps), &names, |b, names| {
+            b.iter(|| intern::<RandomState>(black_box(names)))
+        });
+        group.bench_with_input(BenchmarkId::new("fxhash", maps), &names, |b, names| {
+            b.iter(|| intern::<FxBuildHasher>(black_box(names)))
+        });
+        group.bench_with_input(BenchmarkId::new("interner", maps), &names, |b, names| {
+            b.iter(|| interner(black_box(names)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, map_lookup, seed_location, seed_ranges_location, generated_seed_ranges, hashers);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+use std::hint::black_box;
+
+use aoc_utils::arena::Bump;
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::lines::lines;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use day05::generate;
+use day05::read_almanac;
+
+/// Reading almanacs with a `String` for each line, and with the lines in an
+/// arena freed once the almanac is read.
+fn read_lines(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_almanac");
+    for mappings in [10, 100, 1000] {
+        let input = generate::almanac(12345, 10, 7, mappings, 1 << 32);
+        group.bench_with_input(BenchmarkId::new("strings", mappings), &input, |b, input| {
+            b.iter(|| read_almanac(&mut lines(black_box(input.as_bytes())), false, &mut LinePolicy::strict()).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("arena", mappings), &input, |b, input| {
+            b.iter(|| {
+                let arena = Bump::new();
+                let mut lines = lines(black_box(input.as_bytes())).in_arena(&arena);
+                read_almanac(&mut lines, false, &mut LinePolicy::strict()).unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, read_lines);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+//! Parsing and solving each part on the real input and generated ones.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day05::{generate, Day05};
+
+/// The real input, then generated ones: almanacs with more and more mappings
+/// in each of the 7 maps.
+fn inputs() -> Vec<(String, String)> {
+    let mut inputs = vec![("input".to_string(), include_str!("../input.txt").to_string())];
+    for mappings in [10, 100, 1000] {
+        inputs.push((format!("generated/{mappings}"), generate::almanac(12345, 10, 7, mappings, 1 << 32)));
+    }
+    inputs
+}
+
+fn solve(c: &mut Criterion) {
+    aoc_utils::benches::solver(c, &Day05, &inputs());
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
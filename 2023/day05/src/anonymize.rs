@@ -0,0 +1,72 @@
+//! Scramble an almanac so it can be shared without sharing the puzzle input.
+
+use aoc_utils::error::{AocError, Result};
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::random::Rng;
+
+use crate::read_almanac;
+
+/// Add the same random offset to every seed range and mapping start, and
+/// shuffle the seed ranges, the maps, and their mappings. Values that aren't
+/// mapped pass through unchanged either way, so part 2's answer grows by the
+/// offset, which is returned with the almanac. Part 1 also reads the range
+/// lengths as seeds, and they aren't offset, so its answer may change.
+pub fn anonymize(input: &str, seed: u64) -> Result<(String, u64)> {
+    let mut rng = Rng::new(seed);
+    let (seeds, mappings) =
+        read_almanac(&mut aoc_utils::lines::lines(input.as_bytes()), false, &mut LinePolicy::strict())?;
+    let offset = rng.range(1..1 << 32);
+    let shift = |value: u64| {
+        value
+            .checked_add(offset)
+            .ok_or_else(|| AocError::overflow(format!("{value} is too big to offset by {offset}")))
+    };
+
+    let mut pairs: Vec<&[u64]> = seeds.chunks(2).collect();
+    rng.shuffle(&mut pairs);
+    let mut numbers = Vec::new();
+    for pair in pairs {
+        numbers.push(shift(pair[0])?.to_string());
+        numbers.extend(pair[1..].iter().map(u64::to_string));
+    }
+    let mut almanac = format!("seeds: {}\n", numbers.join(" "));
+
+    let mut maps: Vec<_> = mappings.maps.iter().collect();
+    rng.shuffle(&mut maps);
+    for map in maps {
+        almanac.push_str(&format!("\n{}-to-{} map:\n", map.source, map.destination));
+        let mut lines = map
+            .mappings
+            .iter()
+            .map(|mapping| {
+                let length = mapping.source.end - mapping.source.start;
+                shift(mapping.source.end)?;
+                Ok(format!("{} {} {length}\n", shift(mapping.destination_start)?, shift(mapping.source.start)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        rng.shuffle(&mut lines);
+        almanac.extend(lines);
+    }
+    Ok((almanac, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::solver::{Answer, DynSolver};
+
+    use super::*;
+    use crate::Day05;
+
+    #[test]
+    fn test_anonymize() {
+        for input in [include_str!("../fixtures/example.txt"), include_str!("../input.txt")] {
+            let (anonymized, offset) = anonymize(input, 1).unwrap();
+            assert_ne!(anonymized, input);
+            assert_eq!(anonymize(input, 1).unwrap(), (anonymized.clone(), offset));
+            let Answer::Unsigned(answer) = Day05.solve(input, 2).unwrap() else { panic!() };
+            assert_eq!(Day05.solve(&anonymized, 2).unwrap(), Answer::Unsigned(answer + offset));
+            Day05.solve(&anonymized, 1).unwrap();
+        }
+        assert!(anonymize("seeds: 18446744073709551615 1\n", 1).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! Part 2 by looking up every seed in the ranges, in parallel. This is far
+//! slower than mapping whole ranges, but simple enough to trust as an oracle.
+
+use std::ops::Range;
+use std::sync::Mutex;
+
+use aoc_utils::error::{AocError, Result};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Map, Mappings};
+
+/// The number of seeds each parallel task looks up.
+const CHUNK_SIZE: u64 = 1 << 20;
+
+/// Split the ranges into chunks of at most `CHUNK_SIZE` seeds, as they're
+/// needed, since a huge range has too many to hold at once.
+fn chunks(seeds: &[Range<u64>]) -> impl Iterator<Item = Range<u64>> + Send + '_ {
+    seeds.iter().flat_map(|range| {
+        (range.start..range.end)
+            .step_by(CHUNK_SIZE as usize)
+            .map(move |start| start..start.saturating_add(CHUNK_SIZE).min(range.end))
+    })
+}
+
+/// How many seeds a search looks up: those in the ranges, counting seeds in
+/// more than one range once, as they're only looked up once.
+pub fn total(seeds: &[Range<u64>]) -> u64 {
+    seeds
+        .iter()
+        .fold(Progress::default(), |mut all, range| {
+            all.add(range.clone(), None);
+            all
+        })
+        .searched()
+}
+
+/// How far a search got: the smallest location found, and how many of the
+/// seeds were looked up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Search {
+    pub smallest: Option<u64>,
+    pub searched: u64,
+    pub total: u64,
+}
+
+impl Search {
+    /// Whether every seed was looked up, so `smallest` is the answer.
+    pub fn is_complete(&self) -> bool {
+        self.searched == self.total
+    }
+}
+
+/// The chunks of seeds already looked up and the smallest location among
+/// them, saved to resume a search from.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// The seeds looked up, as sorted ranges that don't touch.
+    pub done: Vec<Range<u64>>,
+    pub smallest: Option<u64>,
+}
+
+impl Progress {
+    /// How many seeds have been looked up.
+    pub fn searched(&self) -> u64 {
+        self.done.iter().map(|range| range.end - range.start).sum()
+    }
+
+    /// Whether every seed in the chunk has been looked up.
+    fn contains(&self, chunk: &Range<u64>) -> bool {
+        let i = self.done.partition_point(|range| range.end < chunk.end);
+        self.done.get(i).is_some_and(|range| range.start <= chunk.start && chunk.end <= range.end)
+    }
+
+    /// Record a chunk as looked up, merging it with the ranges it touches.
+    fn add(&mut self, chunk: Range<u64>, smallest: Option<u64>) {
+        self.smallest = self.smallest.into_iter().chain(smallest).min();
+        let start = self.done.partition_point(|range| range.end < chunk.start);
+        let end = self.done.partition_point(|range| range.start <= chunk.end);
+        let merged = match self.done.get(start..end) {
+            Some([first, .., last]) | Some([first @ last]) => first.start.min(chunk.start)..last.end.max(chunk.end),
+            _ => chunk,
+        };
+        self.done.splice(start..end, [merged]);
+    }
+}
+
+/// Find the smallest location of any seed in the ranges by looking up each
+/// one. `on_progress` is called with the number of seeds in each finished
+/// chunk.
+pub fn smallest_location(
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    on_progress: &(dyn Fn(u64) + Sync),
+) -> Result<u64> {
+    search(seeds, maps, on_progress, &|| false)?.smallest.ok_or_else(|| AocError::no_solution("No seeds"))
+}
+
+/// Like `smallest_location`, but chunks not yet started once `stop` returns
+/// true are skipped, giving how far the search got.
+pub fn search(
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    on_progress: &(dyn Fn(u64) + Sync),
+    stop: &(dyn Fn() -> bool + Sync),
+) -> Result<Search> {
+    search_from(seeds, maps, &Mutex::default(), on_progress, stop)
+}
+
+/// Like `search`, but skipping the chunks already in `progress`, and adding
+/// each chunk to it as it's finished, before `on_progress` is called.
+pub fn search_from(
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    progress: &Mutex<Progress>,
+    on_progress: &(dyn Fn(u64) + Sync),
+    stop: &(dyn Fn() -> bool + Sync),
+) -> Result<Search> {
+    let chain = maps.seed_to_location_chain()?;
+    let total = total(seeds);
+    let resumed = progress.lock().unwrap().clone();
+    let chunks = chunks(seeds).filter(|chunk| !resumed.contains(chunk));
+    chunks.take_while(|_| !stop()).par_bridge().for_each(|chunk| {
+        let count = chunk.end - chunk.start;
+        let smallest = chunk_smallest(chain, chunk.clone());
+        progress.lock().unwrap().add(chunk, smallest);
+        on_progress(count);
+    });
+    let progress = progress.lock().unwrap();
+    Ok(Search { smallest: progress.smallest, searched: progress.searched(), total })
+}
+
+/// The smallest location of any seed in the chunk, looking each one up.
+#[cfg(not(feature = "simd"))]
+fn chunk_smallest(chain: &[Map], chunk: Range<u64>) -> Option<u64> {
+    chunk.map(|seed| chain.iter().fold(seed, |value, map| map.lookup(value))).min()
+}
+
+/// The smallest location of any seed in the chunk, looking up 8 at a time.
+#[cfg(feature = "simd")]
+fn chunk_smallest(chain: &[Map], chunk: Range<u64>) -> Option<u64> {
+    crate::simd::smallest_location(chain, chunk)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::*;
+    use crate::tests::example_maps;
+    use crate::{find_smallest_location_for_seed_ranges, seed_ranges};
+
+    #[test]
+    fn test_chunks() {
+        assert_eq!(chunks(&[0..10, 20..20]).collect::<Vec<_>>(), vec![0..10]);
+        let seeds = seed_ranges(&[5, CHUNK_SIZE * 2 + 5]).unwrap();
+        assert_eq!(
+            chunks(&seeds).collect::<Vec<_>>(),
+            vec![5..CHUNK_SIZE + 5, CHUNK_SIZE + 5..CHUNK_SIZE * 2 + 5, CHUNK_SIZE * 2 + 5..CHUNK_SIZE * 2 + 10]
+        );
+        // A range ending near the top doesn't overflow.
+        let seeds = seed_ranges(&[u64::MAX - 1000, 500]).unwrap();
+        assert_eq!(chunks(&seeds).collect::<Vec<_>>(), seeds);
+        // Nor does one too big to split up front.
+        let seeds = seed_ranges(&[0, u64::MAX]).unwrap();
+        assert_eq!(chunks(&seeds).nth(1), Some(CHUNK_SIZE..CHUNK_SIZE * 2));
+    }
+
+    #[test]
+    fn test_smallest_location() {
+        let maps = example_maps();
+        let seeds = seed_ranges(&[79, 14, 55, 13]).unwrap();
+        let progress = AtomicU64::new(0);
+        let smallest = smallest_location(&seeds, &maps, &|count| {
+            progress.fetch_add(count, Ordering::Relaxed);
+        });
+        assert_eq!(smallest.unwrap(), 46);
+        assert_eq!(progress.load(Ordering::Relaxed), 27);
+        assert!(smallest_location(&[], &maps, &|_| {}).is_err());
+    }
+
+    #[test]
+    fn test_search_stopped() {
+        let maps = example_maps();
+        let seeds = seed_ranges(&[79, 14, 55, 13]).unwrap();
+        let search = search(&seeds, &maps, &|_| {}, &|| true).unwrap();
+        assert_eq!(search, Search { smallest: None, searched: 0, total: 27 });
+        assert!(!search.is_complete());
+    }
+
+    #[test]
+    fn test_search_overlapping() {
+        let maps = example_maps();
+        let seeds = seed_ranges(&[55, 13, 60, 20, 70, 1]).unwrap();
+        let search = search(&seeds, &maps, &|_| {}, &|| false).unwrap();
+        assert_eq!(search, Search { smallest: Some(0), searched: 25, total: 25 });
+        assert!(search.is_complete());
+    }
+
+    #[test]
+    fn test_total() {
+        assert_eq!(total(&seed_ranges(&[79, 14, 55, 13]).unwrap()), 27);
+        assert_eq!(total(&seed_ranges(&[55, 13, 60, 20, 70, 1]).unwrap()), 25);
+        assert_eq!(total(&seed_ranges(&[0, u64::MAX, 0, 5]).unwrap()), u64::MAX);
+        assert_eq!(total(&[]), 0);
+    }
+
+    #[test]
+    fn test_progress() {
+        let mut progress = Progress::default();
+        progress.add(10..20, Some(7));
+        progress.add(30..40, None);
+        progress.add(0..5, Some(9));
+        assert_eq!(progress.done, vec![0..5, 10..20, 30..40]);
+        progress.add(20..30, Some(3));
+        assert_eq!(progress.done, vec![0..5, 10..40]);
+        assert_eq!((progress.smallest, progress.searched()), (Some(3), 35));
+        assert!(progress.contains(&(12..15)) && progress.contains(&(0..5)));
+        assert!(!progress.contains(&(4..6)) && !progress.contains(&(5..10)) && !progress.contains(&(40..41)));
+    }
+
+    #[test]
+    fn test_search_resumed() {
+        let maps = example_maps();
+        let seeds = seed_ranges(&[79, 14, 55, 13]).unwrap();
+        // Resuming with the first range recorded as done with a smallest
+        // location of 60, which isn't so, to show it isn't searched again.
+        let progress = Mutex::new(Progress { done: seeds[..1].to_vec(), smallest: Some(60) });
+        let searched = AtomicU64::new(0);
+        let search = search_from(&seeds, &maps, &progress, &|count| {
+            searched.fetch_add(count, Ordering::Relaxed);
+        }, &|| false)
+        .unwrap();
+        assert_eq!(search, Search { smallest: Some(56), searched: 27, total: 27 });
+        assert_eq!(searched.load(Ordering::Relaxed), 13);
+        assert_eq!(progress.lock().unwrap().done, vec![55..68, 79..93]);
+    }
+
+    #[test]
+    fn test_matches_intervals() {
+        let maps = example_maps();
+        for (start, len) in [(0, 100), (90, 20), (13, 1), (50, 2)] {
+            let seeds = seed_ranges(&[start, len]).unwrap();
+            assert_eq!(
+                smallest_location(&seeds, &maps, &|_| {}).unwrap(),
+                find_smallest_location_for_seed_ranges(&seeds, &maps).unwrap(),
+            );
+        }
+    }
+}
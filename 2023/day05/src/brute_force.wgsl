@@ -0,0 +1,52 @@
+// Looks up one seed per invocation and keeps the smallest location.
+
+struct Params {
+    seed_start: u32,
+    count: u32,
+    num_maps: u32,
+    _padding: u32,
+}
+
+struct Mapping {
+    source_start: u32,
+    length: u32,
+    destination_start: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// The index of each map's first mapping, followed by the number of mappings.
+@group(0) @binding(1) var<storage, read> offsets: array<u32>;
+// Each map's mappings, sorted by source start.
+@group(0) @binding(2) var<storage, read> mappings: array<Mapping>;
+@group(0) @binding(3) var<storage, read_write> smallest: atomic<u32>;
+
+@compute @workgroup_size(256)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= params.count {
+        return;
+    }
+
+    var value = params.seed_start + id.x;
+    for (var map = 0u; map < params.num_maps; map++) {
+        // Find the last mapping starting at or before the value.
+        let first = offsets[map];
+        var low = first;
+        var high = offsets[map + 1u];
+        while low < high {
+            let mid = (low + high) / 2u;
+            if mappings[mid].source_start <= value {
+                low = mid + 1u;
+            } else {
+                high = mid;
+            }
+        }
+        if low > first {
+            let mapping = mappings[low - 1u];
+            let offset = value - mapping.source_start;
+            if offset < mapping.length {
+                value = mapping.destination_start + offset;
+            }
+        }
+    }
+    atomicMin(&smallest, value);
+}
@@ -0,0 +1,86 @@
+//! Seeded almanacs of any size, for benchmarks, stress tests, and checking
+//! the part 2 strategies against each other.
+
+use aoc_utils::random::Rng;
+
+/// The categories between seed and location in the puzzle input.
+const CATEGORIES: [&str; 6] = ["soil", "fertilizer", "water", "light", "temperature", "humidity"];
+
+/// An almanac with `seed_ranges` pairs of seeds and a chain of `maps` maps
+/// from seed to location, each with up to `mappings` mappings. Every number
+/// is below `space`, and the mappings in a map don't overlap. With 7 maps the
+/// categories are the puzzle's; with more, the extras are numbered.
+pub fn almanac(seed: u64, seed_ranges: usize, maps: usize, mappings: usize, space: u64) -> String {
+    assert!(maps > 0 && space > 1, "An almanac needs a map and room for a mapping");
+    let mut rng = Rng::new(seed);
+    let seeds: Vec<String> = (0..seed_ranges)
+        .flat_map(|_| {
+            let start = rng.range(0..space - 1);
+            let length = rng.range(1..(space - start).min(space / seed_ranges as u64).max(2));
+            [start, length]
+        })
+        .map(|number| number.to_string())
+        .collect();
+    let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+
+    let category = |i: usize| match i {
+        0 => "seed".to_string(),
+        i if i == maps => "location".to_string(),
+        i => CATEGORIES.get(i - 1).map_or_else(|| format!("category{i}"), |name| name.to_string()),
+    };
+    for i in 0..maps {
+        almanac.push_str(&format!("\n{}-to-{} map:\n", category(i), category(i + 1)));
+        // Pair up sorted distinct points into source ranges that can't overlap.
+        let mut points: Vec<u64> = (0..mappings * 2).map(|_| rng.range(0..space)).collect();
+        points.sort();
+        points.dedup();
+        for pair in points.chunks_exact(2) {
+            let destination = rng.range(0..space);
+            almanac.push_str(&format!("{destination} {} {}\n", pair[0], pair[1] - pair[0]));
+        }
+    }
+    almanac
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::cancel::Cancel;
+    use aoc_utils::solver::{Answer, Solver};
+
+    use super::*;
+    use crate::{check_smallest_location_for_seed_ranges, seed_ranges, Day05};
+
+    #[test]
+    fn test_almanac() {
+        let text = almanac(1, 4, 7, 10, 1 << 32);
+        assert_eq!(text, almanac(1, 4, 7, 10, 1 << 32));
+        assert_ne!(text, almanac(2, 4, 7, 10, 1 << 32));
+        let (seeds, maps) = Day05.parse(&text).unwrap();
+        assert_eq!(seeds.len(), 8);
+        let chain = maps.seed_to_location_chain().unwrap();
+        assert_eq!(chain.len(), 7);
+        assert_eq!((chain[1].source(), chain[6].destination()), ("soil", "location"));
+
+        let (_, maps) = Day05.parse(&almanac(1, 1, 9, 1, 100)).unwrap();
+        assert_eq!(maps.seed_to_location_chain().unwrap()[7].source(), "category7");
+    }
+
+    /// A large almanac solves, and no seed range start beats part 2.
+    #[test]
+    fn test_large_almanac() {
+        let parsed = Day05.parse(&almanac(7, 10, 7, 1000, 1 << 32)).unwrap();
+        let Answer::Unsigned(smallest) = Day05.part2(&parsed, &Cancel::new()).unwrap() else { panic!() };
+        for range in seed_ranges(&parsed.0).unwrap() {
+            assert!(parsed.1.lookup_seed_location(range.start).unwrap() >= smallest);
+        }
+    }
+
+    /// Every part 2 strategy agrees on small almanacs.
+    #[test]
+    fn test_strategies_agree() {
+        for seed in 0..20 {
+            let (seeds, maps) = Day05.parse(&almanac(seed, 3, 4, 5, 2000)).unwrap();
+            check_smallest_location_for_seed_ranges(&seed_ranges(&seeds).unwrap(), &maps, &|_| {}, &Cancel::new()).unwrap();
+        }
+    }
+}
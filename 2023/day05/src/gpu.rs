@@ -0,0 +1,192 @@
+//! Part 2 by looking up every seed on the GPU. The maps are uploaded once and
+//! a compute shader looks up millions of seeds per dispatch, keeping the
+//! smallest location with an atomic.
+//!
+//! WGSL has no 64-bit integers, so every seed, source and destination must
+//! fit in a `u32`. That holds for the puzzle inputs.
+
+use std::ops::Range;
+use std::sync::mpsc;
+
+use anyhow::{anyhow, Context, Result};
+use wgpu::util::DeviceExt;
+
+use crate::Mappings;
+
+const WORKGROUP_SIZE: u32 = 256;
+/// The most workgroups a single dispatch dimension may have.
+const MAX_WORKGROUPS: u32 = 65535;
+/// The number of seeds each dispatch looks up.
+const DISPATCH_SIZE: u64 = (WORKGROUP_SIZE * MAX_WORKGROUPS) as u64;
+
+/// The map chain flattened into the shader's buffers: the offset of each
+/// map's mappings, and each mapping as `[source start, length, destination
+/// start]`.
+fn tables(maps: &Mappings) -> Result<(Vec<u32>, Vec<[u32; 3]>)> {
+    let too_big = || anyhow!("The maps use numbers too big for the GPU");
+    let mut offsets = vec![0];
+    let mut mappings = Vec::new();
+    for map in maps.seed_to_location_chain()? {
+        for mapping in &map.mappings {
+            let length = mapping.source.end - mapping.source.start;
+            if mapping.source.end > 1 << 32 || mapping.destination_start + length > 1 << 32 {
+                return Err(too_big());
+            }
+            mappings.push([
+                mapping.source.start as u32,
+                length as u32,
+                mapping.destination_start as u32,
+            ]);
+        }
+        offsets.push(mappings.len() as u32);
+    }
+    Ok((offsets, mappings))
+}
+
+/// Find the smallest location of any seed in the ranges on the GPU. Returns
+/// `None` if there is no GPU to use. `on_progress` is called with the number
+/// of seeds in each finished dispatch.
+pub fn smallest_location(
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    on_progress: &dyn Fn(u64),
+) -> Result<Option<u64>> {
+    if seeds.iter().all(|range| range.is_empty()) {
+        return Err(anyhow!("No seeds"));
+    }
+    if seeds.iter().any(|range| range.end > 1 << 32) {
+        return Err(anyhow!("The seeds are too big for the GPU"));
+    }
+    let (offsets, mut mappings) = tables(maps)?;
+    let num_maps = offsets.len() as u32 - 1;
+    if mappings.is_empty() {
+        // Storage buffers can't be empty.
+        mappings.push([0; 3]);
+    }
+
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+    let Ok(adapter) = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    })) else {
+        return Ok(None);
+    };
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+        .context("Error opening the GPU")?;
+
+    let module = device.create_shader_module(wgpu::include_wgsl!("brute_force.wgsl"));
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("brute force"),
+        layout: None,
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    let params = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("params"),
+        size: 16,
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let offsets = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("offsets"),
+        contents: bytemuck::cast_slice(&offsets),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    let mappings = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("mappings"),
+        contents: bytemuck::cast_slice(&mappings),
+        usage: wgpu::BufferUsages::STORAGE,
+    });
+    let smallest = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("smallest"),
+        size: 4,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("readback"),
+        size: 4,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: offsets.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 2, resource: mappings.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 3, resource: smallest.as_entire_binding() },
+        ],
+    });
+
+    queue.write_buffer(&smallest, 0, bytemuck::bytes_of(&u32::MAX));
+    for range in seeds {
+        for start in (range.start..range.end).step_by(DISPATCH_SIZE as usize) {
+            let count = (range.end - start).min(DISPATCH_SIZE) as u32;
+            queue.write_buffer(&params, 0, bytemuck::cast_slice(&[start as u32, count, num_maps, 0]));
+
+            let mut encoder = device.create_command_encoder(&Default::default());
+            {
+                let mut pass = encoder.begin_compute_pass(&Default::default());
+                pass.set_pipeline(&pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(count.div_ceil(WORKGROUP_SIZE), 1, 1);
+            }
+            queue.submit([encoder.finish()]);
+            device
+                .poll(wgpu::PollType::wait_indefinitely())
+                .context("Error waiting for the GPU")?;
+            on_progress(count as u64);
+        }
+    }
+
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_buffer_to_buffer(&smallest, 0, &readback, 0, 4);
+    queue.submit([encoder.finish()]);
+    let (sender, receiver) = mpsc::channel();
+    readback.map_async(wgpu::MapMode::Read, .., move |result| sender.send(result).unwrap());
+    device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .context("Error waiting for the GPU")?;
+    receiver.recv()?.context("Error reading the result from the GPU")?;
+    let smallest = *bytemuck::from_bytes::<u32>(&readback.get_mapped_range(..)?);
+    Ok(Some(smallest as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::lenient::LinePolicy;
+
+    use super::*;
+    use crate::tests::example_maps;
+    use crate::{find_smallest_location_for_seed_ranges, read_all_maps, seed_ranges};
+
+    #[test]
+    fn test_tables() {
+        let (offsets, mappings) = tables(&example_maps()).unwrap();
+        assert_eq!(offsets, vec![0, 2, 5, 9, 11, 14, 16, 18]);
+        assert_eq!(mappings[..2], [[50, 48, 52], [98, 2, 50]]);
+
+        let input = "seed-to-location map:\n4294967290 0 10\n";
+        let mut lines = input.lines().map(|line| Ok(line.to_string()));
+        assert!(tables(&read_all_maps(&mut lines, false, &mut LinePolicy::strict()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_smallest_location() {
+        let maps = example_maps();
+        for (start, len) in [(79, 14), (0, 100), (90, 20), (13, 1)] {
+            let seeds = seed_ranges(&[start, len]).unwrap();
+            // Nothing to check on machines without a GPU.
+            if let Some(smallest) = smallest_location(&seeds, &maps, &|_| {}).unwrap() {
+                assert_eq!(smallest, find_smallest_location_for_seed_ranges(&seeds, &maps).unwrap());
+            }
+        }
+        assert!(smallest_location(&[], &maps, &|_| {}).is_err());
+        assert!(smallest_location(&seed_ranges(&[1, 1 << 32]).unwrap(), &maps, &|_| {}).is_err());
+    }
+}
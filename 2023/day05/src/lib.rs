@@ -0,0 +1,1451 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+use std::fmt;
+use std::io::BufRead;
+use std::ops::Range;
+use std::str::FromStr;
+
+use aoc_utils::arena::Bump;
+use aoc_utils::cancel::Cancel;
+use aoc_utils::check;
+use aoc_utils::error::{AocError, Context, Result};
+use aoc_utils::intern::{Interner, Symbol};
+use aoc_utils::lenient::{self, LinePolicy, Validation};
+use aoc_utils::small;
+use aoc_utils::solver::{Answer, Solver};
+use serde::{Deserialize, Serialize, Serializer};
+
+pub mod anonymize;
+pub mod brute_force;
+pub mod generate;
+pub mod reverse;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+#[cfg(feature = "simd")]
+pub mod simd;
+
+/// An interned category name, from `Mappings::categories`.
+pub type CategoryId = Symbol;
+
+/// Serialized as the list of maps.
+#[derive(Debug, Deserialize)]
+#[serde(from = "Vec<Map>")]
+pub struct Mappings {
+    /// The category names.
+    categories: Interner,
+    /// The maps, with those from "seed" to "location" first, in order.
+    maps: Vec<Map>,
+    /// The index in `maps` of the map from each category.
+    by_source: Vec<Option<usize>>,
+    /// The destination category of each map in `maps`.
+    destinations: Vec<CategoryId>,
+    /// The number of maps from "seed" to "location", if they get there.
+    seed_to_location: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(try_from = "MapData")]
+pub struct Map {
+    source: String,
+    destination: String,
+    mappings: Vec<Mapping>,
+    /// The mappings as given, sorted by source start, if some overlapped and
+    /// were resolved, so [`Map::visualize`] can show where. Otherwise empty.
+    #[serde(skip)]
+    overlapping: Vec<Mapping>,
+}
+
+/// Serialized as it appears in the almanac.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Mapping {
+    source: Range<u64>,
+    destination_start: u64,
+}
+
+/// A map as deserialized, before its mappings are sorted and checked.
+#[derive(Deserialize)]
+struct MapData {
+    source: String,
+    destination: String,
+    mappings: Vec<Mapping>,
+}
+
+impl TryFrom<MapData> for Map {
+    type Error = AocError;
+
+    fn try_from(data: MapData) -> Result<Map> {
+        Map::new(data.source, data.destination, data.mappings, false)
+    }
+}
+
+/// One step of a value's path through the maps.
+pub struct Hop<'a> {
+    pub map: &'a Map,
+    /// The mapping that applied, or `None` if the value mapped to itself.
+    pub mapping: Option<&'a Mapping>,
+    pub source: u64,
+    pub destination: u64,
+}
+
+impl Mappings {
+    /// Index the maps by category. A later map from the same category as an
+    /// earlier one replaces it.
+    pub fn new(maps: Vec<Map>) -> Mappings {
+        let mut categories = Interner::new();
+        let seed = categories.intern("seed");
+        let location = categories.intern("location");
+        let mut slots: Vec<Option<Map>> = Vec::new();
+        for map in maps {
+            let source = categories.intern(&map.source).index();
+            categories.intern(&map.destination);
+            slots.resize_with(slots.len().max(source + 1), || None);
+            slots[source] = Some(map);
+        }
+        let id = |name: &str| categories.get(name).unwrap();
+
+        // Put the chain from "seed" first, so it can be used as a slice.
+        let mut ordered = Vec::new();
+        let mut seed_to_location = None;
+        let mut category = seed;
+        while let Some(map) = slots.get_mut(category.index()).and_then(Option::take) {
+            category = id(&map.destination);
+            ordered.push(map);
+            if category == location {
+                seed_to_location = Some(ordered.len());
+                break;
+            }
+        }
+        ordered.extend(slots.into_iter().flatten());
+
+        let mut by_source = vec![None; categories.len()];
+        for (index, map) in ordered.iter().enumerate() {
+            by_source[id(&map.source).index()] = Some(index);
+        }
+        let destinations = ordered.iter().map(|map| id(&map.destination)).collect();
+        Mappings { categories, maps: ordered, by_source, destinations, seed_to_location }
+    }
+
+    /// Look up the ID of a category by name.
+    pub fn category(&self, name: &str) -> Option<CategoryId> {
+        self.categories.get(name)
+    }
+
+    pub fn category_name(&self, id: CategoryId) -> &str {
+        self.categories.resolve(id)
+    }
+
+    /// The map from the source category, if there is one.
+    pub fn get(&self, source: &str) -> Option<&Map> {
+        let index = self.by_source[self.category(source)?.index()]?;
+        Some(&self.maps[index])
+    }
+
+    /// The maps from "seed" to "location", in order. Fails if a category has
+    /// no map, or if the maps loop back on themselves.
+    fn seed_to_location_chain(&self) -> Result<&[Map]> {
+        match self.seed_to_location {
+            Some(len) => Ok(&self.maps[..len]),
+            None => Err(self
+                .chain("seed", "location")
+                .err()
+                .unwrap_or_else(|| AocError::no_solution("The maps don't lead from seed to location"))),
+        }
+    }
+
+    /// Follow the maps from the `from` category to the `to` category,
+    /// returning them in order. Fails if a category has no map, or if the
+    /// maps loop back on themselves.
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&Map>> {
+        let mut chain: Vec<&Map> = Vec::new();
+        if from == to {
+            return Ok(chain);
+        }
+        let to = self.category(to);
+        let mut visited = vec![false; self.maps.len()];
+        let mut key = self.category(from);
+        while key.is_none() || key != to {
+            let path = || {
+                let mut path = vec![from];
+                path.extend(chain.iter().map(|map| map.destination.as_str()));
+                path.join(" -> ")
+            };
+            let Some(index) = key.and_then(|key| self.by_source[key.index()]) else {
+                let name = key.map_or(from, |key| self.category_name(key));
+                let mut available: Vec<&str> = self.maps.iter().map(|map| map.source.as_str()).collect();
+                available.sort();
+                let after = if chain.is_empty() { String::new() } else { format!(" after {}", path()) };
+                return Err(AocError::missing(format!(
+                    "a map for source '{name}'{after}. Maps exist for: {}",
+                    available.join(", ")
+                )));
+            };
+            if visited[index] {
+                return Err(AocError::no_solution(format!("The maps contain a cycle: {}", path())));
+            }
+            visited[index] = true;
+            chain.push(&self.maps[index]);
+            key = Some(self.destinations[index]);
+        }
+        Ok(chain)
+    }
+
+    /// Traverse the maps from the `from` category to find the value in the
+    /// `to` category.
+    pub fn lookup_between(&self, from: &str, to: &str, value: u64) -> Result<u64> {
+        let chain = self.chain(from, to)?;
+        Ok(chain.iter().fold(value, |value, map| map.lookup(value)))
+    }
+
+    /// Traverse all the maps to find the location for the given seed.
+    pub fn lookup_seed_location(&self, seed: u64) -> Result<u64> {
+        let chain = self.seed_to_location_chain()?;
+        Ok(chain.iter().fold(seed, |value, map| map.lookup(value)))
+    }
+
+    /// Render the maps as a Graphviz graph from source to destination
+    /// category, labelling each edge with the number of mappings.
+    pub fn to_dot(&self) -> String {
+        let mut maps: Vec<&Map> = self.maps.iter().collect();
+        maps.sort_by(|a, b| (&a.source, &a.destination).cmp(&(&b.source, &b.destination)));
+        let mut dot = String::from("digraph almanac {\n");
+        for map in maps {
+            dot.push_str(&format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                map.source,
+                map.destination,
+                map.mappings.len()
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Draw each map's ranges with [`Map::visualize`], in chain order.
+    pub fn visualize(&self, width: usize) -> String {
+        self.maps.iter().map(|map| map.visualize(width)).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Traverse all the maps for the given seed, recording each step.
+    pub fn trace_seed(&self, seed: u64) -> Result<Vec<Hop<'_>>> {
+        let mut value = seed;
+        let mut hops = Vec::new();
+        for map in self.seed_to_location_chain()? {
+            let destination = map.lookup(value);
+            hops.push(Hop { map, mapping: map.find_mapping(value), source: value, destination });
+            value = destination;
+        }
+        Ok(hops)
+    }
+
+    /// Traverse all the maps backwards to find the seed that ends up at the
+    /// given location.
+    pub fn lookup_location_seed(&self, location: u64) -> Result<u64> {
+        let mut value = location;
+        for map in self.seed_to_location_chain()?.iter().rev() {
+            value = map.reverse_lookup(value)
+                .ok_or_else(|| AocError::no_solution(format!("No {} maps to {} {value}", map.source, map.destination)))?;
+        }
+        Ok(value)
+    }
+
+    /// Traverse all the maps to find the location ranges for the given seed ranges.
+    pub fn lookup_seed_location_ranges(&self, seeds: &[Range<u64>]) -> Result<Vec<Range<u64>>> {
+        let chain = self.seed_to_location_chain()?;
+        Ok(chain.iter().fold(seeds.to_vec(), |ranges, map| map.lookup_ranges(&ranges)))
+    }
+}
+
+impl Map {
+    /// Create a map, sorting the mappings by source start. Unless
+    /// `allow_overlaps` is set, it is an error for the mappings' source ranges
+    /// to overlap, since which one applies would depend on their order. With
+    /// it, the first in input order applies, as by [`first_wins`].
+    pub fn new(source: String, destination: String, mut mappings: Vec<Mapping>, allow_overlaps: bool) -> Result<Map> {
+        let mut overlapping = Vec::new();
+        if allow_overlaps {
+            let resolved = first_wins(mappings.clone());
+            if resolved != mappings {
+                overlapping = mappings;
+                overlapping.sort_by_key(|mapping| mapping.source.start);
+            }
+            mappings = resolved;
+        }
+        mappings.sort_by_key(|mapping| mapping.source.start);
+        if !allow_overlaps {
+            for pair in mappings.windows(2) {
+                if pair[0].source.end > pair[1].source.start {
+                    return Err(AocError::parse(format!(
+                        "Mappings '{}' and '{}' for '{source}-to-{destination} map:' overlap",
+                        pair[0],
+                        pair[1]
+                    )));
+                }
+            }
+        }
+        Ok(Map { source, destination, mappings, overlapping })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    /// Find the mapping containing the source value. The mappings must be
+    /// sorted by source start, as they are when parsed.
+    pub fn find_mapping(&self, source: u64) -> Option<&Mapping> {
+        // The only mapping that can contain the value is the last one starting at or before it.
+        let index = self.mappings.partition_point(|mapping| mapping.source.start <= source);
+        index.checked_sub(1)
+            .map(|i| &self.mappings[i])
+            .filter(|mapping| mapping.source.contains(&source))
+    }
+
+    /// Map the source value to its destination. The mappings must be sorted
+    /// by source start, as they are when parsed.
+    pub fn lookup(&self, source: u64) -> u64 {
+        match self.find_mapping(source) {
+            Some(mapping) => mapping.destination_start + (source - mapping.source.start),
+            None => source,
+        }
+    }
+
+    /// Map the source value by scanning every mapping. This is the reference
+    /// for `lookup`, kept for tests and benchmarks.
+    pub fn lookup_linear(&self, source: u64) -> u64 {
+        let mapping = self.mappings.iter()
+            .find(|mapping| mapping.source.contains(&source));
+        match mapping {
+            Some(mapping) => mapping.destination_start + (source - mapping.source.start),
+            None => source,
+        }
+    }
+
+    /// Find the source value that maps to the destination value, if any.
+    pub fn reverse_lookup(&self, destination: u64) -> Option<u64> {
+        let mapping = self.mappings.iter()
+            .find(|mapping| mapping.destination_range().contains(&destination));
+        match mapping {
+            Some(mapping) => Some(mapping.source.start + (destination - mapping.destination_start)),
+            // Values not covered by any mapping map to themselves.
+            None if !self.mappings.iter().any(|mapping| mapping.source.contains(&destination)) => Some(destination),
+            None => None,
+        }
+    }
+
+    /// Draw the mappings' source ranges on a number line `width` columns
+    /// wide, one row per mapping followed by its destination offset. A last
+    /// row shows how the ranges cover the line: `#` once, `!` more than once,
+    /// and a space for gaps. Mappings that overlapped are drawn as given,
+    /// not as they were resolved.
+    pub fn visualize(&self, width: usize) -> String {
+        let header = format!("{}-to-{} map:", self.source, self.destination);
+        let mappings = if self.overlapping.is_empty() { &self.mappings } else { &self.overlapping };
+        let (Some(low), Some(high)) = (
+            mappings.iter().map(|mapping| mapping.source.start).min(),
+            mappings.iter().map(|mapping| mapping.source.end).max(),
+        ) else {
+            return format!("{header} no mappings\n");
+        };
+        // Each range covers every column it overlaps, so none disappear.
+        let span = (high - low).max(1) as u128;
+        let scale = |value: u64| (value - low) as u128 * width as u128;
+        let columns = |range: &Range<u64>| {
+            (scale(range.start) / span) as usize..scale(range.end).div_ceil(span) as usize
+        };
+
+        let labels: Vec<String> = mappings.iter()
+            .map(|mapping| format!("{}..{}", mapping.source.start, mapping.source.end))
+            .collect();
+        let label_width = labels.iter().map(String::len).max().unwrap().max("coverage".len());
+
+        let mut out = format!("{header} {low}..{high}\n");
+        for (mapping, label) in mappings.iter().zip(&labels) {
+            let mut bar = vec![' '; width];
+            if !mapping.source.is_empty() {
+                bar[columns(&mapping.source)].fill('#');
+            }
+            let offset = mapping.destination_start as i128 - mapping.source.start as i128;
+            out.push_str(&format!("  {label:<label_width$} |{}| {offset:+}\n", bar.iter().collect::<String>()));
+        }
+
+        let mut coverage = vec![' '; width];
+        for range in merge_ranges(mappings.iter().map(|mapping| mapping.source.clone()).collect()) {
+            coverage[columns(&range)].fill('#');
+        }
+        for (i, a) in mappings.iter().enumerate() {
+            for b in &mappings[i + 1..] {
+                let overlap = a.source.start.max(b.source.start)..a.source.end.min(b.source.end);
+                if !overlap.is_empty() {
+                    coverage[columns(&overlap)].fill('!');
+                }
+            }
+        }
+        out.push_str(&format!("  {:<label_width$} |{}|\n", "coverage", coverage.iter().collect::<String>()));
+        out
+    }
+
+    /// Find every source value that maps to the destination value, in
+    /// ascending order.
+    pub fn preimages(&self, destination: u64) -> Vec<u64> {
+        let mut sources: Vec<u64> = self.mappings.iter()
+            .filter(|mapping| mapping.destination_range().contains(&destination))
+            .map(|mapping| mapping.source.start + (destination - mapping.destination_start))
+            .collect();
+        if self.find_mapping(destination).is_none() {
+            sources.push(destination);
+        }
+        sources.sort();
+        sources.dedup();
+        sources
+    }
+
+    /// Map the input range through the map, splitting it on mapping
+    /// boundaries. Values outside of all mappings map to themselves. Returns
+    /// disjoint output ranges sorted by start.
+    pub fn lookup_range(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let mut unmapped = vec![range];
+        let mut mapped = Vec::new();
+        for mapping in &self.mappings {
+            let mut remaining = Vec::new();
+            for range in unmapped {
+                let before = range.start..range.end.min(mapping.source.start);
+                let overlap = range.start.max(mapping.source.start)..range.end.min(mapping.source.end);
+                let after = range.start.max(mapping.source.end)..range.end;
+                if !before.is_empty() {
+                    remaining.push(before);
+                }
+                if !overlap.is_empty() {
+                    let start = mapping.destination_start + (overlap.start - mapping.source.start);
+                    mapped.push(start..start + (overlap.end - overlap.start));
+                }
+                if !after.is_empty() {
+                    remaining.push(after);
+                }
+            }
+            unmapped = remaining;
+        }
+        mapped.extend(unmapped);
+        merge_ranges(mapped)
+    }
+
+    /// Map all the ranges through the map. Returns disjoint output ranges
+    /// sorted by start.
+    pub fn lookup_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        merge_ranges(ranges.iter().flat_map(|range| self.lookup_range(range.clone())).collect())
+    }
+}
+
+impl From<Vec<Map>> for Mappings {
+    fn from(maps: Vec<Map>) -> Mappings {
+        Mappings::new(maps)
+    }
+}
+
+impl Serialize for Mappings {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.maps)
+    }
+}
+
+/// Parses the maps section of the almanac.
+impl FromStr for Mappings {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Mappings> {
+        read_all_maps(&mut aoc_utils::lines::lines(s.as_bytes()), false, &mut LinePolicy::strict())
+    }
+}
+
+/// Parses a map header followed by its mappings.
+impl FromStr for Map {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Map> {
+        let mut line_iter = aoc_utils::lines::lines(s.as_bytes());
+        let map = read_map(&mut line_iter, false, &mut LinePolicy::strict())?
+            .ok_or_else(|| AocError::missing("a map"))?;
+        if line_iter.any(|line| line.is_ok_and(|line| !line.is_empty())) {
+            return Err(AocError::parse(format!(
+                "Unexpected text after the '{}-to-{} map:' map",
+                map.source, map.destination
+            )));
+        }
+        Ok(map)
+    }
+}
+
+/// Formats the mapping as it appears in the almanac.
+impl fmt::Display for Mapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.destination_start, self.source.start, self.source.end - self.source.start)
+    }
+}
+
+impl Mapping {
+    fn destination_range(&self) -> Range<u64> {
+        self.destination_start..self.destination_start + (self.source.end - self.source.start)
+    }
+
+}
+
+/// Parses a mapping as it appears in the almanac: "destination source length".
+impl FromStr for Mapping {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Mapping> {
+        let [destination, source, length] =
+            small::fields(s).ok_or_else(|| AocError::parse("Mapping must have 3 parts"))?;
+
+        let destination_start: u64 = destination
+            .parse()
+            .with_context(|| format!("Mapping destination '{destination}' must be a number"))?;
+        let source_start: u64 = source
+            .parse()
+            .with_context(|| format!("Mapping source start '{source}' must be a number"))?;
+        let length: u64 = length
+            .parse()
+            .with_context(|| format!("Mapping length '{length}' must be a number"))?;
+
+        // With both ends in range, mapping a value can't overflow either.
+        let source_end = source_start
+            .checked_add(length)
+            .ok_or_else(|| AocError::overflow(format!("Mapping source {source_start} + length {length} overflows")))?;
+        destination_start
+            .checked_add(length)
+            .ok_or_else(|| {
+                AocError::overflow(format!("Mapping destination {destination_start} + length {length} overflows"))
+            })?;
+
+        Ok(Mapping {
+            source: source_start..source_end,
+            destination_start,
+        })
+    }
+}
+
+impl TryFrom<String> for Mapping {
+    type Error = AocError;
+
+    fn try_from(s: String) -> Result<Mapping> {
+        s.parse()
+    }
+}
+
+impl From<Mapping> for String {
+    fn from(mapping: Mapping) -> String {
+        mapping.to_string()
+    }
+}
+
+/// Day 5's solution: the smallest location of the seeds in part 1, and of
+/// the seed ranges in part 2.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Day05;
+
+impl Solver for Day05 {
+    /// The seeds and the maps.
+    type Parsed = (Vec<u64>, Mappings);
+
+    fn parse(&self, input: &str) -> Result<(Vec<u64>, Mappings)> {
+        // The lines are only needed until the maps are built.
+        let arena = Bump::new();
+        let mut lines = aoc_utils::lines::lines(input.as_bytes()).in_arena(&arena);
+        read_almanac(&mut lines, false, &mut LinePolicy::strict())
+    }
+
+    fn part1(&self, (seeds, maps): &(Vec<u64>, Mappings), _: &Cancel) -> Result<Answer> {
+        let seed = find_seed_with_smallest_location(seeds.clone(), maps)?;
+        Ok(maps.lookup_seed_location(seed)?.into())
+    }
+
+    fn part2(&self, (seeds, maps): &(Vec<u64>, Mappings), _: &Cancel) -> Result<Answer> {
+        Ok(find_smallest_location_for_seed_ranges(&seed_ranges(seeds)?, maps)?.into())
+    }
+}
+
+/// From all the given seeds, lookup the locations to find the one with the smallest location.
+pub fn find_seed_with_smallest_location(seeds: Vec<u64>, maps: &Mappings) -> Result<u64> {
+    if seeds.is_empty() {
+        return Err(AocError::no_solution("No seeds"));
+    }
+    let location = maps.lookup_seed_location(seeds[0])?;
+    let mut smallest = (seeds[0], location);
+    for seed in seeds.iter().skip(1) {
+        let location = maps.lookup_seed_location(*seed)?;
+        if location < smallest.1 {
+            smallest = (*seed, location);
+        }
+    }
+
+    Ok(smallest.0)
+}
+
+/// Resolve overlapping mappings with the first in input order applying
+/// where they overlap: each mapping keeps the parts of its source range no
+/// earlier one covers, as a mapping each, and is dropped if none are left.
+fn first_wins(mappings: Vec<Mapping>) -> Vec<Mapping> {
+    let mut resolved: Vec<Mapping> = Vec::new();
+    for mapping in mappings {
+        let mut parts: Vec<Range<u64>> = [mapping.source.clone()].into_iter().filter(|part| !part.is_empty()).collect();
+        for earlier in &resolved {
+            parts = parts
+                .into_iter()
+                .flat_map(|part| {
+                    [part.start..part.end.min(earlier.source.start), part.start.max(earlier.source.end)..part.end]
+                })
+                .filter(|part| !part.is_empty())
+                .collect();
+        }
+        resolved.extend(parts.into_iter().map(|part| Mapping {
+            destination_start: mapping.destination_start + (part.start - mapping.source.start),
+            source: part,
+        }));
+    }
+    resolved
+}
+
+/// Sort the ranges and combine any that overlap or touch.
+fn merge_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Find the smallest location reachable from any seed in the ranges.
+pub fn find_smallest_location_for_seed_ranges(seeds: &[Range<u64>], maps: &Mappings) -> Result<u64> {
+    maps.lookup_seed_location_ranges(seeds)?
+        .iter()
+        .map(|range| range.start)
+        .min()
+        .ok_or_else(|| AocError::no_solution("No seeds"))
+}
+
+/// Find the smallest location for the seed ranges with every strategy,
+/// failing unless they agree. `on_progress` is called as the brute force,
+/// which is slow on a real input, looks up the seeds. The reverse search
+/// and the brute force stop with `AocError::Cancelled` once `cancel` is.
+pub fn check_smallest_location_for_seed_ranges(
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    on_progress: &(dyn Fn(u64) + Sync),
+    cancel: &Cancel,
+) -> Result<u64> {
+    let brute_force = || {
+        let search = brute_force::search(seeds, maps, on_progress, &|| cancel.is_cancelled())?;
+        if !search.is_complete() {
+            return Err(AocError::Cancelled);
+        }
+        search.smallest.ok_or_else(|| AocError::no_solution("No seeds"))
+    };
+    check::agree(&[
+        ("intervals", &|| find_smallest_location_for_seed_ranges(seeds, maps)),
+        ("reverse", &|| reverse::smallest_location(seeds, maps, cancel)),
+        ("brute force", &brute_force),
+    ])
+}
+
+/// Interpret the seeds as pairs of range start and length.
+pub fn seed_ranges(seeds: &[u64]) -> Result<Vec<Range<u64>>> {
+    if !seeds.len().is_multiple_of(2) {
+        return Err(AocError::parse(format!(
+            "Seed ranges must be pairs of start and length, but there are {} numbers",
+            seeds.len()
+        )));
+    }
+    seeds
+        .chunks(2)
+        .map(|pair| {
+            let end = pair[0]
+                .checked_add(pair[1])
+                .ok_or_else(|| AocError::overflow(format!("Seed range {} + length {} overflows", pair[0], pair[1])))?;
+            Ok(pair[0]..end)
+        })
+        .collect()
+}
+
+/// Read the seeds, the blank line after them, and the maps. Map lines that
+/// don't parse are handled by `policy`, as by [`read_all_maps`]. The lines
+/// may be `String`s or borrowed, as from an arena.
+pub fn read_almanac<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L>>,
+    allow_overlaps: bool,
+    policy: &mut LinePolicy,
+) -> Result<(Vec<u64>, Mappings)> {
+    let seeds = line_iter.next().ok_or_else(|| AocError::missing("the seeds"))??;
+    policy.next_line();
+    let seeds = read_seeds(seeds.as_ref()).map_err(|error| error.context("Error reading seeds").at_line(1))?;
+    let line = line_iter.next().ok_or_else(|| AocError::missing("the maps after the seeds"))??;
+    let line = line.as_ref();
+    policy.next_line();
+    if !line.is_empty() {
+        policy.reject(format!("Expected a blank line after the seeds, found '{line}'"))?;
+    }
+
+    let maps = read_all_maps(line_iter, allow_overlaps, policy)?;
+    Ok((seeds, maps))
+}
+
+/// Check the whole almanac, collecting all the errors: every line must parse,
+/// the seeds must pair up into ranges, and the maps must lead from seed to
+/// location.
+pub fn validate(reader: impl BufRead, allow_overlaps: bool) -> Validation {
+    lenient::validate(|policy| {
+        let (seeds, maps) = read_almanac(&mut aoc_utils::lines::lines(reader), allow_overlaps, policy)?;
+        seed_ranges(&seeds)?;
+        maps.seed_to_location_chain()?;
+        Ok(())
+    })
+}
+
+/// Read a line of the form "seeds: 1 2 3" and return a vector of the seeds.
+pub fn read_seeds(line: &str) -> Result<Vec<u64>> {
+    let Some(line) = line.strip_prefix("seeds:") else {
+        return Err(AocError::parse("Line must start with 'seeds:'"));
+    };
+
+    let mut seeds = Vec::new();
+    for seed in line.split_ascii_whitespace() {
+        let seed = seed.parse()
+            .with_context(|| format!("Seed '{}' must be a number", seed))?;
+        seeds.push(seed);
+    }
+
+    Ok(seeds)
+}
+
+/// Read all maps in the file. Unless `allow_overlaps` is set, it is an error
+/// for the mappings in a map to overlap. Lines that don't parse are handled
+/// by `policy`.
+pub fn read_all_maps<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L>>,
+    allow_overlaps: bool,
+    policy: &mut LinePolicy,
+) -> Result<Mappings> {
+    let mut maps = Vec::new();
+    while let Some(map) = read_map(line_iter, allow_overlaps, policy)? {
+        maps.push(map);
+    }
+
+    Ok(Mappings::new(maps))
+}
+
+/// Reads the map header and mappings until EOF or a blank line. The mappings
+/// are sorted and checked for overlaps as by [`Map::new`]. When `policy` is
+/// lenient, a header that doesn't parse is skipped along with its mappings,
+/// and so are mappings that don't parse.
+pub fn read_map<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L>>,
+    allow_overlaps: bool,
+    policy: &mut LinePolicy,
+) -> Result<Option<Map>> {
+    let (header, source, destination) = loop {
+        let Some(header) = line_iter.next() else {
+            return Ok(None);
+        };
+        let header = header?;
+        let header_line = policy.next_line();
+        let parsed = parse_map_header(header.as_ref())
+            .with_context(|| format!("Error parsing map header '{}'", header.as_ref()));
+        if let Some((source, destination)) = policy.check(parsed)? {
+            break (header, source, destination);
+        }
+        // A stray blank line is skipped on its own, a bad header with its
+        // section.
+        if !header.as_ref().is_empty() {
+            skip_section(line_iter, policy, header_line)?;
+        }
+    };
+    let header = header.as_ref();
+
+    let mut mappings = Vec::new();
+    for line in line_iter {
+        let line = line?;
+        let line = line.as_ref();
+        policy.next_line();
+        if line.is_empty() {
+            break;
+        }
+
+        let mapping = line.parse()
+            .with_context(|| format!("Error parsing mapping '{line}' for '{header}'"));
+        if let Some(mapping) = policy.check(mapping)? {
+            mappings.push(mapping);
+        }
+    }
+
+    Map::new(source, destination, mappings, allow_overlaps).map(Some)
+}
+
+/// Skip the lines up to a blank line after the header on `header_line`
+/// didn't parse.
+fn skip_section<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L>>,
+    policy: &mut LinePolicy,
+    header_line: usize,
+) -> Result<()> {
+    for line in line_iter {
+        let line = line?;
+        let line = line.as_ref();
+        policy.next_line();
+        if line.is_empty() {
+            break;
+        }
+        policy.reject(format!("Mapping '{line}' is under the skipped header on line {header_line}"))?;
+    }
+    Ok(())
+}
+
+/// Parse a map header of the form "source-to-destination map:".
+/// Returns (source, destination).
+fn parse_map_header(line: &str) -> Result<(String, String)> {
+    if !line.ends_with(" map:") {
+        return Err(AocError::parse("Map header must end with ' map:'"));
+    }
+    let line = &line[..line.len() - " map:".len()];
+    let parts: Vec<&str> = line.split('-').collect();
+    if parts.len() != 3 || parts[1] != "to" {
+        return Err(AocError::parse("Map header must be in the format 'source-to-destination map:'"));
+    }
+
+    Ok((parts[0].to_string(), parts[2].to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{vec, io::Cursor};
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EXAMPLE_MAPS: &str = r#"seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+"#;
+
+    pub(crate) fn example_maps() -> Mappings {
+        let cursor = Cursor::new(EXAMPLE_MAPS);
+        let mut line_iter = aoc_utils::lines::lines(cursor);
+        read_all_maps(&mut line_iter, false, &mut LinePolicy::strict()).unwrap()
+    }
+
+    #[test]
+    fn test_seeds() {
+        assert_eq!(read_seeds("seeds: 1 2 3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(read_seeds("seeds: 1").unwrap(), vec![1]);
+        assert_eq!(read_seeds("seeds: ").unwrap(), Vec::<u64>::new());
+        assert_eq!(read_seeds("seeds:").unwrap(), Vec::<u64>::new());
+        assert!(read_seeds("seeds: a").is_err());
+        assert!(read_seeds("seeds: 1 a").is_err());
+    }
+
+    #[test]
+    fn test_read_map() {
+        let mut line_iter = vec![
+            Ok("seed-to-soil map:".to_string()),
+            Ok("50 98 2".to_string()),
+            Ok("52 50 48".to_string()),
+            Ok("".to_string()),
+        ]
+            .into_iter();
+        let map = read_map(&mut line_iter, false, &mut LinePolicy::strict()).unwrap().unwrap();
+        assert_eq!(map.source, "seed".to_string());
+        assert_eq!(map.destination, "soil".to_string());
+        assert_eq!(map.mappings, vec![
+            Mapping {
+                source: 50..98,
+                destination_start: 52,
+            },
+            Mapping {
+                source: 98..100,
+                destination_start: 50,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_read_map_overlaps() {
+        let lines = || ["seed-to-soil map:", "0 10 5", "50 98 2", "20 12 3"].into_iter().map(|line| Ok(line.to_string()));
+        let error = read_map(&mut lines(), false, &mut LinePolicy::strict()).unwrap_err().to_string();
+        assert_eq!(error, "Mappings '0 10 5' and '20 12 3' for 'seed-to-soil map:' overlap");
+        // The first mapping covers all of the last, which is dropped.
+        let map = read_map(&mut lines(), true, &mut LinePolicy::strict()).unwrap().unwrap();
+        assert_eq!(map.mappings.len(), 2);
+        assert_eq!(map.lookup(13), 3);
+
+        // Touching ranges don't overlap.
+        let mut line_iter = ["seed-to-soil map:", "0 10 5", "20 15 3"].into_iter().map(|line| Ok(line.to_string()));
+        assert!(read_map(&mut line_iter, false, &mut LinePolicy::strict()).is_ok());
+    }
+
+    #[test]
+    fn test_read_map_overflow() {
+        let mut line_iter = ["seed-to-soil map:", "0 18446744073709551615 1"].into_iter().map(|line| Ok(line.to_string()));
+        let error = format!("{:#}", read_map(&mut line_iter, false, &mut LinePolicy::strict()).unwrap_err());
+        assert_eq!(
+            error,
+            "Error on line 2: Error parsing mapping '0 18446744073709551615 1' for 'seed-to-soil map:': \
+            Mapping source 18446744073709551615 + length 1 overflows"
+        );
+
+        let mut line_iter = ["seed-to-soil map:", "18446744073709551610 0 10"].into_iter().map(|line| Ok(line.to_string()));
+        assert!(read_map(&mut line_iter, false, &mut LinePolicy::strict()).is_err());
+    }
+
+    #[test]
+    fn test_read_map_read_error() {
+        let input = &b"seed-to-soil map:\n50 98 2\n52 \xff0 48\n"[..];
+        let mut line_iter = aoc_utils::lines::lines(input).strict_utf8(true);
+        let error = read_map(&mut line_iter, false, &mut LinePolicy::strict()).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 3, column 4: Invalid UTF-8 at byte offset 29");
+
+        let mut line_iter = aoc_utils::lines::lines(input);
+        let error = read_map(&mut line_iter, false, &mut LinePolicy::strict()).unwrap_err();
+        assert!(error.to_string().contains("Mapping source start '\u{fffd}0' must be a number"), "{error}");
+    }
+
+    #[test]
+    fn test_from_str() {
+        let mapping: Mapping = "52 50 48".parse().unwrap();
+        assert_eq!(mapping, Mapping { source: 50..98, destination_start: 52 });
+        assert!("52 50".parse::<Mapping>().is_err());
+
+        let map: Map = "seed-to-soil map:\n50 98 2\n52 50 48\n".parse().unwrap();
+        assert_eq!(map.source(), "seed");
+        assert_eq!(map.mappings[0], mapping);
+        assert!("seed-to-soil map:\n50 98 2\n\nsoil-to-water map:\n".parse::<Map>().is_err());
+        assert!("".parse::<Map>().is_err());
+
+        let maps: Mappings = EXAMPLE_MAPS.parse().unwrap();
+        assert_eq!(maps.lookup_seed_location(79).unwrap(), 82);
+    }
+
+    #[test]
+    fn test_solver() {
+        let parsed = Day05.parse(&format!("seeds: 79 14 55 13\n\n{EXAMPLE_MAPS}")).unwrap();
+        assert_eq!(Day05.part1(&parsed, &Cancel::new()).unwrap(), Answer::Unsigned(35));
+        assert_eq!(Day05.part2(&parsed, &Cancel::new()).unwrap(), Answer::Unsigned(46));
+        assert!(Day05.parse("seeds: 1 2\n").is_err());
+    }
+
+    aoc_utils::aoc_example_tests!(Day05, part1 = 35, part2 = 46, input = "fixtures/example.txt");
+
+    #[test]
+    fn test_crlf_input() {
+        let input = format!("\u{feff}seeds: 79 14 55 13 \r\n \r\n{}", EXAMPLE_MAPS.replace('\n', " \r\n"));
+        let mut line_iter = aoc_utils::lines::lines(input.as_bytes());
+        assert_eq!(read_seeds(&line_iter.next().unwrap().unwrap()).unwrap(), vec![79, 14, 55, 13]);
+        assert_eq!(line_iter.next().unwrap().unwrap(), "");
+        let maps = read_all_maps(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+        assert_eq!(maps.lookup_seed_location(79).unwrap(), 82);
+
+        let maps: Mappings = EXAMPLE_MAPS.replace('\n', "\r\n").parse().unwrap();
+        assert_eq!(maps.lookup_seed_location(13).unwrap(), 35);
+    }
+
+    #[test]
+    fn test_serde() {
+        let map: Map = "seed-to-soil map:\n50 98 2\n52 50 48\n".parse().unwrap();
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"source":"seed","destination":"soil","mappings":["52 50 48","50 98 2"]}"#);
+
+        let maps = example_maps();
+        let json = serde_json::to_string(&maps).unwrap();
+        let maps: Mappings = serde_json::from_str(&json).unwrap();
+        assert_eq!(maps.lookup_seed_location(79).unwrap(), 82);
+        assert_eq!(serde_json::to_string(&maps).unwrap(), json);
+
+        let overlapping = r#"{"source":"seed","destination":"soil","mappings":["0 10 5","20 12 3"]}"#;
+        assert!(serde_json::from_str::<Map>(overlapping).is_err());
+        assert!(serde_json::from_str::<Mapping>(r#""1 2""#).is_err());
+    }
+
+    #[test]
+    fn test_read_all_maps_lenient() {
+        let input = "seed-to-soil map:\n50 98 2\n52 50\n\n\nsoil-to-water mpa:\n1 2 3\n\nwater-to-light map:\n1 2 3\n";
+        let lines = || input.lines().map(|line| Ok(line.to_string()));
+        let error = read_all_maps(&mut lines(), false, &mut LinePolicy::strict()).err().unwrap();
+        assert!(error.to_string().starts_with("Error on line 3"), "{error:#}");
+
+        let mut policy = LinePolicy::lenient();
+        let maps = read_all_maps(&mut lines(), false, &mut policy).unwrap();
+        assert_eq!(maps.get("seed").unwrap().mappings.len(), 1);
+        assert!(maps.get("soil").is_none());
+        assert_eq!(maps.get("water").unwrap().mappings.len(), 1);
+        let skipped: Vec<usize> = policy.skipped().iter().map(|skipped| skipped.line_number).collect();
+        assert_eq!(skipped, [3, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let input = format!("seeds: 79 14 55 13\n\n{EXAMPLE_MAPS}");
+        assert!(validate(input.as_bytes(), false).is_valid());
+
+        let input = "seeds: 1 2 3\nseed-to-soil map:\n1 2\n\nsoil-to-water map:\n1 2 3\n";
+        let validation = validate(input.as_bytes(), false);
+        assert_eq!(validation.lines.iter().map(|line| line.line_number).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(
+            validation.error.unwrap().to_string(),
+            "Seed ranges must be pairs of start and length, but there are 3 numbers"
+        );
+    }
+
+    #[test]
+    fn test_read_all_maps() {
+        let mut line_iter = vec![
+            Ok("seed-to-soil map:".to_string()),
+            Ok("50 98 2".to_string()),
+            Ok("52 50 48".to_string()),
+            Ok("".to_string()),
+            Ok("soil-to-fertilizer map:".to_string()),
+            Ok("0 15 37".to_string()),
+            Ok("37 52 2".to_string()),
+            Ok("39 0 15".to_string()),
+            Ok("".to_string()),
+        ]
+            .into_iter();
+        let maps = read_all_maps(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+        assert_eq!(maps.maps.len(), 2);
+        let seed_soil_map = maps.get("seed").unwrap();
+        assert_eq!(seed_soil_map.source, "seed".to_string());
+        assert_eq!(seed_soil_map.destination, "soil".to_string());
+        assert_eq!(seed_soil_map.mappings, vec![
+            Mapping {
+                source: 50..98,
+                destination_start: 52,
+            },
+            Mapping {
+                source: 98..100,
+                destination_start: 50,
+            },
+        ]);
+        let soil_fertilizer_map = maps.get("soil").unwrap();
+        assert_eq!(soil_fertilizer_map.source, "soil".to_string());
+        assert_eq!(soil_fertilizer_map.destination, "fertilizer".to_string());
+        assert_eq!(soil_fertilizer_map.mappings, vec![
+            Mapping {
+                source: 0..15,
+                destination_start: 39,
+            },
+            Mapping {
+                source: 15..52,
+                destination_start: 0,
+            },
+            Mapping {
+                source: 52..54,
+                destination_start: 37,
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_map_lookup() {
+        let map = Map {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            mappings: vec![
+                Mapping {
+                    source: 50..98,
+                    destination_start: 52,
+                },
+                Mapping {
+                    source: 98..100,
+                    destination_start: 50,
+                },
+            ],
+            overlapping: vec![],
+        };
+        assert_eq!(map.lookup(79), 81);
+        assert_eq!(map.lookup(14), 14);
+        assert_eq!(map.lookup(55), 57);
+        assert_eq!(map.lookup(13), 13);
+        assert_eq!(map.lookup(98), 50);
+        assert_eq!(map.lookup(100), 100);
+        for value in 0..120 {
+            assert_eq!(map.lookup(value), map.lookup_linear(value));
+        }
+    }
+
+    #[test]
+    fn test_lookup_seed_location() {
+        let maps = example_maps();
+        assert_eq!(maps.lookup_seed_location(79).unwrap(), 82);
+        assert_eq!(maps.lookup_seed_location(14).unwrap(), 43);
+        assert_eq!(maps.lookup_seed_location(55).unwrap(), 86);
+        assert_eq!(maps.lookup_seed_location(13).unwrap(), 35);
+    }
+
+    #[test]
+    fn test_trace_seed() {
+        let maps = example_maps();
+        let hops = maps.trace_seed(79).unwrap();
+        let path: Vec<(&str, u64, Option<String>)> = hops.iter()
+            .map(|hop| (hop.map.destination(), hop.destination, hop.mapping.map(Mapping::to_string)))
+            .collect();
+        assert_eq!(path, vec![
+            ("soil", 81, Some("52 50 48".to_string())),
+            ("fertilizer", 81, None),
+            ("water", 81, None),
+            ("light", 74, Some("18 25 70".to_string())),
+            ("temperature", 78, Some("68 64 13".to_string())),
+            ("humidity", 78, None),
+            ("location", 82, Some("60 56 37".to_string())),
+        ]);
+        assert_eq!(hops[0].source, 79);
+        assert_eq!(hops[0].map.source(), "seed");
+    }
+
+    #[test]
+    fn test_seed_ranges() {
+        assert_eq!(seed_ranges(&[79, 14, 55, 13]).unwrap(), vec![79..93, 55..68]);
+        assert_eq!(seed_ranges(&[]).unwrap(), vec![]);
+        assert!(seed_ranges(&[1, 2, 3]).is_err());
+        assert!(seed_ranges(&[u64::MAX, 1]).is_err());
+    }
+
+    #[test]
+    fn test_map_lookup_ranges() {
+        let map = Map {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            mappings: vec![
+                Mapping {
+                    source: 50..98,
+                    destination_start: 52,
+                },
+                Mapping {
+                    source: 98..100,
+                    destination_start: 50,
+                },
+            ],
+            overlapping: vec![],
+        };
+        assert_eq!(map.lookup_ranges(&[79..93, 40..60, 95..105]), vec![40..62, 81..95, 97..105]);
+        assert!(map.lookup_ranges(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_map_lookup_range() {
+        let map = Map {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            mappings: vec![
+                Mapping {
+                    source: 50..98,
+                    destination_start: 52,
+                },
+                Mapping {
+                    source: 98..100,
+                    destination_start: 50,
+                },
+            ],
+            overlapping: vec![],
+        };
+        // Entirely inside one mapping.
+        assert_eq!(map.lookup_range(79..93), vec![81..95]);
+        // Entirely outside the mappings.
+        assert_eq!(map.lookup_range(0..10), vec![0..10]);
+        assert_eq!(map.lookup_range(100..110), vec![100..110]);
+        // Split across both mappings and the identity on either side.
+        assert_eq!(map.lookup_range(60..99), vec![50..51, 62..100]);
+        // Pieces that touch after mapping are merged.
+        assert_eq!(map.lookup_range(45..105), vec![45..105]);
+        assert!(map.lookup_range(5..5).is_empty());
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        assert_eq!(merge_ranges(vec![5..7, 1..3, 2..4, 7..8, 10..10, 12..15]), vec![1..4, 5..8, 12..15]);
+        assert!(merge_ranges(vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_smallest_location_for_seed_ranges() {
+        let maps = example_maps();
+        let seeds = seed_ranges(&[79, 14, 55, 13]).unwrap();
+        assert_eq!(find_smallest_location_for_seed_ranges(&seeds, &maps).unwrap(), 46);
+    }
+
+    #[test]
+    fn test_overlapping_strategies_agree() {
+        // The first mapping covers all of the second, then part of the third.
+        let almanac = "seeds: 70 1 55 45\n\nseed-to-location map:\n1000 0 60\n500 50 10\n200 55 15\n";
+        let (seeds, maps) =
+            read_almanac(&mut aoc_utils::lines::lines(almanac.as_bytes()), true, &mut LinePolicy::strict()).unwrap();
+        assert_eq!(maps.lookup_seed_location(55).unwrap(), 1055);
+        assert_eq!(maps.lookup_seed_location(60).unwrap(), 205);
+        assert_eq!(find_seed_with_smallest_location(seeds.clone(), &maps).unwrap(), 70);
+        let seeds = seed_ranges(&seeds).unwrap();
+        assert_eq!(check_smallest_location_for_seed_ranges(&seeds, &maps, &|_| {}, &Cancel::new()).unwrap(), 70);
+        assert_eq!(check_smallest_location_for_seed_ranges(&seeds[1..], &maps, &|_| {}, &Cancel::new()).unwrap(), 70);
+        assert_eq!(maps.lookup_location_seed(205).unwrap(), 60);
+        assert!(maps.lookup_location_seed(65).is_err());
+    }
+
+    #[test]
+    fn test_map_reverse_lookup() {
+        let map = Map {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            mappings: vec![
+                Mapping {
+                    source: 50..98,
+                    destination_start: 52,
+                },
+                Mapping {
+                    source: 98..100,
+                    destination_start: 50,
+                },
+            ],
+            overlapping: vec![],
+        };
+        assert_eq!(map.reverse_lookup(81), Some(79));
+        assert_eq!(map.reverse_lookup(14), Some(14));
+        assert_eq!(map.reverse_lookup(50), Some(98));
+        assert_eq!(map.reverse_lookup(99), Some(97));
+        assert_eq!(map.reverse_lookup(100), Some(100));
+
+        // 98 is a mapping source, so it doesn't map to itself, and no
+        // mapping has it as a destination either.
+        let map = Map {
+            source: "seed".to_string(),
+            destination: "soil".to_string(),
+            mappings: vec![Mapping {
+                source: 98..100,
+                destination_start: 50,
+            }],
+            overlapping: vec![],
+        };
+        assert_eq!(map.reverse_lookup(98), None);
+    }
+
+    #[test]
+    fn test_map_visualize() {
+        let map: Map = "seed-to-soil map:\n52 50 48\n50 98 2\n".parse().unwrap();
+        assert_eq!(map.visualize(10), "\
+seed-to-soil map: 50..100
+  50..98   |##########| +2
+  98..100  |         #| -48
+  coverage |##########|
+");
+
+        let map = Map::new("a".to_string(), "b".to_string(), vec![
+            "0 0 4".parse().unwrap(),
+            "0 3 3".parse().unwrap(),
+            "0 8 2".parse().unwrap(),
+        ], true).unwrap();
+        assert_eq!(map.visualize(10), "\
+a-to-b map: 0..10
+  0..4     |####      | +0
+  3..6     |   ###    | -3
+  8..10    |        ##| -8
+  coverage |###!##  ##|
+");
+        // Only where a mapping applies is resolved.
+        assert_eq!(map.lookup(3), 3);
+        assert_eq!(map.lookup(4), 1);
+
+        let map: Map = "a-to-b map:\n".parse().unwrap();
+        assert_eq!(map.visualize(10), "a-to-b map: no mappings\n");
+    }
+
+    #[test]
+    fn test_map_preimages() {
+        let map: Map = "a-to-b map:\n50 98 2\n52 50 48\n".parse().unwrap();
+        assert_eq!(map.preimages(10), vec![10]);
+        assert_eq!(map.preimages(51), vec![99]);
+        assert_eq!(map.preimages(52), vec![50]);
+        assert_eq!(map.preimages(98), vec![96]);
+        assert_eq!(map.preimages(99), vec![97]);
+        assert_eq!(map.preimages(100), vec![100]);
+
+        // 5 maps to itself and from 1.
+        let map: Map = "a-to-b map:\n5 1 1\n".parse().unwrap();
+        assert_eq!(map.preimages(5), vec![1, 5]);
+        assert!(map.preimages(1).is_empty());
+    }
+
+    #[test]
+    fn test_lookup_location_seed() {
+        let maps = example_maps();
+        assert_eq!(maps.lookup_location_seed(82).unwrap(), 79);
+        assert_eq!(maps.lookup_location_seed(43).unwrap(), 14);
+        assert_eq!(maps.lookup_location_seed(86).unwrap(), 55);
+        assert_eq!(maps.lookup_location_seed(35).unwrap(), 13);
+        assert_eq!(maps.lookup_location_seed(46).unwrap(), 82);
+        for seed in 0..100 {
+            let location = maps.lookup_seed_location(seed).unwrap();
+            assert_eq!(maps.lookup_location_seed(location).unwrap(), seed);
+        }
+    }
+
+    /// Build maps without any mappings from (source, destination) pairs.
+    fn empty_maps(pairs: &[(&str, &str)]) -> Mappings {
+        Mappings::new(pairs.iter().map(|(source, destination)| {
+            Map {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                mappings: vec![],
+                overlapping: vec![],
+            }
+        }).collect())
+    }
+
+    #[test]
+    fn test_mappings_new() {
+        let maps = empty_maps(&[("water", "light"), ("soil", "water"), ("soil", "location"), ("seed", "soil")]);
+        let order: Vec<&str> = maps.maps.iter().map(Map::source).collect();
+        assert_eq!(order, ["seed", "soil", "water"]);
+        assert_eq!(maps.seed_to_location, Some(2));
+        assert_eq!(maps.get("soil").unwrap().destination(), "location");
+        let soil = maps.category("soil").unwrap();
+        assert_eq!(maps.category_name(soil), "soil");
+        assert_eq!(maps.category("fertilizer"), None);
+    }
+
+    #[test]
+    fn test_chain_missing_map() {
+        let maps = empty_maps(&[("seed", "soil"), ("soil", "water"), ("light", "location")]);
+        let error = maps.lookup_seed_location(1).unwrap_err().to_string();
+        assert_eq!(error, "The input is missing a map for source 'water' after seed -> soil -> water. Maps exist for: light, seed, soil");
+    }
+
+    #[test]
+    fn test_lookup_between() {
+        let maps = example_maps();
+        assert_eq!(maps.lookup_between("soil", "light", 81).unwrap(), 74);
+        assert_eq!(maps.lookup_between("seed", "location", 79).unwrap(), 82);
+        assert_eq!(maps.lookup_between("water", "water", 81).unwrap(), 81);
+        let error = maps.lookup_between("location", "seed", 82).unwrap_err().to_string();
+        assert_eq!(error, "The input is missing a map for source 'location'. Maps exist for: fertilizer, humidity, light, seed, soil, temperature, water");
+    }
+
+    #[test]
+    fn test_chain_cycle() {
+        let maps = empty_maps(&[("seed", "soil"), ("soil", "water"), ("water", "soil")]);
+        let error = maps.lookup_seed_location(1).unwrap_err().to_string();
+        assert_eq!(error, "The maps contain a cycle: seed -> soil -> water -> soil");
+        assert!(maps.lookup_location_seed(1).is_err());
+        assert!(maps.lookup_seed_location_ranges(&[1..2, 5..6]).is_err());
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = example_maps().to_dot();
+        assert!(dot.starts_with("digraph almanac {\n    \"fertilizer\" -> \"water\" [label=\"4\"];\n"));
+        assert!(dot.contains("    \"seed\" -> \"soil\" [label=\"2\"];\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot.lines().count(), 9);
+    }
+
+    #[test]
+    fn test_chain() {
+        let maps = empty_maps(&[("seed", "soil"), ("soil", "location"), ("water", "light")]);
+        assert_eq!(maps.lookup_seed_location(5).unwrap(), 5);
+        assert_eq!(maps.lookup_location_seed(5).unwrap(), 5);
+    }
+
+    /// Small maps from seed to location through two other categories, with
+    /// the mappings in each spaced out so they don't overlap.
+    fn small_maps() -> impl Strategy<Value = Mappings> {
+        let map = prop::collection::vec((0..20u64, 1..30u64, 0..200u64), 0..5);
+        prop::collection::vec(map, 3).prop_map(|maps| {
+            let categories = ["seed", "soil", "water", "location"];
+            let maps = maps.into_iter().enumerate().map(|(i, mappings)| {
+                let mut start = 0;
+                let mappings = mappings
+                    .into_iter()
+                    .map(|(gap, length, destination)| {
+                        start += gap;
+                        let mapping = format!("{destination} {start} {length}").parse().unwrap();
+                        start += length;
+                        mapping
+                    })
+                    .collect();
+                Map::new(categories[i].to_string(), categories[i + 1].to_string(), mappings, false).unwrap()
+            });
+            Mappings::new(maps.collect())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn test_strategies_agree(maps in small_maps(), seeds in prop::collection::vec((0..250u64, 1..40u64), 1..4)) {
+            let seeds: Vec<Range<u64>> = seeds.into_iter().map(|(start, length)| start..start + length).collect();
+            let checked = check_smallest_location_for_seed_ranges(&seeds, &maps, &|_| {}, &Cancel::new());
+            prop_assert!(checked.is_ok(), "{:?}", checked);
+        }
+
+        #[test]
+        fn test_read_seeds_any_line(line in "\\PC{0,60}") {
+            let _ = read_seeds(&line);
+        }
+
+        #[test]
+        fn test_read_seeds_valid(seeds in prop::collection::vec(any::<u64>(), 0..20)) {
+            let line = format!("seeds: {}", seeds.iter().map(u64::to_string).collect::<Vec<_>>().join(" "));
+            prop_assert_eq!(read_seeds(&line).unwrap(), seeds);
+        }
+
+        #[test]
+        fn test_mapping_any_line(line in "\\PC{0,60}") {
+            let _ = line.parse::<Mapping>();
+        }
+
+        #[test]
+        fn test_mapping_near_valid(line in "( {0,2}[0-9]{1,21}){0,4} {0,2}") {
+            if let Ok(mapping) = line.parse::<Mapping>() {
+                prop_assert_eq!(mapping.to_string().parse::<Mapping>().unwrap(), mapping);
+            }
+        }
+
+        #[test]
+        fn test_mapping_valid(destination in any::<u64>(), source in any::<u64>(), length in any::<u64>()) {
+            let length = length % (u64::MAX - destination.max(source) + 1);
+            let line = format!("{destination} {source} {length}");
+            let mapping: Mapping = line.parse().unwrap();
+            prop_assert_eq!(mapping.to_string(), line);
+            let json = serde_json::to_string(&mapping).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Mapping>(&json).unwrap(), mapping);
+        }
+    }
+}
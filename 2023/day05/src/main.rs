@@ -1,329 +1,360 @@
-use std::collections::HashMap;
 use std::ops::Range;
-use std::io::{self, BufRead};
-
-use anyhow::{anyhow, Result, Context};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use aoc_utils::arena::Bump;
+use aoc_utils::cancel::Cancel;
+use aoc_utils::checkpoint::Checkpoint;
+use aoc_utils::error::AocError;
+use aoc_utils::interrupt::{self, Interrupted};
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::lines::str_lines;
+use aoc_utils::output::{ColorChoice, Format, Output, Timings};
+use aoc_utils::progress::{self, ProgressBar};
+use aoc_utils::source::InputArg;
+use clap::{Parser, ValueEnum};
+
+use day05::{
+    brute_force, check_smallest_location_for_seed_ranges, find_seed_with_smallest_location,
+    find_smallest_location_for_seed_ranges, read_almanac, reverse, seed_ranges, validate, Mappings,
+};
+
+#[cfg(feature = "fast-alloc")]
+#[global_allocator]
+static ALLOC: aoc_utils::alloc::MiMalloc = aoc_utils::alloc::MiMalloc;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Emit {
+    /// A Graphviz graph of the maps between categories.
+    Dot,
+    /// The parsed maps as JSON.
+    Json,
+}
 
-struct Mappings (HashMap<String, Map>);
+#[derive(Clone, Copy, Default, ValueEnum)]
+enum Strategy {
+    /// Map whole seed ranges through the maps.
+    #[default]
+    Intervals,
+    /// Look up every seed.
+    BruteForce,
+    /// Map locations back to seeds, from 0 up, until one is in the ranges.
+    /// This tries as many locations as the answer, so it's slow on a real
+    /// input; Ctrl-C stops it.
+    Reverse,
+    /// Use every strategy, failing unless they agree. The brute force and
+    /// the reverse search make this slow on a real input; Ctrl-C stops it.
+    Check,
+}
 
-struct Map {
-    source: String,
-    destination: String,
-    mappings: Vec<Mapping>,
+#[derive(Parser)]
+struct Args {
+    /// How to solve part 2.
+    #[arg(long, value_enum, default_value_t)]
+    strategy: Strategy,
+    /// Short for --strategy brute-force.
+    #[arg(long, conflicts_with = "strategy")]
+    brute_force: bool,
+    /// Short for --strategy check.
+    #[arg(long, conflicts_with_all = ["strategy", "brute_force"])]
+    check: bool,
+    /// Accept maps whose mappings overlap, rather than failing. Where they
+    /// overlap, the first in the input applies.
+    #[arg(long)]
+    allow_overlaps: bool,
+    /// Skip map headers and mappings that don't parse, with a warning,
+    /// instead of failing.
+    #[arg(long)]
+    lenient: bool,
+    /// Check the whole input, reporting every error, instead of solving.
+    #[arg(long)]
+    validate_only: bool,
+    /// Fail on input that isn't UTF-8, giving the offset of the first bad
+    /// byte, instead of replacing the bad bytes.
+    #[arg(long)]
+    strict_utf8: bool,
+    /// Print only the answer, for scripts.
+    #[arg(short, long, conflicts_with = "format")]
+    quiet: bool,
+    /// How to print the answer.
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Print how long reading the almanac and solving each part took.
+    #[arg(long)]
+    time: bool,
+    /// When to color the output.
+    #[arg(long, value_enum, default_value_t, value_name = "WHEN")]
+    color: ColorChoice,
+    #[command(flatten)]
+    input: InputArg,
+    /// Map the input file into memory, or read the whole input, and parse
+    /// its lines in place rather than reading them one at a time.
+    #[arg(long)]
+    mmap: bool,
+    /// Print how a seed is mapped through each category instead of solving.
+    #[arg(long, value_name = "SEED")]
+    explain: Vec<u64>,
+    /// Draw each map's ranges instead of solving, marking gaps and overlaps.
+    /// Overlapping mappings are drawn rather than rejected.
+    #[arg(long)]
+    visualize: bool,
+    /// Print a description of the maps instead of solving.
+    #[arg(long)]
+    emit: Option<Emit>,
+    /// Look up values from this category instead of solving.
+    #[arg(long, requires_all = ["to", "values"])]
+    from: Option<String>,
+    /// The category to look up values in.
+    #[arg(long, requires = "from")]
+    to: Option<String>,
+    /// The values to look up with --from and --to.
+    #[arg(requires = "from")]
+    values: Vec<u64>,
+    /// Save the brute force's progress to this file every few seconds and
+    /// on Ctrl-C, and resume from it if it's there. It's removed once the
+    /// search finishes.
+    #[arg(long, value_name = "FILE")]
+    checkpoint: Option<PathBuf>,
+    /// Brute force part 2 on the GPU, falling back to the CPU without one.
+    #[cfg(feature = "gpu")]
+    #[arg(long, conflicts_with = "checkpoint")]
+    gpu: bool,
 }
 
-#[derive(Debug, PartialEq)]
-struct Mapping {
-    source: Range<u64>,
-    destination_start: u64,
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let output = Output::new(args.quiet).show_time(args.time).color(args.color).format(args.format);
+    output.exit(run(&args, output))
 }
 
-impl Mappings {
-    /// Traverse all the maps to find the location for the given seed.
-    fn lookup_seed_location(&self, seed: u64) -> Result<u64> {
-        let mut key = "seed".to_string();
-        let mut value = seed;
-        loop {
-            let map = self.0.get(&key)
-                .ok_or_else(|| anyhow!("No map for source '{key}'"))?;
-            key = map.destination.to_owned();
-            value = map.lookup(value);
-            if key == "location" {
-                return Ok(value);
-            }
-        }
+fn run(args: &Args, output: Output) -> Result<()> {
+    if args.validate_only {
+        return validate(args.input.source.open()?, args.allow_overlaps).print();
     }
-}
+    let arena = Bump::new();
+    let mapped;
+    let text;
+    let mut line_iter: Box<dyn Iterator<Item = aoc_utils::error::Result<&str>>> = if args.mmap {
+        mapped = args.input.source.map()?;
+        text = mapped.text(args.strict_utf8)?;
+        Box::new(str_lines(&text))
+    } else {
+        Box::new(args.input.source.lines()?.strict_utf8(args.strict_utf8).in_arena(&arena))
+    };
+    let mut policy = LinePolicy::new(args.lenient);
 
-impl Map {
-    fn lookup(&self, source: u64) -> u64 {
-        let mapping = self.mappings.iter()
-            .find(|mapping| mapping.source.contains(&source));
-        match mapping {
-            Some(mapping) => mapping.destination_start + (source - mapping.source.start),
-            None => source,
+    let mut timings = Timings::new();
+    let allow_overlaps = args.allow_overlaps || args.visualize;
+    let (seeds, maps) =
+        timings.time("read and parse", || read_almanac(&mut line_iter, allow_overlaps, &mut policy))?;
+    policy.report();
+
+    if args.visualize {
+        print!("{}", maps.visualize(60));
+        return Ok(());
+    }
+    match args.emit {
+        Some(Emit::Dot) => {
+            print!("{}", maps.to_dot());
+            return Ok(());
         }
+        Some(Emit::Json) => {
+            println!("{}", serde_json::to_string_pretty(&maps)?);
+            return Ok(());
+        }
+        None => {}
     }
-}
-
-impl Mapping {
-    fn from_str(s: &str) -> Result<Mapping> {
-        let parts: Vec<&str> = s.split_ascii_whitespace().collect();
-        if parts.len() != 3 {
-            return Err(anyhow!("Mapping must have 3 parts"));
+    if let (Some(from), Some(to)) = (&args.from, &args.to) {
+        for &value in &args.values {
+            println!("{from} {value} -> {to} {}", maps.lookup_between(from, to, value)?);
         }
-
-        let destination_start: u64 = parts[0]
-            .parse()
-            .with_context(|| format!("Mapping destination '{}' must be a number", parts[0]))?;
-        let source_start: u64 = parts[1]
-            .parse()
-            .with_context(|| format!("Mapping source start '{}' must be a number", parts[1]))?;
-        let length: u64 = parts[2]
-            .parse()
-            .with_context(|| format!("Mapping length '{}' must be a number", parts[2]))?;
-
-        Ok(Mapping {
-            source: source_start..source_start + length,
-            destination_start,
-        })
+        return Ok(());
+    }
+    if !args.explain.is_empty() {
+        for &seed in &args.explain {
+            explain(&maps, seed)?;
+        }
+        return Ok(());
     }
-}
-
-fn main() -> Result<()> {
-    let stdin = std::io::stdin();
-    let mut line_iter = stdin.lock().lines();
-
-    let seeds = read_seeds(&line_iter.next().unwrap().unwrap())
-        .context("Error reading seeds")?;
-    let line = line_iter.next().unwrap().unwrap();
-    assert_eq!(line, "", "Expected blank line after seeds");
-
-    let maps = read_all_maps(&mut line_iter)?;
 
-    let smallest = find_seed_with_smallest_location(seeds, &maps)?;
-    println!("Seed with smallest location: {}", smallest);
-    println!("Smallest location: {}", maps.lookup_seed_location(smallest).unwrap());
+    let smallest = timings.time("part 1", || find_seed_with_smallest_location(seeds.clone(), &maps))?;
+    output.detail(format!("Seed with smallest location: {smallest}"));
+    output.answer("Smallest location", maps.lookup_seed_location(smallest)?);
+
+    let start = Instant::now();
+    let seed_ranges = seed_ranges(&seeds)?;
+    let strategy = if args.check {
+        Strategy::Check
+    } else if args.brute_force || use_gpu(args) {
+        Strategy::BruteForce
+    } else {
+        args.strategy
+    };
+    let smallest = match strategy {
+        Strategy::Intervals => find_smallest_location_for_seed_ranges(&seed_ranges, &maps)?,
+        Strategy::BruteForce => {
+            let total = brute_force::total(&seed_ranges);
+            let progress = progress::bar(total);
+            let smallest = match gpu_smallest_location(args, &seed_ranges, &maps, &progress)? {
+                Some(smallest) => smallest,
+                None => {
+                    interrupt::install(|| {})?;
+                    let search = match &args.checkpoint {
+                        Some(path) => checkpointed_search(path, &seed_ranges, &maps, &progress)?,
+                        None => {
+                            let on_progress = |count| progress.inc(count);
+                            brute_force::search(&seed_ranges, &maps, &on_progress, &interrupt::interrupted)?
+                        }
+                    };
+                    if !search.is_complete() {
+                        progress.finish_and_clear();
+                        report_interrupted(&search);
+                        return Err(Interrupted.into());
+                    }
+                    search.smallest.ok_or_else(|| anyhow!("No seeds"))?
+                }
+            };
+            progress.finish_and_clear();
+            smallest
+        }
+        Strategy::Reverse => unless_cancelled(reverse::smallest_location(&seed_ranges, &maps, &cancel_on_interrupt()?))?,
+        Strategy::Check => {
+            let cancel = cancel_on_interrupt()?;
+            let total = brute_force::total(&seed_ranges);
+            let progress = progress::bar(total);
+            let on_progress = |count| progress.inc(count);
+            let smallest = check_smallest_location_for_seed_ranges(&seed_ranges, &maps, &on_progress, &cancel);
+            progress.finish_and_clear();
+            unless_cancelled(smallest)?
+        }
+    };
+    timings.record("part 2", start.elapsed());
+    output.answer("Smallest location for seed ranges", smallest);
+    output.detail(format!("Seed with smallest location in seed ranges: {}", maps.lookup_location_seed(smallest)?));
+    output.timings(&timings);
 
     Ok(())
 }
 
-/// From all the given seeds, lookup the locations to find the one with the smallest location.
-fn find_seed_with_smallest_location(seeds: Vec<u64>, maps: &Mappings) -> Result<u64> {
-    if seeds.is_empty() {
-        return Err(anyhow!("No seeds"));
+/// Brute force, resuming from the checkpoint file if there is one and saving
+/// to it as the search goes and when it's stopped, or removing it once the
+/// search finishes.
+fn checkpointed_search(
+    path: &Path,
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    bar: &ProgressBar,
+) -> Result<brute_force::Search> {
+    let checkpoint = Checkpoint::new(path, &serde_json::to_vec(&(seeds, maps))?);
+    let resumed: brute_force::Progress = checkpoint.load()?.unwrap_or_default();
+    if resumed.searched() > 0 {
+        bar.inc(resumed.searched());
+        bar.suspend(|| eprintln!("Resuming from {}", path.display()));
     }
-    let location = maps.lookup_seed_location(seeds[0])?;
-    let mut smallest = (seeds[0], location);
-    for seed in seeds.iter().skip(1) {
-        let location = maps.lookup_seed_location(*seed)?;
-        if location < smallest.1 {
-            smallest = (*seed, location);
+    let progress = Mutex::new(resumed);
+    // A failed save stops the search, rather than leaving it to run on
+    // unsaved.
+    let failed = Mutex::new(None);
+    let on_progress = |count| {
+        bar.inc(count);
+        if let Err(error) = checkpoint.save_due(|| progress.lock().unwrap().clone()) {
+            failed.lock().unwrap().get_or_insert(error);
         }
+    };
+    let stop = || interrupt::interrupted() || failed.lock().unwrap().is_some();
+    let search = brute_force::search_from(seeds, maps, &progress, &on_progress, &stop)?;
+    if let Some(error) = failed.into_inner().unwrap() {
+        return Err(error);
     }
-
-    Ok(smallest.0)
-}
-
-/// Read a line of the form "seeds: 1 2 3" and return a vector of the seeds.
-fn read_seeds(line: &str) -> Result<Vec<u64>> {
-    if !line.starts_with("seeds: ") {
-        return Err(anyhow!("Line must start with 'seeds: '"));
-    }
-    let line = &line["seeds: ".len()..];
-
-    let mut seeds = Vec::new();
-    for seed in line.split_ascii_whitespace() {
-        let seed = seed.parse()
-            .with_context(|| format!("Seed '{}' must be a number", seed))?;
-        seeds.push(seed);
+    if search.is_complete() {
+        checkpoint.remove()?;
+    } else {
+        checkpoint.save(&progress.into_inner().unwrap())?;
+        bar.suspend(|| eprintln!("Saved progress to {}; run again with it to resume", path.display()));
     }
-
-    Ok(seeds)
+    Ok(search)
 }
 
-/// Read all maps in the file.
-fn read_all_maps(line_iter: &mut dyn Iterator<Item = io::Result<String>>) -> Result<Mappings> {
-    let mut maps = HashMap::new();
-    while let Some(map) = read_map(line_iter)? {
-        maps.insert(map.source.to_owned(), map);
-    }
-
-    Ok(Mappings(maps))
+/// A token cancelled by Ctrl-C.
+fn cancel_on_interrupt() -> Result<Cancel> {
+    let cancel = Cancel::new();
+    let token = cancel.clone();
+    interrupt::install(move || token.cancel())?;
+    Ok(cancel)
 }
 
-/// Reads the map header and mappings until EOF or a blank line.
-fn read_map(line_iter: &mut dyn Iterator<Item = io::Result<String>>) -> Result<Option<Map>> {
-    let Some(header) = line_iter.next() else {
-        return Ok(None);
-    };
-    let header = header.unwrap();
-    let (source, destination) = parse_map_header(&header)
-        .with_context(|| format!("Error parsing map header '{header}'"))?;
-
-    let mut mappings = Vec::new();
-    for line in line_iter {
-        let line = line.unwrap();
-        if line.is_empty() {
-            break;
+/// A search's result, with cancelling by Ctrl-C as `Interrupted`.
+fn unless_cancelled(result: aoc_utils::error::Result<u64>) -> Result<u64> {
+    match result {
+        Err(AocError::Cancelled) => {
+            eprintln!("Stopped before finding the smallest location");
+            Err(Interrupted.into())
         }
-        
-        let mapping = Mapping::from_str(&line)
-            .with_context(|| format!("Error parsing mapping '{line}' for '{header}'"))?;
-        mappings.push(mapping);
+        result => Ok(result?),
     }
-
-    Ok(Some(Map {
-        source,
-        destination,
-        mappings,
-    }))
 }
 
-/// Parse a map header of the form "source-to-destination map:".
-/// Returns (source, destination).
-fn parse_map_header(line: &str) -> Result<(String, String)> {
-    if !line.ends_with(" map:") {
-        return Err(anyhow!("Map header must end with ' map:'"));
+/// Print how far a brute force got before Ctrl-C stopped it.
+fn report_interrupted(search: &brute_force::Search) {
+    let percent = search.searched as f64 / search.total as f64 * 100.0;
+    eprintln!("Searched {} of {} seeds ({percent:.1}%)", search.searched, search.total);
+    match search.smallest {
+        Some(smallest) => eprintln!("Smallest location so far: {smallest}"),
+        None => eprintln!("No location found yet"),
     }
-    let line = &line[..line.len() - " map:".len()];
-    let parts: Vec<&str> = line.split('-').collect();
-    if parts.len() != 3 || parts[1] != "to" {
-        return Err(anyhow!("Map header must be in the format 'source-to-destination map:'"));
-    }
-
-    Ok((parts[0].to_string(), parts[2].to_string()))
 }
 
-#[cfg(test)]
-mod test {
-    use std::{vec, io::{BufReader, Cursor}};
-
-    use super::*;
-
-    #[test]
-    fn test_seeds() {
-        assert_eq!(read_seeds("seeds: 1 2 3").unwrap(), vec![1, 2, 3]);
-        assert_eq!(read_seeds("seeds: 1").unwrap(), vec![1]);
-        assert_eq!(read_seeds("seeds: ").unwrap(), vec![]);
-        assert!(read_seeds("seeds: a").is_err());
-        assert!(read_seeds("seeds: 1 a").is_err());
-    }
-
-    #[test]
-    fn test_read_map() {
-        let mut line_iter = vec![
-            Ok("seed-to-soil map:".to_string()),
-            Ok("50 98 2".to_string()),
-            Ok("52 50 48".to_string()),
-            Ok("".to_string()),
-        ]
-            .into_iter();
-        let map = read_map(&mut line_iter).unwrap().unwrap();
-        assert_eq!(map.source, "seed".to_string());
-        assert_eq!(map.destination, "soil".to_string());
-        assert_eq!(map.mappings, vec![
-            Mapping {
-                source: 98..100,
-                destination_start: 50,
-            },
-            Mapping {
-                source: 50..98,
-                destination_start: 52,
-            },
-        ]);
-    }
-
-    #[test]
-    fn test_read_all_maps() {
-        let mut line_iter = vec![
-            Ok("seed-to-soil map:".to_string()),
-            Ok("50 98 2".to_string()),
-            Ok("52 50 48".to_string()),
-            Ok("".to_string()),
-            Ok("soil-to-fertilizer map:".to_string()),
-            Ok("0 15 37".to_string()),
-            Ok("37 52 2".to_string()),
-            Ok("39 0 15".to_string()),
-            Ok("".to_string()),
-        ]
-            .into_iter();
-        let maps = read_all_maps(&mut line_iter).unwrap();
-        assert_eq!(maps.0.len(), 2);
-        let seed_soil_map = maps.0.get("seed").unwrap();
-        assert_eq!(seed_soil_map.source, "seed".to_string());
-        assert_eq!(seed_soil_map.destination, "soil".to_string());
-        assert_eq!(seed_soil_map.mappings, vec![
-            Mapping {
-                source: 98..100,
-                destination_start: 50,
-            },
-            Mapping {
-                source: 50..98,
-                destination_start: 52,
-            },
-        ]);
-        let soil_fertilizer_map = maps.0.get("soil").unwrap();
-        assert_eq!(soil_fertilizer_map.source, "soil".to_string());
-        assert_eq!(soil_fertilizer_map.destination, "fertilizer".to_string());
-        assert_eq!(soil_fertilizer_map.mappings, vec![
-            Mapping {
-                source: 15..52,
-                destination_start: 0,
-            },
-            Mapping {
-                source: 52..54,
-                destination_start: 37,
-            },
-            Mapping {
-                source: 0..15,
-                destination_start: 39,
-            },
-        ]);
+/// Print each step of the seed's path, and the mapping that applied.
+fn explain(maps: &Mappings, seed: u64) -> Result<()> {
+    let hops = maps.trace_seed(seed)?;
+    let mut path = format!("seed {seed}");
+    for hop in &hops {
+        path.push_str(&format!(" -> {} {}", hop.map.destination(), hop.destination));
     }
-
-    #[test]
-    fn test_map_lookup() {
-        let map = Map {
-            source: "seed".to_string(),
-            destination: "soil".to_string(),
-            mappings: vec![
-                Mapping {
-                    source: 98..100,
-                    destination_start: 50,
-                },
-                Mapping {
-                    source: 50..98,
-                    destination_start: 52,
-                },
-            ],
+    println!("{path}");
+    for hop in &hops {
+        let rule = match hop.mapping {
+            Some(mapping) => mapping.to_string(),
+            None => "identity".to_string(),
         };
-        assert_eq!(map.lookup(79), 81);
-        assert_eq!(map.lookup(14), 14);
-        assert_eq!(map.lookup(55), 57);
-        assert_eq!(map.lookup(13), 13);
+        println!("  {}-to-{} map: {} -> {} ({rule})", hop.map.source(), hop.map.destination(), hop.source, hop.destination);
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_lookup_seed_location() {
-        let text = r#"seed-to-soil map:
-50 98 2
-52 50 48
-
-soil-to-fertilizer map:
-0 15 37
-37 52 2
-39 0 15
-
-fertilizer-to-water map:
-49 53 8
-0 11 42
-42 0 7
-57 7 4
-
-water-to-light map:
-88 18 7
-18 25 70
-
-light-to-temperature map:
-45 77 23
-81 45 19
-68 64 13
+#[cfg(feature = "gpu")]
+fn use_gpu(args: &Args) -> bool {
+    args.gpu
+}
 
-temperature-to-humidity map:
-0 69 1
-1 0 69
+#[cfg(not(feature = "gpu"))]
+fn use_gpu(_args: &Args) -> bool {
+    false
+}
 
-humidity-to-location map:
-60 56 37
-56 93 4
-"#;
-        let cursor = Cursor::new(text);
-        let mut line_iter = BufReader::new(cursor).lines();
-        let maps = read_all_maps(&mut line_iter).unwrap();
-        assert_eq!(maps.lookup_seed_location(79).unwrap(), 82);
-        assert_eq!(maps.lookup_seed_location(14).unwrap(), 43);
-        assert_eq!(maps.lookup_seed_location(55).unwrap(), 86);
-        assert_eq!(maps.lookup_seed_location(13).unwrap(), 35);
+/// Brute force part 2 on the GPU if asked to. Returns `None` to fall back to
+/// the CPU.
+#[cfg(feature = "gpu")]
+fn gpu_smallest_location(
+    args: &Args,
+    seeds: &[Range<u64>],
+    maps: &Mappings,
+    progress: &ProgressBar,
+) -> Result<Option<u64>> {
+    if !args.gpu {
+        return Ok(None);
     }
+    let smallest = day05::gpu::smallest_location(seeds, maps, &|count| progress.inc(count))?;
+    if smallest.is_none() {
+        progress.suspend(|| eprintln!("No GPU found, using the CPU"));
+    }
+    Ok(smallest)
+}
+
+#[cfg(not(feature = "gpu"))]
+fn gpu_smallest_location(_: &Args, _: &[Range<u64>], _: &Mappings, _: &ProgressBar) -> Result<Option<u64>> {
+    Ok(None)
 }
@@ -0,0 +1,98 @@
+//! Part 2 by trying each location in ascending order, mapping it back
+//! through the maps, and stopping at the first that comes from a seed in the
+//! ranges. It is exact, and fast when the answer is small; otherwise it can
+//! take as many steps as the answer, so it stops when cancelled.
+
+use std::ops::Range;
+
+use aoc_utils::cancel::Cancel;
+use aoc_utils::error::{AocError, Result};
+
+use crate::{Map, Mappings};
+
+/// How many locations are tried between checks for cancellation.
+const CHECK_EVERY: u64 = 1 << 16;
+
+/// Find the smallest location of any seed in the ranges by working back from
+/// location 0, up to the largest location a seed could reach, or until
+/// `cancel` is cancelled.
+pub fn smallest_location(seeds: &[Range<u64>], maps: &Mappings, cancel: &Cancel) -> Result<u64> {
+    if seeds.iter().all(|range| range.is_empty()) {
+        return Err(AocError::no_solution("No seeds"));
+    }
+    let chain = maps.seed_to_location_chain()?;
+    for location in 0..=largest_reachable(seeds, chain) {
+        if location % CHECK_EVERY == 0 {
+            cancel.check()?;
+        }
+        let mut values = vec![location];
+        for map in chain.iter().rev() {
+            values = values.iter().flat_map(|&value| map.preimages(value)).collect();
+        }
+        if values.iter().any(|seed| seeds.iter().any(|range| range.contains(seed))) {
+            return Ok(location);
+        }
+    }
+    // Every seed reaches a location no larger than the bound, so this means
+    // the bound is wrong.
+    Err(AocError::no_solution("No seed reaches a location"))
+}
+
+/// An upper bound on the locations the seeds can reach: a value either
+/// stays the same through a map, or lands in the destination of a mapping
+/// whose source starts no higher than it.
+fn largest_reachable(seeds: &[Range<u64>], chain: &[Map]) -> u64 {
+    let seed = seeds.iter().filter(|range| !range.is_empty()).map(|range| range.end - 1).max().unwrap_or(0);
+    chain.iter().fold(seed, |largest, map| {
+        map.mappings
+            .iter()
+            .filter(|mapping| mapping.source.start <= largest)
+            .map(|mapping| mapping.destination_range().end - 1)
+            .fold(largest, u64::max)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::tests::example_maps;
+    use crate::{find_smallest_location_for_seed_ranges, seed_ranges};
+
+    #[test]
+    fn test_smallest_location() {
+        let maps = example_maps();
+        let seeds = seed_ranges(&[79, 14, 55, 13]).unwrap();
+        assert_eq!(smallest_location(&seeds, &maps, &Cancel::new()).unwrap(), 46);
+        assert!(smallest_location(&[], &maps, &Cancel::new()).is_err());
+        assert!(smallest_location(&seed_ranges(&[5, 0]).unwrap(), &maps, &Cancel::new()).is_err());
+        assert!(matches!(
+            smallest_location(&seeds, &maps, &Cancel::with_timeout(Duration::ZERO)),
+            Err(AocError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_largest_reachable() {
+        let maps = example_maps();
+        let chain = maps.seed_to_location_chain().unwrap();
+        // The seed-to-soil map sends 50..98 up to 99, and nothing goes higher.
+        assert_eq!(largest_reachable(&seed_ranges(&[79, 14, 55, 13]).unwrap(), chain), 99);
+        assert_eq!(largest_reachable(&seed_ranges(&[0, 1]).unwrap(), chain), 99);
+        // No mapping starts beyond 98, so 200 stays 200.
+        assert_eq!(largest_reachable(&seed_ranges(&[200, 1]).unwrap(), chain), 200);
+    }
+
+    #[test]
+    fn test_matches_intervals() {
+        let maps = example_maps();
+        for (start, len) in [(0, 100), (90, 20), (13, 1), (50, 2), (97, 1), (60, 40)] {
+            let seeds = seed_ranges(&[start, len]).unwrap();
+            assert_eq!(
+                smallest_location(&seeds, &maps, &Cancel::new()).unwrap(),
+                find_smallest_location_for_seed_ranges(&seeds, &maps).unwrap(),
+            );
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! The brute force's lookups 8 seeds at a time with `std::simd`, which needs
+//! a nightly compiler. Each map tests all 8 values against one mapping at a
+//! time, in place of a binary search per value.
+
+use std::ops::Range;
+use std::simd::prelude::*;
+
+use crate::Map;
+
+/// The number of seeds looked up together.
+pub const LANES: usize = 8;
+
+type Values = Simd<u64, LANES>;
+
+impl Map {
+    /// Map 8 source values to their destinations at once, as by
+    /// [`Map::lookup`]. The mappings must be sorted by source start, as they
+    /// are when parsed.
+    pub fn lookup_lanes(&self, sources: Values) -> Values {
+        let mut destinations = sources;
+        for mapping in &self.mappings {
+            let start = Values::splat(mapping.source.start);
+            // Later mappings start later still, so none contain any value.
+            if sources.simd_lt(start).all() {
+                break;
+            }
+            let inside = sources.simd_ge(start) & sources.simd_lt(Values::splat(mapping.source.end));
+            destinations = inside.select(sources - start + Values::splat(mapping.destination_start), destinations);
+        }
+        destinations
+    }
+}
+
+/// The smallest location of any seed in `seeds` through the maps in
+/// `chain`, 8 seeds at a time and any left over one at a time.
+pub fn smallest_location(chain: &[Map], seeds: Range<u64>) -> Option<u64> {
+    let lanes_end = seeds.end - (seeds.end - seeds.start) % LANES as u64;
+    let offsets = Values::from_array(std::array::from_fn(|lane| lane as u64));
+    let mut smallest = None;
+    if seeds.start < lanes_end {
+        let mut smallest_lanes = Values::splat(u64::MAX);
+        for start in (seeds.start..lanes_end).step_by(LANES) {
+            let locations = chain.iter().fold(Values::splat(start) + offsets, |values, map| map.lookup_lanes(values));
+            smallest_lanes = smallest_lanes.simd_min(locations);
+        }
+        smallest = Some(smallest_lanes.reduce_min());
+    }
+    let rest = (lanes_end..seeds.end).map(|seed| chain.iter().fold(seed, |value, map| map.lookup(value)));
+    smallest.into_iter().chain(rest).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::example_maps;
+
+    #[test]
+    fn test_lookup_lanes() {
+        let maps = example_maps();
+        for map in &maps.maps {
+            for start in (0..120).step_by(LANES) {
+                let sources = Values::from_array(std::array::from_fn(|lane| start + lane as u64));
+                let expected = sources.to_array().map(|source| map.lookup(source));
+                assert_eq!(map.lookup_lanes(sources).to_array(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_smallest_location() {
+        let maps = example_maps();
+        let chain = maps.seed_to_location_chain().unwrap();
+        for seeds in [79..93, 55..68, 0..100, 46..47, 5..5] {
+            let expected = seeds.clone().map(|seed| maps.lookup_seed_location(seed).unwrap()).min();
+            assert_eq!(smallest_location(chain, seeds), expected);
+        }
+    }
+}
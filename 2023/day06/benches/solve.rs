@@ -0,0 +1,22 @@
+//! Parsing and solving each part on the real input and generated ones.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day06::{generate, Day06};
+
+/// The real input, then generated ones: a single race of longer and longer
+/// times, so part 2 reads it as is.
+fn inputs() -> Vec<(String, String)> {
+    let mut inputs = vec![("input".to_string(), include_str!("../input.txt").to_string())];
+    for max_time in [1_000, 1_000_000_000, 1_000_000_000_000_000_000] {
+        inputs.push((format!("generated/{max_time}"), generate::races(12345, 1, max_time)));
+    }
+    inputs
+}
+
+fn solve(c: &mut Criterion) {
+    aoc_utils::benches::solver(c, &Day06, &inputs());
+}
+
+criterion_group!(benches, solve);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+//! Scramble the races so they can be shared without sharing the puzzle
+//! input, keeping part 1's answer.
+
+use aoc_utils::error::{AocError, Result};
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::random::Rng;
+
+use crate::{read_races, Race};
+
+/// Make each race up to twice as long, with the record moved so the number of
+/// ways to win is the same, and shuffle the races. Part 1's answer stays the
+/// same; part 2 reads the new digits, so its answer changes.
+pub fn anonymize(input: &str, seed: u64) -> Result<String> {
+    let mut rng = Rng::new(seed);
+    let mut races = read_races(&mut aoc_utils::lines::lines(input.as_bytes()), false, &mut LinePolicy::strict())?;
+    rng.shuffle(&mut races);
+
+    let mut times = String::from("Time:    ");
+    let mut distances = String::from("Distance:");
+    for race in races {
+        let race = stretch(&race, rng.range(0..race.time / 2 + 1))?;
+        let width = race.time.to_string().len().max(race.record_distance.to_string().len());
+        times.push_str(&format!(" {:>width$}", race.time));
+        distances.push_str(&format!(" {:>width$}", race.record_distance));
+    }
+    Ok(format!("{times}\n{distances}\n"))
+}
+
+/// The race `2 * by` longer with the same number of ways to win: the record
+/// is the distance for holding one less than the new shortest winning hold.
+fn stretch(race: &Race, by: u64) -> Result<Race> {
+    let overflow = || AocError::overflow(format!("Race {race:?} is too long to stretch by {by}"));
+    let time = by.checked_mul(2).and_then(|twice| race.time.checked_add(twice)).ok_or_else(overflow)?;
+    let hold_times = race.winning_hold_times();
+    let record = if hold_times.is_empty() {
+        // Still unwinnable: the best distance only ties the record.
+        u128::from(time / 2) * u128::from(time - time / 2)
+    } else {
+        let hold = hold_times.start() + by - 1;
+        u128::from(hold) * u128::from(time - hold)
+    };
+    Ok(Race::new(time, u64::try_from(record).map_err(|_| overflow())?))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::solver::DynSolver;
+
+    use super::*;
+    use crate::Day06;
+
+    #[test]
+    fn test_stretch() {
+        let race = Race::new(7, 9);
+        for by in 0..5 {
+            let stretched = stretch(&race, by).unwrap();
+            assert_eq!(stretched.time, 7 + 2 * by);
+            assert_eq!(stretched.winning_hold_times_iterative().count(), 4);
+        }
+        assert!(stretch(&Race::new(4, 4), 3).unwrap().winning_hold_times_iterative().is_empty());
+        assert!(stretch(&Race::new(u64::MAX, 0), 1).is_err());
+    }
+
+    #[test]
+    fn test_anonymize() {
+        for input in [include_str!("../fixtures/example.txt"), include_str!("../input.txt")] {
+            let anonymized = anonymize(input, 1).unwrap();
+            assert_ne!(anonymized, input);
+            assert_eq!(anonymized, anonymize(input, 1).unwrap());
+            assert_eq!(Day06.solve(&anonymized, 1).unwrap(), Day06.solve(input, 1).unwrap());
+        }
+    }
+}
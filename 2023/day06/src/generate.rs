@@ -0,0 +1,72 @@
+//! Seeded races of any size, for stress tests and checking the quadratic
+//! solution against trying each hold time.
+
+use aoc_utils::random::Rng;
+
+/// `count` races with times up to `max_time`, each with a record that can be
+/// beaten, laid out in columns like the puzzle input. Part 2 reads the digits
+/// as one race, so it only parses while they fit in a u64.
+pub fn races(seed: u64, count: usize, max_time: u64) -> String {
+    assert!(max_time >= 2, "A race needs 2 time units to be winnable");
+    let mut rng = Rng::new(seed);
+    let races: Vec<(u64, u64)> = (0..count)
+        .map(|_| {
+            let time = rng.range(2..max_time.saturating_add(1));
+            let best = u128::from(time / 2) * u128::from(time - time / 2);
+            (time, rng.range(0..best.min(u128::from(u64::MAX)) as u64))
+        })
+        .collect();
+
+    let mut times = String::from("Time:    ");
+    let mut distances = String::from("Distance:");
+    for (time, record) in races {
+        let width = time.to_string().len().max(record.to_string().len());
+        times.push_str(&format!(" {time:>width$}"));
+        distances.push_str(&format!(" {record:>width$}"));
+    }
+    format!("{times}\n{distances}\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::lenient::LinePolicy;
+
+    use super::*;
+    use crate::{check_number_of_winning_hold_times, read_races, Race};
+
+    fn read(text: &str, kerning: bool) -> Vec<Race> {
+        read_races(&mut aoc_utils::lines::lines(text.as_bytes()), kerning, &mut LinePolicy::strict()).unwrap()
+    }
+
+    #[test]
+    fn test_races() {
+        let text = races(1, 4, 100);
+        assert_eq!(text, races(1, 4, 100));
+        assert_ne!(text, races(2, 4, 100));
+        assert_eq!(read(&text, false).len(), 4);
+        assert_eq!(read(&text, true).len(), 1);
+        assert_eq!(races(1, 1, 2), "Time:     2\nDistance: 0\n");
+    }
+
+    /// Both solutions agree on races small enough to try every hold time.
+    #[test]
+    fn test_solutions_agree() {
+        for seed in 0..20 {
+            for race in read(&races(seed, 4, 10_000), false) {
+                check_number_of_winning_hold_times(&race, &|_| {}).unwrap();
+            }
+        }
+    }
+
+    /// The quadratic solution finds the exact boundaries of huge races.
+    #[test]
+    fn test_huge_races() {
+        for race in read(&races(7, 50, u64::MAX), false) {
+            let hold_times = race.winning_hold_times();
+            assert!(!hold_times.is_empty(), "{race:?}");
+            assert!(race.wins(*hold_times.start()).unwrap() && race.wins(*hold_times.end()).unwrap());
+            assert!(!race.wins(hold_times.start() - 1).unwrap());
+            assert!(!race.wins(hold_times.end() + 1).unwrap());
+        }
+    }
+}
@@ -0,0 +1,507 @@
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+use aoc_utils::cancel::Cancel;
+use aoc_utils::check;
+use aoc_utils::error::{AocError, Context, Result};
+use aoc_utils::lenient::{self, LinePolicy, Validation};
+use aoc_utils::solver::{Answer, Solver};
+
+pub mod anonymize;
+pub mod generate;
+
+/// Read the races from the file. With `kerning`, the spaces between digits
+/// are ignored, so there is a single race. Lines that don't parse are handled
+/// by `policy`.
+pub fn read_races<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L>>,
+    kerning: bool,
+    policy: &mut LinePolicy,
+) -> Result<Vec<Race>> {
+    let times = read_numbers(line_iter, "Time:", "time", kerning, policy)?;
+    let distances = read_numbers(line_iter, "Distance:", "distance", kerning, policy)?;
+
+    if times.len() != distances.len() {
+        return Err(AocError::parse(format!(
+            "Number of times ({}) must match the number of distances ({})",
+            times.len(),
+            distances.len()
+        ))
+        .at_line(policy.line_number()));
+    }
+
+    let mut races = Vec::with_capacity(times.len());
+    for i in 0..times.len() {
+        races.push(Race::new(times[i], distances[i]));
+    }
+    Ok(races)
+}
+
+/// Check that the races can be read, collecting all the errors.
+pub fn validate(reader: impl BufRead) -> Validation {
+    lenient::validate(|policy| read_races(&mut aoc_utils::lines::lines(reader), false, policy).map(drop))
+}
+
+/// Read the numbers from the next line, which starts with `prefix`.
+fn read_numbers<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L>>,
+    prefix: &str,
+    name: &str,
+    kerning: bool,
+    policy: &mut LinePolicy,
+) -> Result<Vec<u64>> {
+    loop {
+        let line = line_iter
+            .next()
+            .ok_or_else(|| AocError::missing(format!("the '{name}' line")))??;
+        policy.next_line();
+        if let Some(numbers) = policy.check(parse_numbers(line.as_ref(), prefix, name, kerning))? {
+            return Ok(numbers);
+        }
+    }
+}
+
+fn parse_numbers(line: &str, prefix: &str, name: &str, kerning: bool) -> Result<Vec<u64>> {
+    let numbers = trim_line_prefix(line, prefix)?.trim();
+    if kerning {
+        let number = numbers.replace(" ", "").parse().with_context(|| format!("Error parsing {name}"))?;
+        return Ok(vec![number]);
+    }
+    numbers
+        .split_ascii_whitespace()
+        .map(|number| {
+            number
+                .parse()
+                .with_context(|| format!("Error parsing {name} '{number}'"))
+                .map_err(|error| error.at_column(number.as_ptr() as usize - line.as_ptr() as usize + 1))
+        })
+        .collect()
+}
+
+fn trim_line_prefix<'a>(line: &'a str, prefix: &str) -> Result<&'a str> {
+    line.strip_prefix(prefix)
+        .ok_or_else(|| AocError::parse(format!("The line '{line}' does not start with the prefix '{prefix}'")))
+}
+
+/// Day 6's solution: the product of the number of ways to win each race, with
+/// the spaces between digits kept in part 1 and ignored in part 2.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Day06;
+
+impl Solver for Day06 {
+    /// The races read both ways: separately, and kerned into one.
+    type Parsed = (Vec<Race>, Vec<Race>);
+
+    fn parse(&self, input: &str) -> Result<(Vec<Race>, Vec<Race>)> {
+        let read = |kerning| {
+            read_races(&mut aoc_utils::lines::lines(input.as_bytes()), kerning, &mut LinePolicy::strict())
+        };
+        Ok((read(false)?, read(true)?))
+    }
+
+    fn part1(&self, (races, _): &(Vec<Race>, Vec<Race>), cancel: &Cancel) -> Result<Answer> {
+        Ok(multiply_winning_hold_times(races, cancel)?.into())
+    }
+
+    fn part2(&self, (_, races): &(Vec<Race>, Vec<Race>), cancel: &Cancel) -> Result<Answer> {
+        Ok(multiply_winning_hold_times(races, cancel)?.into())
+    }
+}
+
+/// The product of the number of ways to win each race.
+fn multiply_winning_hold_times(races: &[Race], cancel: &Cancel) -> Result<u64> {
+    races.iter().try_fold(1u64, |product, race| {
+        cancel.check()?;
+        find_number_of_winning_hold_times(race, false)?
+            .checked_mul(product)
+            .ok_or_else(|| AocError::overflow("The product of the winning ranges overflows"))
+    })
+}
+
+/// Finds the number of button hold times that win the race.
+pub fn find_number_of_winning_hold_times(race: &Race, iterative: bool) -> Result<u64> {
+    let hold_times = if iterative { race.winning_hold_times_iterative() } else { race.winning_hold_times() };
+    count_hold_times(race, hold_times)
+}
+
+/// Finds the number of button hold times that win the race by trying each
+/// one, calling `on_progress` with the number tried as the search goes.
+pub fn find_number_of_winning_hold_times_iterative(race: &Race, on_progress: &dyn Fn(u64)) -> Result<u64> {
+    count_hold_times(race, race.winning_hold_times_iterative_with_progress(on_progress))
+}
+
+/// Count the winning hold times both by solving for them and by trying
+/// each one, failing unless they agree. `on_progress` is called as the
+/// hold times are tried.
+pub fn check_number_of_winning_hold_times(race: &Race, on_progress: &dyn Fn(u64)) -> Result<u64> {
+    check::agree(&[
+        ("quadratic", &|| find_number_of_winning_hold_times(race, false)),
+        ("iterative", &|| find_number_of_winning_hold_times_iterative(race, on_progress)),
+    ])
+}
+
+fn count_hold_times(race: &Race, hold_times: RangeInclusive<u64>) -> Result<u64> {
+    if hold_times.is_empty() {
+        return Err(AocError::no_solution(format!("There's no way to win this race. {race:?}")));
+    }
+
+    Ok(hold_times.end() - hold_times.start() + 1)
+}
+
+/// Finds the minimum button hold time to win the race, by solving
+/// `hold * (time - hold) > record` with the quadratic formula.
+fn find_minimum_winning_race(race: &Race) -> Result<u64> {
+    // The distance peaks at half the time, so if that doesn't win nothing does.
+    if !race.wins(race.time / 2)? {
+        return Err(AocError::no_solution(format!("There's no way to win this race. {race:?}")));
+    }
+    let time = u128::from(race.time);
+    let discriminant = (time * time)
+        .checked_sub(4 * u128::from(race.record_distance))
+        .ok_or_else(|| AocError::no_solution(format!("There's no way to win this race. {race:?}")))?;
+    let mut hold = u64::try_from((time - discriminant.isqrt()) / 2)?;
+
+    // The integer square root is rounded, so step to the exact boundary.
+    while hold > 0 && race.wins(hold - 1)? {
+        hold -= 1;
+    }
+    while !race.wins(hold)? {
+        hold += 1;
+    }
+    Ok(hold)
+}
+
+/// Finds the maxmimum button hold time to win the race. The distances are
+/// symmetric around half the time, so it mirrors the minimum.
+fn find_maximum_winning_race(race: &Race) -> Result<u64> {
+    Ok(race.time - find_minimum_winning_race(race)?)
+}
+
+/// How many hold times are tried between calls to `on_progress`.
+const PROGRESS_STEP: u64 = 1 << 16;
+
+/// Finds the minimum button hold time to win the race by trying each one.
+fn find_minimum_winning_race_iterative(race: &Race, on_progress: &dyn Fn(u64)) -> Result<u64> {
+    let mut time_iter = 1..race.time;
+    find_first_winning_race_iter(race, &mut time_iter, on_progress)
+}
+
+/// Finds the maxmimum button hold time to win the race by trying each one.
+fn find_maximum_winning_race_iterative(race: &Race, on_progress: &dyn Fn(u64)) -> Result<u64> {
+    let mut time_iter = (1..race.time).rev();
+    find_first_winning_race_iter(race, &mut time_iter, on_progress)
+}
+
+/// Find the button hold time for the first winning race in the time iterator.
+/// `on_progress` is called with the number of hold times tried, every
+/// `PROGRESS_STEP` of them.
+fn find_first_winning_race_iter(
+    race: &Race,
+    time_iter: &mut dyn Iterator<Item = u64>,
+    on_progress: &dyn Fn(u64),
+) -> Result<u64> {
+    let mut tried = 0;
+    for button_hold_time in time_iter {
+        if race.wins(button_hold_time)? {
+            on_progress(tried);
+            return Ok(button_hold_time);
+        }
+        tried += 1;
+        if tried == PROGRESS_STEP {
+            on_progress(tried);
+            tried = 0;
+        }
+    }
+    on_progress(tried);
+
+    Err(AocError::no_solution(format!("There's no way to win this race. {race:?}")))
+}
+
+/// Draw the distance for each hold time as a `width` by `height` chart, with
+/// the record as a horizontal line. Hold times that win are drawn with `#`
+/// instead of `*`, and marked with `=` under the chart.
+pub fn plot(race: &Race, width: usize, height: usize) -> Result<String> {
+    let width = usize::try_from(race.time).map_or(width, |time| width.min(time.saturating_add(1)));
+    let hold_time = |column: usize| {
+        (column as u128 * race.time as u128 / (width - 1).max(1) as u128) as u64
+    };
+    let top = race.calculate_distance(race.time / 2)?.max(race.record_distance as u128).max(1);
+    // Distances can be near 2^126, so scale those over 2^63 down before
+    // multiplying by the height, which then can't overflow.
+    let scale = (top >> 63) + 1;
+    let row = |distance: u128| ((distance / scale) * (height - 1) as u128).div_ceil(top / scale) as usize;
+    let record_row = row(race.record_distance as u128);
+
+    let mut chart = format!("Time {}, record {}\n", race.time, race.record_distance);
+    for r in (0..height).rev() {
+        let line: String = (0..width)
+            .map(|column| {
+                let hold = hold_time(column);
+                Ok(if row(race.calculate_distance(hold)?) == r {
+                    if race.wins(hold)? { '#' } else { '*' }
+                } else if r == record_row {
+                    '-'
+                } else {
+                    ' '
+                })
+            })
+            .collect::<Result<_>>()?;
+        chart.push_str(&format!("|{}\n", line.trim_end()));
+    }
+    let axis: String = (0..width)
+        .map(|column| Ok(if race.wins(hold_time(column))? { '=' } else { '-' }))
+        .collect::<Result<_>>()?;
+    chart.push_str(&format!("+{axis}\n"));
+    chart.push_str(&format!("0{:>width$}\n", race.time));
+    Ok(chart)
+}
+
+#[derive(Debug)]
+pub struct Race {
+    pub time: u64,
+    pub record_distance: u64,
+}
+
+impl Race {
+    pub fn new(time: u64, distance: u64) -> Self {
+        Self { time, record_distance: distance }
+    }
+
+    /// Calculate the distance traveled for the amount of time holding the
+    /// button. It is widened to u128 so it can't overflow. Holding the button
+    /// for longer than the race is an error.
+    pub fn calculate_distance(&self, button_hold_time: u64) -> Result<u128> {
+        let travel_time = self.time.checked_sub(button_hold_time).ok_or_else(|| {
+            AocError::overflow(format!(
+                "Holding the button for {button_hold_time} is longer than the race's time {}",
+                self.time
+            ))
+        })?;
+
+        // The button_hold_time is the speed.
+        Ok(u128::from(travel_time) * u128::from(button_hold_time))
+    }
+
+    /// The button hold times that beat the record, solved with the quadratic
+    /// formula. Empty if there's no way to win.
+    pub fn winning_hold_times(&self) -> RangeInclusive<u64> {
+        match (find_minimum_winning_race(self), find_maximum_winning_race(self)) {
+            (Ok(min), Ok(max)) => min..=max,
+            _ => RangeInclusive::new(1, 0),
+        }
+    }
+
+    /// The button hold times that beat the record, found by trying each one.
+    pub fn winning_hold_times_iterative(&self) -> RangeInclusive<u64> {
+        self.winning_hold_times_iterative_with_progress(&|_| {})
+    }
+
+    /// Like `winning_hold_times_iterative`, calling `on_progress` with the
+    /// number of hold times tried as the search goes. At most `time` are
+    /// tried.
+    pub fn winning_hold_times_iterative_with_progress(&self, on_progress: &dyn Fn(u64)) -> RangeInclusive<u64> {
+        match (
+            find_minimum_winning_race_iterative(self, on_progress),
+            find_maximum_winning_race_iterative(self, on_progress),
+        ) {
+            (Ok(min), Ok(max)) => min..=max,
+            _ => RangeInclusive::new(1, 0),
+        }
+    }
+
+    /// Whether holding the button for the time beats the record.
+    pub fn wins(&self, button_hold_time: u64) -> Result<bool> {
+        Ok(self.calculate_distance(button_hold_time)? > u128::from(self.record_distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const EXAMPLE: &str = "Time:      7  15   30\nDistance:  9  40  200\n";
+
+    #[test]
+    fn test_read_races() {
+        let mut line_iter = EXAMPLE.lines().map(|line| Ok(line.to_string()));
+        let races = read_races(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+        assert_eq!(races.iter().map(|race| (race.time, race.record_distance)).collect::<Vec<_>>(), vec![(7, 9), (15, 40), (30, 200)]);
+
+        let mut line_iter = EXAMPLE.lines().map(|line| Ok(line.to_string()));
+        let races = read_races(&mut line_iter, true, &mut LinePolicy::strict()).unwrap();
+        assert_eq!(races.iter().map(|race| (race.time, race.record_distance)).collect::<Vec<_>>(), vec![(71530, 940200)]);
+    }
+
+    #[test]
+    fn test_read_races_crlf() {
+        let mut line_iter = aoc_utils::lines::lines("\u{feff}Time:      7  15   30 \r\nDistance:  9  40  200\r\n".as_bytes());
+        let races = read_races(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+        assert_eq!(races.iter().map(|race| (race.time, race.record_distance)).collect::<Vec<_>>(), vec![(7, 9), (15, 40), (30, 200)]);
+    }
+
+    #[test]
+    fn test_read_races_lenient() {
+        let input = "\nTime:      7  15   30\nDistnce:  9  40  200\nDistance:  9  40  200\n";
+        let lines = || input.lines().map(|line| Ok(line.to_string()));
+        let error = read_races(&mut lines(), false, &mut LinePolicy::strict()).unwrap_err();
+        assert_eq!(format!("{error:#}"), "Error on line 1: The line '' does not start with the prefix 'Time:'");
+
+        let mut policy = LinePolicy::lenient();
+        let races = read_races(&mut lines(), false, &mut policy).unwrap();
+        assert_eq!(races.len(), 3);
+        let skipped: Vec<usize> = policy.skipped().iter().map(|skipped| skipped.line_number).collect();
+        assert_eq!(skipped, [1, 3]);
+
+        let mut line_iter = ["Time: 7 x", "Distance: 9 40"].into_iter().map(|line| Ok(line.to_string()));
+        let error = read_races(&mut line_iter, false, &mut LinePolicy::lenient()).unwrap_err();
+        assert_eq!(error.to_string(), "The input is missing the 'time' line");
+
+        let mut line_iter = ["Time: 7 x", "Distance: 9 40"].into_iter().map(|line| Ok(line.to_string()));
+        let error = read_races(&mut line_iter, false, &mut LinePolicy::strict()).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 1, column 9: Error parsing time 'x': invalid digit found in string");
+    }
+
+    #[test]
+    fn test_find_number_of_winning_hold_times() {
+        for (time, distance, expected) in [(7, 9, 4), (15, 40, 8), (30, 200, 9), (4, 3, 1), (10, 0, 9), (71530, 940200, 71503)] {
+            let race = Race::new(time, distance);
+            assert_eq!(find_number_of_winning_hold_times(&race, false).unwrap(), expected);
+            assert_eq!(find_number_of_winning_hold_times(&race, true).unwrap(), expected);
+        }
+        assert!(find_number_of_winning_hold_times(&Race::new(4, 4), false).is_err());
+        assert!(find_number_of_winning_hold_times(&Race::new(4, 4), true).is_err());
+    }
+
+    #[test]
+    fn test_solver() {
+        let parsed = Day06.parse(EXAMPLE).unwrap();
+        assert_eq!(Day06.part1(&parsed, &Cancel::new()).unwrap(), Answer::Unsigned(288));
+        assert_eq!(Day06.part2(&parsed, &Cancel::new()).unwrap(), Answer::Unsigned(71503));
+        assert!(Day06.part1(&Day06.parse("Time: 1\nDistance: 5\n").unwrap(), &Cancel::new()).is_err());
+    }
+
+    aoc_utils::aoc_example_tests!(Day06, part1 = 288, part2 = 71503, input = "fixtures/example.txt");
+
+    #[test]
+    fn test_plot() {
+        assert_eq!(plot(&Race::new(7, 9), 60, 6).unwrap(), "\
+Time 7, record 9
+|  ####
+|--------
+| *    *
+|
+|
+|*      *
++--====--
+0       7
+");
+
+        // The longest race doesn't overflow the width or the rows.
+        assert_eq!(plot(&Race::new(u64::MAX, 5), 60, 6).unwrap(), "\
+Time 18446744073709551615, record 5
+|                 ##########################
+|           ######                          ######
+|       ####                                      ####
+|    ###                                              ###
+| ###                                                    ###
+|*----------------------------------------------------------*
++-==========================================================-
+0                                        18446744073709551615
+");
+    }
+
+    #[test]
+    fn test_calculate_distance() {
+        let race = Race::new(7, 9);
+        assert_eq!(race.calculate_distance(0).unwrap(), 0);
+        assert_eq!(race.calculate_distance(3).unwrap(), 12);
+        assert_eq!(race.calculate_distance(7).unwrap(), 0);
+        let error = race.calculate_distance(8).unwrap_err();
+        assert_eq!(error.to_string(), "Holding the button for 8 is longer than the race's time 7");
+        assert!(race.wins(8).is_err());
+    }
+
+    #[test]
+    fn test_winning_hold_times() {
+        assert_eq!(Race::new(7, 9).winning_hold_times(), 2..=5);
+        assert_eq!(Race::new(30, 200).winning_hold_times_iterative(), 11..=19);
+        assert!(Race::new(4, 4).winning_hold_times().is_empty());
+        assert!(Race::new(0, 0).winning_hold_times_iterative().is_empty());
+
+        let tried = std::cell::Cell::new(0);
+        let on_progress = |count| tried.set(tried.get() + count);
+        let hold_times = Race::new(30, 200).winning_hold_times_iterative_with_progress(&on_progress);
+        assert_eq!(hold_times, 11..=19);
+        assert_eq!(tried.get(), 20);
+    }
+
+    #[test]
+    fn test_extreme_races() {
+        assert_eq!(find_number_of_winning_hold_times(&Race::new(u64::MAX, 0), false).unwrap(), u64::MAX - 1);
+        assert_eq!(find_number_of_winning_hold_times(&Race::new(u64::MAX, u64::MAX), false).unwrap(), u64::MAX - 3);
+        assert!(find_number_of_winning_hold_times(&Race::new(0, 0), false).is_err());
+        assert!(find_number_of_winning_hold_times(&Race::new(0, 0), true).is_err());
+        assert!(find_number_of_winning_hold_times(&Race::new(1, 0), false).is_err());
+    }
+
+    /// Both solvers' answer, as `Some(count)` or `None` when there's no way to win.
+    fn solve_both(race: &Race) -> (Option<u64>, Option<u64>) {
+        (
+            find_number_of_winning_hold_times(race, false).ok(),
+            find_number_of_winning_hold_times(race, true).ok(),
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn test_read_races_any_lines(lines in prop::collection::vec("\\PC{0,40}", 0..4), kerning: bool) {
+            let mut line_iter = lines.into_iter().map(Ok);
+            let _ = read_races(&mut line_iter, kerning, &mut LinePolicy::strict());
+        }
+
+        #[test]
+        fn test_read_races_near_valid(
+            time in "Time: ( {1,3}[0-9]{1,5}){0,4}",
+            distance in "Distance: ( {1,3}[0-9]{1,5}){0,4}",
+            kerning: bool,
+        ) {
+            let mut line_iter = [time, distance].into_iter().map(Ok);
+            let _ = read_races(&mut line_iter, kerning, &mut LinePolicy::strict());
+        }
+
+        #[test]
+        fn test_read_races_valid(races in prop::collection::vec((any::<u64>(), any::<u64>()), 0..6)) {
+            let numbers = |numbers: Vec<u64>| numbers.iter().map(|n| format!(" {n:>4}")).collect::<String>();
+            let time = format!("Time:    {}", numbers(races.iter().map(|race| race.0).collect()));
+            let distance = format!("Distance:{}", numbers(races.iter().map(|race| race.1).collect()));
+            let mut line_iter = [time, distance].into_iter().map(Ok);
+            let read = read_races(&mut line_iter, false, &mut LinePolicy::strict()).unwrap();
+            let read: Vec<(u64, u64)> = read.iter().map(|race| (race.time, race.record_distance)).collect();
+            prop_assert_eq!(read, races);
+        }
+
+        #[test]
+        fn test_solvers_agree(time in 0..5000u64, record in 0..7_000_000u64) {
+            let (quadratic, iterative) = solve_both(&Race::new(time, record));
+            prop_assert_eq!(quadratic, iterative);
+        }
+
+        #[test]
+        fn test_solvers_agree_on_achievable_records(time in 1..5000u64, hold in 0..5000u64, delta in 0..2u64) {
+            // Records exactly reachable by some hold time, and one below.
+            let hold = hold % (time + 1);
+            let record = (hold * (time - hold)).saturating_sub(delta);
+            let race = Race::new(time, record);
+            let (quadratic, iterative) = solve_both(&race);
+            prop_assert_eq!(quadratic, iterative);
+            prop_assert_eq!(check_number_of_winning_hold_times(&race, &|_| {}).ok(), quadratic);
+            if let Some(count) = quadratic {
+                let min = find_minimum_winning_race(&race).unwrap();
+                prop_assert!(race.wins(min).unwrap() && !race.wins(min - 1).unwrap());
+                prop_assert_eq!(find_maximum_winning_race(&race).unwrap() - min + 1, count);
+            }
+        }
+    }
+}
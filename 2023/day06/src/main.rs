@@ -1,112 +1,221 @@
-use std::io::BufRead;
+use std::fs;
+use std::process::ExitCode;
+use std::time::Instant;
 
 use anyhow::{anyhow, Result, Context};
+use aoc_utils::error::AocError;
+use aoc_utils::lenient::LinePolicy;
+use aoc_utils::lines::{lines, str_lines};
+use aoc_utils::output::{ColorChoice, Format, Output, Timings};
+use aoc_utils::progress;
+use aoc_utils::source::{InputArg, InputSource};
+use clap::Parser;
+
+use day06::{
+    check_number_of_winning_hold_times, find_number_of_winning_hold_times, find_number_of_winning_hold_times_iterative,
+    plot, read_races, validate,
+};
+
+#[cfg(feature = "fast-alloc")]
+#[global_allocator]
+static ALLOC: aoc_utils::alloc::MiMalloc = aoc_utils::alloc::MiMalloc;
+
+#[derive(Parser)]
+struct Args {
+    /// Ignore the spaces between digits, reading a single race (part 2).
+    #[arg(long)]
+    kerning: bool,
+    /// Search every hold time instead of solving for the winning ones.
+    #[arg(long)]
+    iterative: bool,
+    /// Count each race's winning hold times both ways, failing unless they
+    /// agree.
+    #[arg(long, conflicts_with = "iterative")]
+    check: bool,
+    /// Draw each race's distance for every hold time.
+    #[arg(long)]
+    plot: bool,
+    /// Skip lines that don't parse, with a warning, instead of failing.
+    #[arg(long)]
+    lenient: bool,
+    /// Check the whole input, or each of the inputs, reporting every error,
+    /// instead of solving.
+    #[arg(long)]
+    validate_only: bool,
+    /// Fail on input that isn't UTF-8, giving the offset of the first bad
+    /// byte, instead of replacing the bad bytes.
+    #[arg(long)]
+    strict_utf8: bool,
+    /// Print only the answer, for scripts.
+    #[arg(short, long, conflicts_with = "format")]
+    quiet: bool,
+    /// How to print the answer.
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Print how long reading the races and solving them took.
+    #[arg(long)]
+    time: bool,
+    /// When to color the output.
+    #[arg(long, value_enum, default_value_t, value_name = "WHEN")]
+    color: ColorChoice,
+    /// Map each input file into memory, or stdin when it's redirected from
+    /// one, rather than reading it.
+    #[arg(long)]
+    mmap: bool,
+    #[command(flatten)]
+    input: InputArg,
+    /// Inputs to solve in a batch instead of the one input: files,
+    /// directories of them, "-" for stdin, URLs, or "clipboard:".
+    #[arg(conflicts_with = "input")]
+    inputs: Vec<InputSource>,
+}
 
-fn main() -> Result<()> {
-    let stdin = std::io::stdin();
-    let mut line_iter = stdin.lock().lines();
-    
-    let races = read_races(&mut line_iter)?;
-
-    let mut multiplied_times = 1;
-    for race in races {
-        let win_range = find_number_of_winning_hold_times(&race)
-            .with_context(|| format!("Error with race {race:?}"))?;
-        println!("Winning range: {}", win_range);
-        multiplied_times *= win_range;
-    }
-    println!("Multiplied winning time possibilities: {}", multiplied_times);
-
-    Ok(())
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let output = Output::new(args.quiet).show_time(args.time).color(args.color).format(args.format);
+    output.exit(run(&args, output))
 }
 
-/// Read the races from the file.
-fn read_races(line_iter: &mut dyn Iterator<Item = std::io::Result<String>>) -> Result<Vec<Race>> {
-    let time_line = line_iter
-        .next()
-        .ok_or_else(|| anyhow!("The file is missing the 'time' line"))?.unwrap();
-    let time_line = trim_line_prefix(&time_line, "Time: ")?.trim();
-    let distance_line = line_iter
-        .next()
-        .ok_or_else(|| anyhow!("The file is missing the 'distance' line"))?.unwrap();
-    let distance_line = trim_line_prefix(&distance_line, "Distance: ")?.trim();
-
-    let times: Vec<u32> = time_line.split_ascii_whitespace().map(|t| t.parse::<u32>()).collect::<Result<_, _>>()?;
-    let distances: Vec<u32> = distance_line.split_ascii_whitespace().map(|d| d.parse()).collect::<Result<_, _>>()?;
-
-    if times.len() != distances.len() {
-        return Err(anyhow!(
-            "Number of times ({}) must match the number of distances ({})",
-            times.len(),
-            distances.len()
-        ));
+fn run(args: &Args, output: Output) -> Result<()> {
+    if args.validate_only {
+        return validate_all(args);
     }
-
-    let mut races = Vec::with_capacity(times.len());
-    for i in 0..times.len() {
-        races.push(Race::new(times[i], distances[i]));
+    if !args.inputs.is_empty() {
+        return solve_batch(args, output);
     }
-    Ok(races)
+
+    let mut timings = Timings::new();
+    let multiplied_times = read_and_solve(&args.input.source, args, output.details(), &mut timings)?;
+    output.answer("Multiplied winning time possibilities", multiplied_times);
+    output.timings(&timings);
+
+    Ok(())
 }
 
-fn trim_line_prefix<'a>(line: &'a str, prefix: &str) -> Result<&'a str> {
-    if line.starts_with(prefix) {
-        Ok(&line[prefix.len()..])
+/// Read the input, mapping it with `--mmap`, and solve it as by `solve`.
+fn read_and_solve(source: &InputSource, args: &Args, verbose: bool, timings: &mut Timings) -> Result<u64> {
+    if args.mmap {
+        let input = timings.time("read", || source.map())?;
+        solve(&mut str_lines(&input.text(args.strict_utf8)?), args, verbose, timings)
     } else {
-        Err(anyhow!("The line '{}' does not start with the prefix '{}'", line, prefix))
+        let input = timings.time("read", || source.read())?;
+        solve(&mut lines(&input[..]).strict_utf8(args.strict_utf8), args, verbose, timings)
     }
 }
 
-/// Finds the range of button hold times possible to win the race.
-fn find_number_of_winning_hold_times(race: &Race) -> Result<u32> {
-    let min_win_hold_time = find_minimum_winning_race(race)?;
-    let max_win_hold_time = find_maximum_winning_race(race)?;
-
-    Ok(max_win_hold_time - min_win_hold_time + 1)
+/// Solve the races in the input, returning the product of the number of ways
+/// to win each. With `verbose`, each race's result is printed. The time taken
+/// is recorded in `timings`.
+fn solve<L: AsRef<str>>(
+    line_iter: &mut dyn Iterator<Item = Result<L, AocError>>,
+    args: &Args,
+    verbose: bool,
+    timings: &mut Timings,
+) -> Result<u64> {
+    let mut policy = LinePolicy::new(args.lenient);
+    let races = timings.time("parse", || read_races(line_iter, args.kerning, &mut policy))?;
+    policy.report();
+
+    let start = Instant::now();
+    let mut multiplied_times: u64 = 1;
+    for race in races {
+        if args.plot {
+            print!("{}", plot(&race, 60, 15)?);
+        }
+        let win_range = if args.check {
+            progress::with_bar(race.time, |on_progress| check_number_of_winning_hold_times(&race, on_progress))
+        } else if args.iterative {
+            progress::with_bar(race.time, |on_progress| find_number_of_winning_hold_times_iterative(&race, on_progress))
+        } else {
+            find_number_of_winning_hold_times(&race, false)
+        }
+        .with_context(|| format!("Error with race {race:?}"))?;
+        if verbose {
+            println!("Winning range: {}", win_range);
+        }
+        multiplied_times = win_range
+            .checked_mul(multiplied_times)
+            .ok_or_else(|| anyhow!("The product of the winning ranges overflows"))?;
+    }
+    timings.record("solve", start.elapsed());
+    Ok(multiplied_times)
 }
 
-/// Finds the minimum button hold time to win the race.
-fn find_minimum_winning_race(race: &Race) -> Result<u32> {
-    let mut time_iter = (1..race.time - 1).into_iter();
-    find_first_winning_race_iter(race, &mut time_iter)
-}
+/// Check each batch input, and the files in each directory, or the one input
+/// when there are none, failing if any of them is invalid.
+fn validate_all(args: &Args) -> Result<()> {
+    if args.inputs.is_empty() {
+        return validate(args.input.source.open()?).print();
+    }
 
-/// Finds the maxmimum button hold time to win the race.
-fn find_maximum_winning_race(race: &Race) -> Result<u32> {
-    let mut time_iter = (1..race.time - 1).rev().into_iter();
-    find_first_winning_race_iter(race, &mut time_iter)
+    let files = batch_files(&args.inputs)?;
+    let mut invalid = 0;
+    for file in &files {
+        println!("{file}:");
+        let valid = match file.open() {
+            Ok(reader) => validate(reader).print().is_ok(),
+            Err(e) => {
+                eprintln!("{file}: error: {e:#}");
+                false
+            }
+        };
+        invalid += !valid as usize;
+    }
+    if invalid > 0 {
+        return Err(anyhow!("{invalid} of {} files are invalid", files.len()));
+    }
+    Ok(())
 }
 
-/// Find the button hold time for the first winning race in the time iterator.
-fn find_first_winning_race_iter(race: &Race, time_iter: &mut dyn Iterator<Item = u32>) -> Result<u32> {
-    let winning_distance = race.record_distance;
-    for button_hold_time in time_iter {
-        let distance = race.calculate_distance(button_hold_time);
-        if distance > winning_distance {
-            return Ok(button_hold_time);
+/// The inputs, with each directory replaced by the files in it.
+fn batch_files(inputs: &[InputSource]) -> Result<Vec<InputSource>> {
+    let mut files = Vec::new();
+    for input in inputs {
+        match input.path() {
+            Some(path) if path.is_dir() => {
+                let mut entries = fs::read_dir(path)
+                    .with_context(|| format!("Error reading '{}'", path.display()))?
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                entries.retain(|path| path.is_file());
+                entries.sort();
+                files.extend(entries.into_iter().map(InputSource::File));
+            }
+            _ => files.push(input.clone()),
         }
     }
-    
-    Err(anyhow!("There's no way to win this race. {race:?}"))
+    Ok(files)
 }
 
-#[derive(Debug)]
-struct Race {
-    time: u32,
-    record_distance: u32,
-}
-
-impl Race {
-    fn new(time: u32, distance: u32) -> Self {
-        Self { time, record_distance: distance }
+/// Solve each input, and the files in each directory, printing each product
+/// and then statistics over all of them.
+fn solve_batch(args: &Args, output: Output) -> Result<()> {
+    let files = batch_files(&args.inputs)?;
+    let mut products = Vec::new();
+    let mut failures = 0;
+    for file in &files {
+        let mut timings = Timings::new();
+        let result = read_and_solve(file, args, false, &mut timings);
+        match result {
+            Ok(product) => {
+                output.answer(&file.to_string(), product);
+                output.timings(&timings);
+                products.push(product);
+            }
+            Err(e) => {
+                eprintln!("{file}: error: {e:#}");
+                failures += 1;
+            }
+        }
     }
 
-    /// Calculate the distance traveled for the amount of time holding the button.
-    fn calculate_distance(&self, button_hold_time: u32) -> u32 {
-        assert!(button_hold_time <= self.time);
-        let travel_time = self.time - button_hold_time;
-
-        // The button_hold_time is the speed.
-        travel_time * button_hold_time
+    output.detail(format!("Files: {}, solved: {}, failed: {failures}", files.len(), products.len()));
+    if let (Some(min), Some(max)) = (products.iter().min(), products.iter().max()) {
+        let mean = products.iter().map(|&product| product as f64).sum::<f64>() / products.len() as f64;
+        output.detail(format!("Min: {min}, max: {max}, mean: {mean:.1}"));
+    }
+    if failures > 0 {
+        return Err(anyhow!("{failures} of {} files failed", files.len()));
     }
+    Ok(())
 }
@@ -0,0 +1,5 @@
+//! The `fast-alloc` feature's global allocator, for the binaries to install.
+//! mimalloc is faster than the system allocator at the many small, short-lived
+//! allocations the parsers make.
+
+pub use mimalloc::MiMalloc;
@@ -0,0 +1,85 @@
+//! Known-correct answers, kept in `answers/<year>.toml` so every solver can
+//! be checked against its real input as a regression suite.
+//!
+//! ```toml
+//! [day01]
+//! part1 = 56049
+//! part2 = "54530"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::submit::{self, History};
+
+/// A year's answers file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, Parts>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Parts {
+    part1: Option<toml::Value>,
+    part2: Option<toml::Value>,
+}
+
+impl Answers {
+    /// Read an answers file. A missing file has no answers.
+    pub fn load(path: &Path) -> Result<Answers> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+        let text = fs::read_to_string(path).with_context(|| format!("Error reading '{}'", path.display()))?;
+        Answers::parse(&text).with_context(|| format!("Error parsing '{}'", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Answers> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// The answer for a part, if known. Numbers may be written bare or as
+    /// strings.
+    pub fn get(&self, day: u8, part: u8) -> Option<String> {
+        let parts = self.days.get(&format!("day{day:02}"))?;
+        let answer = match part {
+            1 => parts.part1.as_ref()?,
+            2 => parts.part2.as_ref()?,
+            _ => return None,
+        };
+        Some(match answer {
+            toml::Value::String(answer) => answer.clone(),
+            answer => answer.to_string(),
+        })
+    }
+}
+
+/// The expected answer for each part of a day, from the answers file, or
+/// else the one accepted when it was submitted.
+pub fn expected(config: &Config, year: u16, day: u8) -> Result<[Option<String>; 2]> {
+    let answers = Answers::load(&config.answers_path(year))?;
+    let history = History::load(&submit::default_history_dir()?, year, day)?;
+    Ok([1, 2].map(|part| answers.get(day, part).or_else(|| history.solution(part).map(String::from))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        let answers = Answers::parse("[day01]\npart1 = 56049\npart2 = \"54530\"\n\n[day04]\npart1 = 24848\n").unwrap();
+        assert_eq!(answers.get(1, 1).as_deref(), Some("56049"));
+        assert_eq!(answers.get(1, 2).as_deref(), Some("54530"));
+        assert_eq!(answers.get(4, 2), None);
+        assert_eq!(answers.get(5, 1), None);
+        assert!(Answers::parse("[day01]\npart3 = 1\n").is_err());
+    }
+}
@@ -0,0 +1,50 @@
+//! Parsing with a bump arena: the lines of an input are copied into one
+//! arena, freed all at once when the parse is done, rather than each into a
+//! `String` allocated and freed on its own.
+
+use std::io::BufRead;
+
+pub use bumpalo::Bump;
+
+use crate::error::Result;
+use crate::lines::Lines;
+
+/// The lines of a reader, read as by [`Lines`], in an arena.
+pub struct ArenaLines<'a, R> {
+    lines: Lines<R>,
+    arena: &'a Bump,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// Copy each line into `arena` rather than a new `String`.
+    pub fn in_arena(self, arena: &Bump) -> ArenaLines<'_, R> {
+        ArenaLines { lines: self, arena }
+    }
+}
+
+impl<'a, R: BufRead> Iterator for ArenaLines<'a, R> {
+    type Item = Result<&'a str>;
+
+    fn next(&mut self) -> Option<Result<&'a str>> {
+        let arena = self.arena;
+        self.lines.read_line().map(|line| line.map(|line| &*arena.alloc_str(line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lines::lines;
+
+    use super::*;
+
+    #[test]
+    fn test_in_arena() {
+        let arena = Bump::new();
+        let read: Vec<&str> = lines("\u{feff}a \r\nbc\n\nd".as_bytes()).in_arena(&arena).collect::<Result<_>>().unwrap();
+        assert_eq!(read, ["a", "bc", "", "d"]);
+        assert!(arena.allocated_bytes() > 0);
+
+        let error = lines(&b"ok\n\xff\n"[..]).strict_utf8(true).in_arena(&arena).nth(1).unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2, column 1: Invalid UTF-8 at byte offset 3");
+    }
+}
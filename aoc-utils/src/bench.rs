@@ -0,0 +1,190 @@
+//! A local history of benchmark runs, so a run can be compared with an
+//! earlier one to spot regressions.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::stats::Stats;
+
+/// A stage's times over a benchmark's runs, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StageTimes {
+    pub min_ms: f64,
+    pub mean_ms: f64,
+    pub median_ms: f64,
+    pub stddev_ms: f64,
+}
+
+impl From<Stats> for StageTimes {
+    fn from(stats: Stats) -> StageTimes {
+        let ms = |time: std::time::Duration| time.as_secs_f64() * 1000.0;
+        StageTimes {
+            min_ms: ms(stats.min),
+            mean_ms: ms(stats.mean),
+            median_ms: ms(stats.median),
+            stddev_ms: ms(stats.stddev),
+        }
+    }
+}
+
+/// One benchmark of a year's days.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchRun {
+    /// `git describe` of the code benchmarked.
+    pub revision: String,
+    /// Unix timestamp of the run.
+    pub time: u64,
+    /// How many times each day was solved.
+    pub runs: u32,
+    /// Each stage's times, by stage ("parse", "part 1", ...), by day
+    /// ("day05").
+    pub days: BTreeMap<String, BTreeMap<String, StageTimes>>,
+}
+
+/// Every benchmark run of a year, oldest first, stored as JSON on disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(skip)]
+    path: PathBuf,
+    pub runs: Vec<BenchRun>,
+}
+
+impl History {
+    /// Load the history for the year from `dir`, or an empty history if
+    /// nothing has been benchmarked yet.
+    pub fn load(dir: &Path, year: u16) -> Result<History> {
+        let path = dir.join(format!("{year}.json"));
+        let mut history: History = if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Error reading '{}'", path.display()))?;
+            serde_json::from_str(&text)
+                .with_context(|| format!("Error parsing '{}'", path.display()))?
+        } else {
+            History::default()
+        };
+        history.path = path;
+        Ok(history)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string_pretty(self)?;
+        fs::write(&self.path, text)
+            .with_context(|| format!("Error writing '{}'", self.path.display()))
+    }
+
+    pub fn record(&mut self, revision: &str, runs: u32, days: BTreeMap<String, BTreeMap<String, StageTimes>>) -> Result<()> {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        self.runs.push(BenchRun { revision: revision.to_string(), time, runs, days });
+        Ok(())
+    }
+
+    /// Find a run by its number, counting from 1, a negative number counting
+    /// back from the latest, or its revision, the latest if there are
+    /// several.
+    pub fn find(&self, run: &str) -> Result<&BenchRun> {
+        let found = match run.parse::<i64>() {
+            Ok(n) if n > 0 => self.runs.get(n as usize - 1),
+            Ok(n) if n < 0 => self.runs.len().checked_sub(n.unsigned_abs() as usize).and_then(|i| self.runs.get(i)),
+            _ => self.runs.iter().rev().find(|found| found.revision == run),
+        };
+        found.ok_or_else(|| anyhow!("There's no benchmark run '{run}' in '{}'", self.path.display()))
+    }
+}
+
+/// Where benchmark runs are recorded by default.
+pub fn default_history_dir() -> Result<PathBuf> {
+    let dir = dirs::data_local_dir().ok_or_else(|| anyhow!("Unable to find a data directory"))?;
+    Ok(dir.join("aoc").join("benches"))
+}
+
+/// A stage's median time in two runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub day: String,
+    pub stage: String,
+    pub before_ms: f64,
+    pub after_ms: f64,
+}
+
+impl Change {
+    /// How much slower the stage got, as a fraction: 0.1 is 10% slower, and
+    /// -0.5 twice as fast.
+    pub fn slowdown(&self) -> f64 {
+        if self.before_ms == 0.0 {
+            0.0
+        } else {
+            self.after_ms / self.before_ms - 1.0
+        }
+    }
+}
+
+/// The change in every stage benchmarked in both runs.
+pub fn compare(before: &BenchRun, after: &BenchRun) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for (day, stages) in &after.days {
+        for (stage, times) in stages {
+            if let Some(earlier) = before.days.get(day).and_then(|stages| stages.get(stage)) {
+                changes.push(Change {
+                    day: day.clone(),
+                    stage: stage.clone(),
+                    before_ms: earlier.median_ms,
+                    after_ms: times.median_ms,
+                });
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(revision: &str, day05: f64, day06: Option<f64>) -> BenchRun {
+        let times = |median_ms| StageTimes { min_ms: median_ms, mean_ms: median_ms, median_ms, stddev_ms: 0.0 };
+        let mut days = BTreeMap::new();
+        days.insert("day05".to_string(), BTreeMap::from([("total".to_string(), times(day05))]));
+        if let Some(day06) = day06 {
+            days.insert("day06".to_string(), BTreeMap::from([("total".to_string(), times(day06))]));
+        }
+        BenchRun { revision: revision.to_string(), time: 0, runs: 10, days }
+    }
+
+    #[test]
+    fn test_compare() {
+        let changes = compare(&run("v1", 10.0, None), &run("v2", 12.0, Some(1.0)));
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].day.as_str(), changes[0].stage.as_str()), ("day05", "total"));
+        assert!((changes[0].slowdown() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_history() {
+        let dir = std::env::temp_dir().join(format!("aoc-bench-test-{}", std::process::id()));
+        let mut history = History::load(&dir, 2023).unwrap();
+        assert!(history.runs.is_empty());
+        for revision in ["v1", "v2", "v1"] {
+            history.runs.push(run(revision, 1.0, None));
+        }
+        history.runs[2].time = 3;
+        history.save().unwrap();
+
+        let history = History::load(&dir, 2023).unwrap();
+        assert_eq!(history.runs.len(), 3);
+        assert_eq!(history.find("2").unwrap().revision, "v2");
+        assert_eq!(history.find("-1").unwrap().time, 3);
+        assert_eq!(history.find("v1").unwrap().time, 3);
+        assert!(history.find("4").is_err());
+        assert!(history.find("-4").is_err());
+        assert!(history.find("v3").is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,37 @@
+//! Criterion benchmarks of a day's solver. Parsing and solving each part are
+//! timed in separate groups, so it's clear which one a change speeds up.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion};
+
+use crate::cancel::Cancel;
+use crate::error::Result;
+use crate::solver::{Answer, Solver};
+
+/// Time parsing, then each part, on each of the named inputs, in the groups
+/// "parse", "part1", and "part2".
+pub fn solver<S: Solver>(c: &mut Criterion, solver: &S, inputs: &[(String, String)]) {
+    let mut group = c.benchmark_group("parse");
+    for (name, input) in inputs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), input, |b, input| {
+            b.iter(|| solver.parse(black_box(input)).unwrap())
+        });
+    }
+    group.finish();
+
+    let parsed: Vec<_> = inputs.iter().map(|(name, input)| (name, solver.parse(input).unwrap())).collect();
+    part(c, "part1", &parsed, |parsed, cancel| solver.part1(parsed, cancel));
+    part(c, "part2", &parsed, |parsed, cancel| solver.part2(parsed, cancel));
+}
+
+/// Time a part on each input, parsed beforehand.
+fn part<P>(c: &mut Criterion, name: &str, inputs: &[(&String, P)], solve: impl Fn(&P, &Cancel) -> Result<Answer>) {
+    let mut group = c.benchmark_group(name);
+    for (name, parsed) in inputs {
+        group.bench_with_input(BenchmarkId::from_parameter(name), parsed, |b, parsed| {
+            b.iter(|| solve(black_box(parsed), &Cancel::new()).unwrap())
+        });
+    }
+    group.finish();
+}
@@ -0,0 +1,141 @@
+//! A local cache of answers, keyed by the puzzle, the part, and a hash of
+//! the input, so solving an input already solved is instant.
+//!
+//! Each answer is stamped with the build that found it, and a different
+//! build ignores it, so changing a solver never serves a stale answer.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::solver::Answer;
+
+/// A cached answer and the build that found it.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    build: String,
+    answer: Answer,
+}
+
+/// Answers cached on disk, one file each.
+pub struct AnswerCache {
+    dir: PathBuf,
+    build: String,
+}
+
+impl AnswerCache {
+    /// A cache in `dir` whose answers are only used by the same `build`.
+    pub fn new(dir: &Path, build: &str) -> AnswerCache {
+        AnswerCache { dir: dir.to_path_buf(), build: build.to_string() }
+    }
+
+    /// The cache in the default directory, for the running build.
+    pub fn open() -> Result<AnswerCache> {
+        Ok(AnswerCache::new(&default_dir()?, &build_stamp()?))
+    }
+
+    /// The hash of an input that answers are keyed by.
+    pub fn hash(input: &str) -> String {
+        blake3::hash(input.as_bytes()).to_hex().to_string()
+    }
+
+    fn path(&self, year: u16, day: u8, part: u8, hash: &str) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("day{day:02}")).join(format!("part{part}-{hash}.json"))
+    }
+
+    /// The answer to a part for the input with this hash, if this build
+    /// has cached one. An unreadable entry is treated as missing.
+    pub fn get(&self, year: u16, day: u8, part: u8, hash: &str) -> Option<Answer> {
+        let text = fs::read_to_string(self.path(year, day, part, hash)).ok()?;
+        let entry: Entry = serde_json::from_str(&text).ok()?;
+        (entry.build == self.build).then_some(entry.answer)
+    }
+
+    /// Cache the answer to a part for the input with this hash.
+    pub fn put(&self, year: u16, day: u8, part: u8, hash: &str, answer: &Answer) -> Result<()> {
+        let path = self.path(year, day, part, hash);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Error creating cache directory '{}'", parent.display()))?;
+        }
+        let entry = Entry { build: self.build.clone(), answer: answer.clone() };
+        fs::write(&path, serde_json::to_string(&entry)?).with_context(|| format!("Error writing '{}'", path.display()))
+    }
+
+    /// Remove every cached answer, or only a year's, returning how many
+    /// were removed.
+    pub fn clear(&self, year: Option<u16>) -> Result<usize> {
+        let dir = match year {
+            Some(year) => self.dir.join(year.to_string()),
+            None => self.dir.clone(),
+        };
+        let removed = count_files(&dir)?;
+        match fs::remove_dir_all(&dir) {
+            Err(error) if error.kind() != ErrorKind::NotFound => {
+                Err(error).with_context(|| format!("Error removing '{}'", dir.display()))
+            }
+            _ => Ok(removed),
+        }
+    }
+}
+
+/// How many files are under a directory, which needn't exist.
+fn count_files(dir: &Path) -> Result<usize> {
+    let entries = match fs::read_dir(dir) {
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(0),
+        entries => entries.with_context(|| format!("Error reading '{}'", dir.display()))?,
+    };
+    let mut count = 0;
+    for entry in entries {
+        let entry = entry?;
+        count += if entry.file_type()?.is_dir() { count_files(&entry.path())? } else { 1 };
+    }
+    Ok(count)
+}
+
+/// The default directory for cached answers.
+pub fn default_dir() -> Result<PathBuf> {
+    Ok(crate::http::default_cache_dir()?.join("answers"))
+}
+
+/// A stamp that changes whenever the running binary is rebuilt: its size
+/// and modification time.
+pub fn build_stamp() -> Result<String> {
+    let exe = std::env::current_exe().context("Error finding the running binary")?;
+    let metadata = fs::metadata(&exe).with_context(|| format!("Error reading '{}'", exe.display()))?;
+    let modified = metadata.modified()?.duration_since(UNIX_EPOCH).map_err(|_| anyhow!("The binary is from before 1970"))?;
+    Ok(format!("{}-{}", metadata.len(), modified.as_nanos()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-test-{}", std::process::id()));
+        let cache = AnswerCache::new(&dir, "v1");
+        let hash = AnswerCache::hash("seeds: 79 14 55 13\n");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(cache.get(2023, 5, 1, &hash), None);
+
+        cache.put(2023, 5, 1, &hash, &Answer::Unsigned(35)).unwrap();
+        cache.put(2023, 5, 2, &hash, &Answer::Text("ABC".to_string())).unwrap();
+        cache.put(2022, 1, 1, &hash, &Answer::Unsigned(1)).unwrap();
+        assert_eq!(cache.get(2023, 5, 1, &hash), Some(Answer::Unsigned(35)));
+        assert_eq!(cache.get(2023, 5, 2, &hash), Some(Answer::Text("ABC".to_string())));
+        assert_eq!(cache.get(2023, 5, 1, &AnswerCache::hash("")), None);
+        assert_eq!(AnswerCache::new(&dir, "v2").get(2023, 5, 1, &hash), None);
+
+        assert_eq!(cache.clear(Some(2023)).unwrap(), 2);
+        assert_eq!(cache.get(2023, 5, 1, &hash), None);
+        assert_eq!(cache.get(2022, 1, 1, &hash), Some(Answer::Unsigned(1)));
+        assert_eq!(cache.clear(None).unwrap(), 1);
+        assert_eq!(cache.clear(None).unwrap(), 0);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,63 @@
+//! Asking a running solver to stop, when it's taken too long. Solvers check
+//! the token in their loops, so cancelling is cooperative.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use web_time::Instant;
+
+use crate::error::{AocError, Result};
+
+/// A cancellation token. Clones share whether it's cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct Cancel {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Cancel {
+    /// A token that's only cancelled by `cancel`.
+    pub fn new() -> Cancel {
+        Cancel::default()
+    }
+
+    /// A token that's also cancelled once `timeout` has passed.
+    pub fn with_timeout(timeout: Duration) -> Cancel {
+        Cancel { deadline: Instant::now().checked_add(timeout), ..Cancel::default() }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Fail with `AocError::Cancelled` if cancelled, for checking with `?`.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(AocError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel() {
+        let cancel = Cancel::new();
+        assert!(cancel.check().is_ok());
+        cancel.clone().cancel();
+        assert!(matches!(cancel.check(), Err(AocError::Cancelled)));
+
+        assert!(Cancel::with_timeout(Duration::ZERO).is_cancelled());
+        assert!(!Cancel::with_timeout(Duration::from_secs(60)).is_cancelled());
+        assert!(!Cancel::with_timeout(Duration::MAX).is_cancelled());
+    }
+}
@@ -0,0 +1,43 @@
+//! Differential testing: solving the same input in more than one way, such
+//! as a brute force and a clever solution, and failing loudly if the ways
+//! disagree.
+
+use std::fmt::Display;
+
+use crate::error::{AocError, Context, Result};
+
+/// Solve with each of the named `solutions` in turn, returning the answer if
+/// they all give the same one, or [`AocError::Disagreement`] naming the
+/// first that differs.
+pub fn agree<T: PartialEq + Display>(solutions: &[(&str, &dyn Fn() -> Result<T>)]) -> Result<T> {
+    let mut agreed: Option<(&str, T)> = None;
+    for &(name, solve) in solutions {
+        let answer = solve().with_context(|| format!("With {name}"))?;
+        match &agreed {
+            Some((first, expected)) if *expected != answer => {
+                return Err(AocError::disagreement(format!("{first} gave {expected}, but {name} gave {answer}")));
+            }
+            Some(_) => {}
+            None => agreed = Some((name, answer)),
+        }
+    }
+    agreed.map(|(_, answer)| answer).ok_or_else(|| AocError::no_solution("Nothing to check"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_agree() {
+        assert_eq!(agree(&[("one", &|| Ok(1)), ("other", &|| Ok(1))]).unwrap(), 1);
+
+        let error = agree(&[("quadratic", &|| Ok(3)), ("iterative", &|| Ok(3)), ("guess", &|| Ok(4))]).unwrap_err();
+        assert!(matches!(error, AocError::Disagreement(_)));
+        assert_eq!(error.to_string(), "quadratic gave 3, but guess gave 4");
+
+        let error = agree(&[("one", &|| Ok(1)), ("broken", &|| Err(AocError::no_solution("No seeds")))]).unwrap_err();
+        assert_eq!(error.to_string(), "With broken: No seeds");
+        assert!(agree::<u64>(&[]).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! A long search's progress saved to a file now and then, so a run that's
+//! interrupted can resume where it left off instead of starting over.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// How often progress is saved by default.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// What's written to the file: the state, and the hash of the input it's
+/// for, so it isn't resumed on another.
+#[derive(Serialize, Deserialize)]
+struct Saved<T> {
+    input: String,
+    state: T,
+}
+
+/// A checkpoint file for a search of one input.
+pub struct Checkpoint {
+    path: PathBuf,
+    input: String,
+    interval: Duration,
+    last_saved: Mutex<Instant>,
+}
+
+impl Checkpoint {
+    /// A checkpoint at `path` for a search of `input`, which can be anything
+    /// identifying it, such as its text.
+    pub fn new(path: &Path, input: &[u8]) -> Checkpoint {
+        Checkpoint {
+            path: path.to_path_buf(),
+            input: blake3::hash(input).to_hex().to_string(),
+            interval: DEFAULT_INTERVAL,
+            last_saved: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// How often [`Checkpoint::save_due`] saves.
+    pub fn interval(mut self, interval: Duration) -> Checkpoint {
+        self.interval = interval;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved state, if there is a file. Fails if the file is for
+    /// another input.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>> {
+        let text = match fs::read_to_string(&self.path) {
+            Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+            text => text.with_context(|| format!("Error reading '{}'", self.path.display()))?,
+        };
+        let saved: Saved<T> =
+            serde_json::from_str(&text).with_context(|| format!("Error parsing '{}'", self.path.display()))?;
+        if saved.input != self.input {
+            return Err(anyhow!("The checkpoint '{}' is for a different input", self.path.display()));
+        }
+        Ok(Some(saved.state))
+    }
+
+    /// Save the state, replacing the file only once it's fully written so
+    /// an interruption can't leave it half written.
+    pub fn save<T: Serialize>(&self, state: &T) -> Result<()> {
+        let mut last_saved = self.last_saved.lock().unwrap();
+        let text = serde_json::to_string(&Saved { input: self.input.clone(), state })?;
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".partial");
+        fs::write(&partial, text).with_context(|| format!("Error writing '{}'", self.path.display()))?;
+        fs::rename(&partial, &self.path).with_context(|| format!("Error writing '{}'", self.path.display()))?;
+        *last_saved = Instant::now();
+        Ok(())
+    }
+
+    /// Save the state if it's been at least the interval since the last
+    /// save, returning whether it did. The state is only made if it's due.
+    pub fn save_due<T: Serialize>(&self, state: impl FnOnce() -> T) -> Result<bool> {
+        if self.last_saved.lock().unwrap().elapsed() < self.interval {
+            return Ok(false);
+        }
+        self.save(&state())?;
+        Ok(true)
+    }
+
+    /// Remove the file, once the search is done, if it's there.
+    pub fn remove(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => {
+                Err(error).with_context(|| format!("Error removing '{}'", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint() {
+        let path = std::env::temp_dir().join(format!("aoc-utils-checkpoint-{}.json", std::process::id()));
+        let checkpoint = Checkpoint::new(&path, b"seeds: 79 14 55 13").interval(Duration::from_secs(3600));
+        assert_eq!(checkpoint.load::<Vec<u64>>().unwrap(), None);
+        checkpoint.save(&vec![1, 2]).unwrap();
+        assert_eq!(checkpoint.load::<Vec<u64>>().unwrap(), Some(vec![1, 2]));
+        assert!(!checkpoint.save_due(|| vec![3]).unwrap());
+        assert_eq!(checkpoint.load::<Vec<u64>>().unwrap(), Some(vec![1, 2]));
+
+        let other = Checkpoint::new(&path, b"seeds: 1 2").interval(Duration::ZERO);
+        assert!(other.load::<Vec<u64>>().unwrap_err().to_string().contains("different input"));
+        assert!(other.save_due(|| vec![3]).unwrap());
+        assert_eq!(other.load::<Vec<u64>>().unwrap(), Some(vec![3]));
+
+        other.remove().unwrap();
+        other.remove().unwrap();
+        assert!(!path.exists());
+    }
+}
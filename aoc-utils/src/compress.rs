@@ -0,0 +1,114 @@
+//! Reading inputs compressed with gzip or zstd as if they weren't, so large
+//! or archived inputs needn't be unpacked first.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use flate2::bufread::MultiGzDecoder;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// The compression of an input starting with `start`, by its magic
+    /// bytes, or failing that by the extension of its `path`.
+    pub fn detect(start: &[u8], path: Option<&Path>) -> Compression {
+        if start.starts_with(GZIP_MAGIC) {
+            return Compression::Gzip;
+        }
+        if start.starts_with(ZSTD_MAGIC) {
+            return Compression::Zstd;
+        }
+        match path.and_then(|path| path.extension()).and_then(|extension| extension.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Wrap `reader` to decompress it if it is compressed. `path` is where it was
+/// read from, if anywhere.
+pub fn decompress<'a>(mut reader: impl BufRead + 'a, path: Option<&Path>) -> io::Result<Box<dyn BufRead + 'a>> {
+    Ok(match Compression::detect(reader.fill_buf()?, path) {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)),
+    })
+}
+
+/// Open a file, decompressing it if it is compressed.
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    decompress(BufReader::new(File::open(path)?), Some(path))
+}
+
+/// Read stdin, decompressing it if it is compressed.
+pub fn stdin() -> io::Result<Box<dyn BufRead>> {
+    decompress(io::stdin().lock(), None)
+}
+
+/// Read a whole file, decompressing it if it is compressed.
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    let mut input = Vec::new();
+    open(path)?.read_to_end(&mut input)?;
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use flate2::write::GzEncoder;
+
+    use super::*;
+
+    const INPUT: &str = "Time:      7  15   30\nDistance:  9  40  200\n";
+
+    fn gzip(input: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(input).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn read_all(reader: impl BufRead, path: Option<&Path>) -> String {
+        let mut read = String::new();
+        decompress(reader, path).unwrap().read_to_string(&mut read).unwrap();
+        read
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Compression::detect(&gzip(b"x"), None), Compression::Gzip);
+        assert_eq!(Compression::detect(&zstd::encode_all(&b"x"[..], 0).unwrap(), None), Compression::Zstd);
+        assert_eq!(Compression::detect(b"Time:", None), Compression::None);
+        assert_eq!(Compression::detect(b"", Some(Path::new("day06.txt.gz"))), Compression::Gzip);
+        assert_eq!(Compression::detect(b"", Some(Path::new("day06.zst"))), Compression::Zstd);
+        assert_eq!(Compression::detect(b"", Some(Path::new("day06.txt"))), Compression::None);
+    }
+
+    #[test]
+    fn test_decompress() {
+        assert_eq!(read_all(INPUT.as_bytes(), None), INPUT);
+        assert_eq!(read_all(&gzip(INPUT.as_bytes())[..], None), INPUT);
+        assert_eq!(read_all(&zstd::encode_all(INPUT.as_bytes(), 0).unwrap()[..], None), INPUT);
+
+        // Concatenated gzip files are read as one.
+        let mut twice = gzip(b"one\n");
+        twice.extend(gzip(b"two\n"));
+        assert_eq!(read_all(&twice[..], None), "one\ntwo\n");
+
+        let mut read = String::new();
+        let error = decompress(INPUT.as_bytes(), Some(Path::new("day06.gz")))
+            .and_then(|mut reader| reader.read_to_string(&mut read))
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
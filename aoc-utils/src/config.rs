@@ -0,0 +1,309 @@
+//! Configuration shared by the tools, read from `aoc.toml` files and the
+//! environment.
+//!
+//! Settings are taken from, in increasing priority:
+//! 1. `~/.config/aoc/aoc.toml`
+//! 2. `aoc.toml` in the current directory or the closest parent with one
+//! 3. `AOC_*` environment variables, which may also be set in a `.env` file
+//!
+//! ```toml
+//! session_file = "~/.config/aoc/session"
+//! year = 2023
+//! input_dir = "inputs"
+//! encrypt_inputs = true
+//! format = "text"
+//! threads = 4
+//!
+//! [budgets]
+//! day05 = "50ms"
+//! ```
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// The year used when none is configured.
+pub const DEFAULT_YEAR: u16 = 2023;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// File containing the session cookie.
+    pub session_file: Option<PathBuf>,
+    pub year: Option<u16>,
+    /// Directory holding inputs as `<year>/day<NN>.txt`. When unset, inputs
+    /// are read from `<year>/day<NN>/input.txt` next to each solution.
+    pub input_dir: Option<PathBuf>,
+    /// Store downloaded inputs encrypted.
+    pub encrypt_inputs: Option<bool>,
+    pub format: Option<OutputFormat>,
+    /// How many threads to solve with. Defaults to one per CPU.
+    pub threads: Option<NonZeroUsize>,
+    /// The longest each day may take to solve in `aoc bench`, like "50ms",
+    /// by day ("day05").
+    pub budgets: BTreeMap<String, String>,
+
+    /// The session cookie, only settable from the environment.
+    #[serde(skip)]
+    session: Option<String>,
+    /// Directory relative paths are resolved against.
+    #[serde(skip)]
+    root: PathBuf,
+}
+
+impl Config {
+    /// Load the configuration from the config files, `.env`, and the
+    /// environment.
+    pub fn load() -> Result<Config> {
+        let cwd = env::current_dir()?;
+        let mut config = Config {
+            root: cwd.clone(),
+            ..Config::default()
+        };
+
+        if let Some(dir) = dirs::config_dir() {
+            let path = dir.join("aoc").join(CONFIG_FILE);
+            if path.exists() {
+                config.merge(Config::from_file(&path)?);
+            }
+        }
+        if let Some(path) = find_upwards(&cwd, CONFIG_FILE) {
+            let file = Config::from_file(&path)?;
+            config.root = file.root.clone();
+            config.merge(file);
+        }
+
+        // A missing .env file is fine, a broken one isn't.
+        match dotenvy::dotenv() {
+            Ok(_) => {}
+            Err(e) if e.not_found() => {}
+            Err(e) => return Err(e).context("Error reading .env"),
+        }
+        config.apply_env(|name| env::var(name).ok())?;
+
+        Ok(config)
+    }
+
+    /// Read a config file, resolving relative paths against its directory.
+    pub fn from_file(path: &Path) -> Result<Config> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Error reading '{}'", path.display()))?;
+        let mut config: Config =
+            toml::from_str(&text).with_context(|| format!("Error parsing '{}'", path.display()))?;
+        config.root = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+        config.session_file = config.session_file.take().map(|p| config.resolve(&p));
+        config.input_dir = config.input_dir.take().map(|p| config.resolve(&p));
+        Ok(config)
+    }
+
+    /// Override settings with those set in `other`.
+    fn merge(&mut self, other: Config) {
+        self.session_file = other.session_file.or(self.session_file.take());
+        self.year = other.year.or(self.year);
+        self.input_dir = other.input_dir.or(self.input_dir.take());
+        self.encrypt_inputs = other.encrypt_inputs.or(self.encrypt_inputs);
+        self.format = other.format.or(self.format);
+        self.threads = other.threads.or(self.threads);
+        self.budgets.extend(other.budgets);
+    }
+
+    /// Override settings from `AOC_*` variables looked up with `var`.
+    fn apply_env(&mut self, var: impl Fn(&str) -> Option<String>) -> Result<()> {
+        if let Some(session) = var("AOC_SESSION") {
+            self.session = Some(session);
+        }
+        if let Some(path) = var("AOC_SESSION_FILE") {
+            self.session_file = Some(self.resolve(Path::new(&path)));
+        }
+        if let Some(year) = var("AOC_YEAR") {
+            self.year = Some(year.parse().with_context(|| format!("AOC_YEAR '{year}' must be a year"))?);
+        }
+        if let Some(path) = var("AOC_INPUT_DIR") {
+            self.input_dir = Some(self.resolve(Path::new(&path)));
+        }
+        if let Some(encrypt) = var("AOC_ENCRYPT_INPUTS") {
+            self.encrypt_inputs = Some(match encrypt.as_str() {
+                "1" | "true" => true,
+                "0" | "false" => false,
+                _ => return Err(anyhow!("AOC_ENCRYPT_INPUTS '{encrypt}' must be 'true' or 'false'")),
+            });
+        }
+        if let Some(format) = var("AOC_FORMAT") {
+            self.format = Some(
+                <OutputFormat as clap::ValueEnum>::from_str(&format, true)
+                    .map_err(|_| anyhow!("AOC_FORMAT '{format}' must be 'text' or 'json'"))?,
+            );
+        }
+        if let Some(threads) = var("AOC_THREADS") {
+            self.threads =
+                Some(threads.parse().map_err(|_| anyhow!("AOC_THREADS '{threads}' must be a number above 0"))?);
+        }
+        Ok(())
+    }
+
+    /// Expand `~` and make relative paths relative to the config root.
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if let Ok(rest) = path.strip_prefix("~") {
+            if let Some(home) = dirs::home_dir() {
+                return home.join(rest);
+            }
+        }
+        self.root.join(path)
+    }
+
+    /// The directory containing the repository's `aoc.toml`, or the current
+    /// directory without one.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn year(&self) -> u16 {
+        self.year.unwrap_or(DEFAULT_YEAR)
+    }
+
+    pub fn encrypt_inputs(&self) -> bool {
+        self.encrypt_inputs.unwrap_or(false)
+    }
+
+    pub fn format(&self) -> OutputFormat {
+        self.format.unwrap_or_default()
+    }
+
+    /// The session cookie, from `AOC_SESSION` or the session file, which
+    /// defaults to `~/.config/aoc/session`.
+    pub fn session(&self) -> Result<String> {
+        if let Some(session) = &self.session {
+            return Ok(session.clone());
+        }
+        let path = match &self.session_file {
+            Some(path) => path.clone(),
+            None => dirs::config_dir()
+                .ok_or_else(|| anyhow!("Unable to find the config directory"))?
+                .join("aoc")
+                .join("session"),
+        };
+        let session = fs::read_to_string(&path)
+            .with_context(|| format!("No AOC_SESSION set and unable to read '{}'", path.display()))?;
+        Ok(session.trim().to_string())
+    }
+
+    /// The file of known-correct answers for a year.
+    pub fn answers_path(&self, year: u16) -> PathBuf {
+        self.root.join("answers").join(format!("{year}.toml"))
+    }
+
+    /// Where the input for a puzzle is stored.
+    pub fn input_path(&self, year: u16, day: u8) -> PathBuf {
+        match &self.input_dir {
+            Some(dir) => dir.join(year.to_string()).join(format!("day{day:02}.txt")),
+            None => self.root.join(year.to_string()).join(format!("day{day:02}")).join("input.txt"),
+        }
+    }
+}
+
+/// Find `name` in `dir` or the closest of its parents.
+fn find_upwards(dir: &Path, name: &str) -> Option<PathBuf> {
+    dir.ancestors().map(|dir| dir.join(name)).find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config: Config =
+            toml::from_str("year = 2022\ninput_dir = \"inputs\"\nformat = \"json\"\nthreads = 4\n").unwrap();
+        assert_eq!(config.year, Some(2022));
+        assert_eq!(config.input_dir, Some(PathBuf::from("inputs")));
+        assert_eq!(config.format, Some(OutputFormat::Json));
+        assert_eq!(config.threads, NonZeroUsize::new(4));
+        assert_eq!(config.session_file, None);
+        assert!(config.budgets.is_empty());
+        assert!(toml::from_str::<Config>("unknown = 1").is_err());
+        assert!(toml::from_str::<Config>("threads = 0").is_err());
+
+        let config: Config = toml::from_str("[budgets]\nday05 = \"50ms\"\n").unwrap();
+        assert_eq!(config.budgets["day05"], "50ms");
+    }
+
+    #[test]
+    fn test_merge() {
+        let budgets = |budgets: &[(&str, &str)]| {
+            budgets.iter().map(|(day, budget)| (day.to_string(), budget.to_string())).collect()
+        };
+        let mut config = Config {
+            year: Some(2022),
+            format: Some(OutputFormat::Json),
+            budgets: budgets(&[("day05", "50ms"), ("day06", "1ms")]),
+            ..Config::default()
+        };
+        config.merge(Config {
+            year: Some(2023),
+            input_dir: Some(PathBuf::from("inputs")),
+            budgets: budgets(&[("day06", "2ms")]),
+            ..Config::default()
+        });
+        assert_eq!(config.budgets, budgets(&[("day05", "50ms"), ("day06", "2ms")]));
+        assert_eq!(config.year, Some(2023));
+        assert_eq!(config.format, Some(OutputFormat::Json));
+        assert_eq!(config.input_dir, Some(PathBuf::from("inputs")));
+    }
+
+    #[test]
+    fn test_apply_env() {
+        let vars = HashMap::from([
+            ("AOC_SESSION", "abc"),
+            ("AOC_YEAR", "2021"),
+            ("AOC_INPUT_DIR", "in"),
+            ("AOC_ENCRYPT_INPUTS", "true"),
+            ("AOC_FORMAT", "JSON"),
+            ("AOC_THREADS", "2"),
+        ]);
+        let mut config = Config {
+            root: PathBuf::from("/repo"),
+            year: Some(2023),
+            ..Config::default()
+        };
+        config.apply_env(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(config.session().unwrap(), "abc");
+        assert_eq!(config.year(), 2021);
+        assert_eq!(config.input_dir, Some(PathBuf::from("/repo/in")));
+        assert!(config.encrypt_inputs());
+        assert_eq!(config.format(), OutputFormat::Json);
+        assert_eq!(config.threads, NonZeroUsize::new(2));
+
+        assert!(config.apply_env(|name| (name == "AOC_YEAR").then(|| "soon".to_string())).is_err());
+        assert!(config.apply_env(|name| (name == "AOC_FORMAT").then(|| "xml".to_string())).is_err());
+        assert!(config.apply_env(|name| (name == "AOC_THREADS").then(|| "0".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_input_path() {
+        let mut config = Config {
+            root: PathBuf::from("/repo"),
+            ..Config::default()
+        };
+        assert_eq!(config.input_path(2023, 5), PathBuf::from("/repo/2023/day05/input.txt"));
+        config.input_dir = Some(PathBuf::from("/inputs"));
+        assert_eq!(config.input_path(2023, 5), PathBuf::from("/inputs/2023/day05.txt"));
+        assert_eq!(config.answers_path(2023), PathBuf::from("/repo/answers/2023.toml"));
+    }
+}
@@ -0,0 +1,178 @@
+//! The error type of the solver libraries, so they can be used without
+//! anyhow. It converts to `anyhow::Error` with `?` in the binaries.
+
+use std::fmt;
+use std::io;
+use std::num::{ParseIntError, TryFromIntError};
+
+pub type Result<T, E = AocError> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AocError {
+    /// Input that doesn't parse, at a 1-based line and column when known.
+    #[error("{}{message}", Location(*line, *column))]
+    Parse {
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+    /// A part of the input that isn't there, like "the 'time' line".
+    #[error("The input is missing {0}")]
+    MissingSection(String),
+    /// A value too big to compute with, on a 1-based line when known.
+    #[error("{}{message}", Location(*line, None))]
+    Overflow { line: Option<usize>, message: String },
+    /// Input with no answer, like a race that can't be won.
+    #[error("{0}")]
+    NoSolution(String),
+    /// Two ways of solving the same input that gave different answers.
+    #[error("{0}")]
+    Disagreement(String),
+    /// The solve was stopped by a `Cancel` token, as by a timeout.
+    #[error("Cancelled")]
+    Cancelled,
+    /// Reading the input failed, on a 1-based line when known.
+    #[error("Error reading {}", match line { Some(line) => format!("line {line}"), None => "the input".to_string() })]
+    Io {
+        line: Option<usize>,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// The start of a parse error's message, saying where it is.
+struct Location(Option<usize>, Option<usize>);
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.0, self.1) {
+            (Some(line), Some(column)) => write!(f, "Error on line {line}, column {column}: "),
+            (Some(line), None) => write!(f, "Error on line {line}: "),
+            (None, Some(column)) => write!(f, "Column {column}: "),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+impl AocError {
+    pub fn parse(message: impl Into<String>) -> AocError {
+        AocError::Parse { line: None, column: None, message: message.into() }
+    }
+
+    pub fn missing(section: impl Into<String>) -> AocError {
+        AocError::MissingSection(section.into())
+    }
+
+    pub fn overflow(message: impl Into<String>) -> AocError {
+        AocError::Overflow { line: None, message: message.into() }
+    }
+
+    pub fn no_solution(message: impl Into<String>) -> AocError {
+        AocError::NoSolution(message.into())
+    }
+
+    pub fn disagreement(message: impl Into<String>) -> AocError {
+        AocError::Disagreement(message.into())
+    }
+
+    /// Say which line a parse, overflow or read error is on, unless it
+    /// already says.
+    pub fn at_line(self, line_number: usize) -> AocError {
+        match self {
+            AocError::Parse { line: None, column, message } => {
+                AocError::Parse { line: Some(line_number), column, message }
+            }
+            AocError::Overflow { line: None, message } => AocError::Overflow { line: Some(line_number), message },
+            AocError::Io { line: None, source } => AocError::Io { line: Some(line_number), source },
+            error => error,
+        }
+    }
+
+    /// Say which column a parse error is at, unless it already says.
+    pub fn at_column(self, column_number: usize) -> AocError {
+        match self {
+            AocError::Parse { line, column: None, message } => {
+                AocError::Parse { line, column: Some(column_number), message }
+            }
+            error => error,
+        }
+    }
+
+    /// Put `context` in front of the error's message.
+    pub fn context(self, context: impl fmt::Display) -> AocError {
+        match self {
+            AocError::Parse { line, column, message } => {
+                AocError::Parse { line, column, message: format!("{context}: {message}") }
+            }
+            AocError::Overflow { line, message } => AocError::Overflow { line, message: format!("{context}: {message}") },
+            AocError::NoSolution(message) => AocError::NoSolution(format!("{context}: {message}")),
+            error => error,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(source: io::Error) -> AocError {
+        AocError::Io { line: None, source }
+    }
+}
+
+impl From<ParseIntError> for AocError {
+    fn from(error: ParseIntError) -> AocError {
+        AocError::parse(error.to_string())
+    }
+}
+
+impl From<TryFromIntError> for AocError {
+    fn from(error: TryFromIntError) -> AocError {
+        AocError::overflow(error.to_string())
+    }
+}
+
+/// Adds context to errors, like `anyhow::Context`.
+pub trait Context<T> {
+    fn context(self, context: impl fmt::Display) -> Result<T>;
+
+    fn with_context<C: fmt::Display>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<AocError>> Context<T> for Result<T, E> {
+    fn context(self, context: impl fmt::Display) -> Result<T> {
+        self.map_err(|error| error.into().context(context))
+    }
+
+    fn with_context<C: fmt::Display>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|error| error.into().context(context()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(AocError::parse("bad").to_string(), "bad");
+        assert_eq!(AocError::parse("bad").at_line(3).to_string(), "Error on line 3: bad");
+        assert_eq!(AocError::parse("bad").at_column(5).to_string(), "Column 5: bad");
+        assert_eq!(AocError::parse("bad").at_column(5).at_line(3).to_string(), "Error on line 3, column 5: bad");
+        assert_eq!(AocError::missing("the seeds").to_string(), "The input is missing the seeds");
+        assert_eq!(AocError::overflow("Too big").at_line(3).to_string(), "Error on line 3: Too big");
+        assert_eq!(AocError::no_solution("No seeds").at_line(3).to_string(), "No seeds");
+        let error = AocError::from(io::Error::other("broken")).at_line(2);
+        assert_eq!(error.to_string(), "Error reading line 2");
+        assert_eq!(format!("{:#}", anyhow::Error::from(error)), "Error reading line 2: broken");
+    }
+
+    #[test]
+    fn test_context() {
+        let error = "x".parse::<u32>().context("Error parsing seed 'x'").unwrap_err();
+        assert_eq!(error.to_string(), "Error parsing seed 'x': invalid digit found in string");
+        let error = Err::<(), _>(AocError::parse("bad").at_line(1)).with_context(|| "Card 1").unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 1: Card 1: bad");
+        let error = AocError::no_solution("No way to win").context("Race 2");
+        assert_eq!(error.to_string(), "Race 2: No way to win");
+        let error = AocError::missing("the seeds").context("Almanac");
+        assert_eq!(error.to_string(), "The input is missing the seeds");
+    }
+}
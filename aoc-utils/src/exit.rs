@@ -0,0 +1,104 @@
+//! Exit codes, so shell scripts and git hooks can tell a wrong answer from
+//! a crash, or from a missing input, without parsing the output.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::error::AocError;
+use crate::interrupt::{self, Interrupted};
+
+/// The answers were found, and match the expected ones where known.
+pub const SUCCESS: u8 = 0;
+/// A solver or anything else failed.
+pub const ERROR: u8 = 1;
+/// An answer doesn't match the expected one, or two ways of solving
+/// disagree.
+pub const MISMATCH: u8 = 2;
+/// There was no input to solve.
+pub const MISSING_INPUT: u8 = 3;
+
+/// The error for an answer other than the expected one.
+#[derive(Debug, Error)]
+#[error("Part {part} is {answer}, but {expected} was expected")]
+pub struct Mismatch {
+    pub part: u8,
+    pub answer: String,
+    pub expected: String,
+}
+
+/// The error for an input that hasn't been downloaded or doesn't exist.
+#[derive(Debug, Error)]
+pub enum MissingInput {
+    #[error("No input stored for {year} day {day}")]
+    Stored { year: u16, day: u8 },
+    #[error("'{}' doesn't exist", .0.display())]
+    File(PathBuf),
+}
+
+/// An error summarizing several, such as the days failed in a run, with
+/// the exit code for them.
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct Failed {
+    pub code: u8,
+    pub message: String,
+}
+
+impl Failed {
+    /// Summarize `errors` with `message`. The exit code is theirs if they
+    /// all have the same one, or [`ERROR`] if not.
+    pub fn new<'a>(message: String, errors: impl IntoIterator<Item = &'a anyhow::Error>) -> Failed {
+        let mut codes = errors.into_iter().map(code);
+        let first = codes.next().unwrap_or(ERROR);
+        let code = if codes.all(|code| code == first) { first } else { ERROR };
+        Failed { code, message }
+    }
+}
+
+/// The exit code for an error, from the first error in its chain that has
+/// one.
+pub fn code(error: &anyhow::Error) -> u8 {
+    error
+        .chain()
+        .find_map(|error| {
+            if error.is::<Interrupted>() {
+                Some(interrupt::EXIT_CODE)
+            } else if error.is::<Mismatch>() || matches!(error.downcast_ref(), Some(AocError::Disagreement(_))) {
+                Some(MISMATCH)
+            } else if error.is::<MissingInput>() {
+                Some(MISSING_INPUT)
+            } else {
+                error.downcast_ref::<Failed>().map(|failed| failed.code)
+            }
+        })
+        .unwrap_or(ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    fn test_code() {
+        assert_eq!(code(&anyhow!("parse error")), ERROR);
+        assert_eq!(code(&Interrupted.into()), interrupt::EXIT_CODE);
+        let mismatch = Mismatch { part: 1, answer: "12".to_string(), expected: "13".to_string() };
+        assert_eq!(mismatch.to_string(), "Part 1 is 12, but 13 was expected");
+        let error = Err::<(), _>(mismatch).context("Day 4").unwrap_err();
+        assert_eq!(code(&error), MISMATCH);
+        assert_eq!(code(&MissingInput::Stored { year: 2023, day: 4 }.into()), MISSING_INPUT);
+        assert_eq!(code(&AocError::disagreement("1 isn't 2").into()), MISMATCH);
+        assert_eq!(code(&AocError::no_solution("No seeds").into()), ERROR);
+    }
+
+    #[test]
+    fn test_failed() {
+        let missing = || anyhow::Error::from(MissingInput::File(PathBuf::from("input.txt")));
+        let failed = Failed::new("2 of 3 days failed".to_string(), &[missing(), missing()]);
+        assert_eq!(code(&failed.into()), MISSING_INPUT);
+        let failed = Failed::new("2 of 3 days failed".to_string(), &[missing(), anyhow!("parse error")]);
+        assert_eq!(code(&failed.into()), ERROR);
+    }
+}
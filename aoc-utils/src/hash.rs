@@ -0,0 +1,11 @@
+//! Hash maps and sets with FxHash rather than the standard SipHash. Puzzle
+//! inputs aren't crafted to collide, so SipHash's resistance to that is only
+//! overhead on the small keys the solutions hash.
+
+pub use rustc_hash::FxBuildHasher;
+
+/// A `HashMap` with FxHash. Make one with `HashMap::default()`.
+pub type HashMap<K, V> = std::collections::HashMap<K, V, FxBuildHasher>;
+
+/// A `HashSet` with FxHash. Make one with `HashSet::default()`.
+pub type HashSet<T> = std::collections::HashSet<T, FxBuildHasher>;
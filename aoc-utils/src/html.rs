@@ -0,0 +1,286 @@
+//! Just enough HTML handling for the pages served by adventofcode.com: pulling
+//! out the `<article>` elements and turning them into Markdown or plain text.
+
+use crate::http::BASE_URL;
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Text(&'a str),
+    Open { name: &'a str, attrs: &'a str },
+    Close(&'a str),
+}
+
+/// Split HTML into text and tags. Comments and doctypes are dropped.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim()));
+        } else if !tag.starts_with('!') {
+            let tag = tag.trim_end_matches('/');
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Open { name, attrs });
+        }
+    }
+    tokens
+}
+
+/// Find the value of an attribute in the attribute text of a tag.
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let start = attrs.find(&format!("{name}=\""))? + name.len() + 2;
+    let end = attrs[start..].find('"')? + start;
+    Some(&attrs[start..end])
+}
+
+/// Replace the character entities used on the site.
+pub fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// The inner HTML of every `<article>` in the page.
+pub fn articles(html: &str) -> Vec<&str> {
+    let mut articles = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        let Some(open_end) = rest[start..].find('>') else {
+            break;
+        };
+        let inner_start = start + open_end + 1;
+        let Some(inner_len) = rest[inner_start..].find("</article>") else {
+            break;
+        };
+        articles.push(&rest[inner_start..inner_start + inner_len]);
+        rest = &rest[inner_start + inner_len..];
+    }
+    articles
+}
+
+/// Strip all tags, decode entities, and collapse whitespace.
+pub fn to_text(html: &str) -> String {
+    let mut text = String::new();
+    for token in tokenize(html) {
+        if let Token::Text(t) = token {
+            text.push_str(t);
+        }
+    }
+    decode_entities(&text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The text of every `<pre>` block, which is where the puzzles show their
+/// example inputs.
+pub fn pre_blocks(html: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<String> = None;
+    for token in tokenize(html) {
+        match token {
+            Token::Open { name: "pre", .. } => current = Some(String::new()),
+            Token::Close("pre") => blocks.extend(current.take()),
+            Token::Text(text) => {
+                if let Some(block) = current.as_mut() {
+                    block.push_str(&decode_entities(text));
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+/// The text of every emphasized `<code>` element. The puzzles highlight the
+/// answers to their examples this way.
+pub fn emphasized_code(html: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut in_code = false;
+    let mut in_em = false;
+    let mut current = String::new();
+    for token in tokenize(html) {
+        match token {
+            Token::Open { name: "code", .. } => in_code = true,
+            Token::Open { name: "em", .. } => in_em = true,
+            Token::Close(name @ ("code" | "em")) => {
+                if in_code && in_em && !current.is_empty() {
+                    found.push(decode_entities(&current));
+                }
+                current.clear();
+                if name == "code" {
+                    in_code = false;
+                } else {
+                    in_em = false;
+                }
+            }
+            Token::Text(text) if in_code && in_em => current.push_str(text),
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Convert the HTML of a puzzle description to Markdown.
+pub fn to_markdown(html: &str) -> String {
+    let mut markdown = String::new();
+    let mut in_pre = false;
+    let mut in_code = false;
+    let mut links = Vec::new();
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) if in_pre => markdown.push_str(&decode_entities(text)),
+            Token::Text(text) => push_collapsed(&mut markdown, &decode_entities(text)),
+            Token::Open { name, attrs } => match name {
+                "h2" => markdown.push_str("## "),
+                "p" => {}
+                "pre" => {
+                    markdown.push_str("```\n");
+                    in_pre = true;
+                }
+                "code" if !in_pre => {
+                    markdown.push('`');
+                    in_code = true;
+                }
+                "em" if !in_pre && !in_code => markdown.push('*'),
+                "li" => markdown.push_str("- "),
+                "a" => {
+                    let href = attr(attrs, "href").unwrap_or_default();
+                    links.push(if href.starts_with('/') {
+                        format!("{BASE_URL}{href}")
+                    } else {
+                        href.to_string()
+                    });
+                    markdown.push('[');
+                }
+                _ => {}
+            },
+            Token::Close(name) => match name {
+                "h2" | "p" | "ul" => {
+                    markdown.truncate(markdown.trim_end().len());
+                    markdown.push_str("\n\n");
+                }
+                "pre" => {
+                    if !markdown.ends_with('\n') {
+                        markdown.push('\n');
+                    }
+                    markdown.push_str("```\n\n");
+                    in_pre = false;
+                }
+                "code" if !in_pre => {
+                    markdown.push('`');
+                    in_code = false;
+                }
+                "em" if !in_pre && !in_code => markdown.push('*'),
+                "li" => {
+                    trim_end_spaces(&mut markdown);
+                    markdown.push('\n');
+                }
+                "a" => {
+                    let href = links.pop().unwrap_or_default();
+                    markdown.push_str(&format!("]({href})"));
+                }
+                _ => {}
+            },
+        }
+    }
+    markdown.trim_end().to_string() + "\n"
+}
+
+/// Append text with runs of whitespace collapsed into single spaces, and no
+/// whitespace at the start of a line.
+fn push_collapsed(markdown: &mut String, text: &str) {
+    for c in text.chars() {
+        if !c.is_whitespace() {
+            markdown.push(c);
+        } else if !markdown.is_empty() && !markdown.ends_with([' ', '\n']) {
+            markdown.push(' ');
+        }
+    }
+}
+
+fn trim_end_spaces(text: &mut String) {
+    text.truncate(text.trim_end_matches(' ').len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("a<!-- x --><p class=\"c\">b</p><br/>"),
+            vec![
+                Token::Text("a"),
+                Token::Open { name: "p", attrs: "class=\"c\"" },
+                Token::Text("b"),
+                Token::Close("p"),
+                Token::Open { name: "br", attrs: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_articles() {
+        let html = "<main><article class=\"day-desc\"><p>one</p></article><p>x</p><article><p>two</p></article></main>";
+        assert_eq!(articles(html), vec!["<p>one</p>", "<p>two</p>"]);
+        assert!(articles("<p>none</p>").is_empty());
+    }
+
+    #[test]
+    fn test_to_text() {
+        assert_eq!(to_text("<p>That's the <em>right</em>\n answer &amp; more!</p>"), "That's the right answer & more!");
+    }
+
+    #[test]
+    fn test_pre_blocks() {
+        let html = "<p>For example:</p><pre><code>1 &lt; 2\n3\n</code></pre><p>x</p><pre><code><em>4</em>\n</code></pre>";
+        assert_eq!(pre_blocks(html), vec!["1 < 2\n3\n", "4\n"]);
+        assert!(pre_blocks("<p>none</p>").is_empty());
+    }
+
+    #[test]
+    fn test_emphasized_code() {
+        let html = "<p>a <code>1</code> b <code><em>35</em></code> c <em><code>46</code></em> d <em>no</em></p>";
+        assert_eq!(emphasized_code(html), vec!["35", "46"]);
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let html = "<h2>--- Day 6: Wait For It ---</h2><p>The <em>race</em> lasts \
+            <code>7</code> ms, see <a href=\"/2023/day/5\">day 5</a>.</p>\
+            <pre><code>Time:      7\nDistance:  9\n</code></pre>\
+            <ul>\n<li>Hold for <code><em>1</em></code> ms.</li>\n<li>Don't.</li>\n</ul>\
+            <p>Answer: <code>a &lt; b</code></p>";
+        assert_eq!(
+            to_markdown(html),
+            "## --- Day 6: Wait For It ---\n\n\
+            The *race* lasts `7` ms, see [day 5](https://adventofcode.com/2023/day/5).\n\n\
+            ```\nTime:      7\nDistance:  9\n```\n\n\
+            - Hold for `1` ms.\n- Don't.\n\n\
+            Answer: `a < b`\n"
+        );
+    }
+}
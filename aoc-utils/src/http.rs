@@ -0,0 +1,277 @@
+//! HTTP client for adventofcode.com.
+//!
+//! Every network feature goes through [`Client`] so the site is treated
+//! politely: requests identify this repository in the User-Agent, are spaced
+//! at least [`MIN_REQUEST_INTERVAL`] apart (also across separate invocations),
+//! GET responses are cached on disk, and puzzles that haven't unlocked yet are
+//! never requested.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+pub const USER_AGENT: &str = concat!(
+    "github.com/joel0/advent-of-code aoc-utils/",
+    env!("CARGO_PKG_VERSION")
+);
+
+/// The minimum time between two requests to the site.
+pub const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+
+/// File in the cache directory recording when the last request was sent.
+const LAST_REQUEST_FILE: &str = "last-request";
+
+pub struct Client {
+    agent: ureq::Agent,
+    session: String,
+    cache_dir: PathBuf,
+}
+
+impl Client {
+    /// Create a client authenticated with the given session cookie value,
+    /// caching responses in `cache_dir`.
+    pub fn new(session: &str, cache_dir: &Path) -> Result<Client> {
+        let session = session.trim();
+        if session.is_empty() {
+            return Err(anyhow!("The session token is empty"));
+        }
+        fs::create_dir_all(cache_dir)
+            .with_context(|| format!("Error creating cache directory '{}'", cache_dir.display()))?;
+
+        let agent = ureq::Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+
+        Ok(Client {
+            agent,
+            session: session.to_string(),
+            cache_dir: cache_dir.to_path_buf(),
+        })
+    }
+
+    /// GET a page such as "/2023/day/5/input", using the cached copy if there
+    /// is one.
+    pub fn get(&self, path: &str) -> Result<String> {
+        if let Some(body) = self.read_cache(path)? {
+            return Ok(body);
+        }
+        self.get_fresh(path)
+    }
+
+    /// GET a page from the site even if it is cached, and update the cache.
+    pub fn get_fresh(&self, path: &str) -> Result<String> {
+        let body = self.request(path)?;
+        self.write_cache(path, &body)?;
+        Ok(body)
+    }
+
+    /// GET a page from the site without caching it, for a page that mustn't
+    /// be left on disk in the clear, removing any copy already cached.
+    pub fn get_uncached(&self, path: &str) -> Result<String> {
+        let body = self.request(path)?;
+        self.remove_cache(path)?;
+        Ok(body)
+    }
+
+    /// GET a page from the site.
+    fn request(&self, path: &str) -> Result<String> {
+        check_unlocked(path, SystemTime::now())?;
+        self.throttle()?;
+
+        let url = format!("{BASE_URL}{path}");
+        self.agent
+            .get(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .with_context(|| format!("Error requesting '{url}'"))
+    }
+
+    /// POST a form to the site. Responses are never cached.
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        check_unlocked(path, SystemTime::now())?;
+        self.throttle()?;
+
+        let url = format!("{BASE_URL}{path}");
+        self.agent
+            .post(&url)
+            .header("Cookie", &format!("session={}", self.session))
+            .send_form(form.iter().copied())
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .with_context(|| format!("Error posting to '{url}'"))
+    }
+
+    /// Sleep until at least `MIN_REQUEST_INTERVAL` has passed since the last
+    /// request, then record the time of this one.
+    fn throttle(&self) -> Result<()> {
+        let stamp_path = self.cache_dir.join(LAST_REQUEST_FILE);
+        if let Ok(stamp) = fs::read_to_string(&stamp_path) {
+            if let Ok(millis) = stamp.trim().parse::<u64>() {
+                let last = UNIX_EPOCH + Duration::from_millis(millis);
+                if let Some(wait) = remaining_wait(last, SystemTime::now()) {
+                    thread::sleep(wait);
+                }
+            }
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        fs::write(&stamp_path, now.to_string())
+            .with_context(|| format!("Error writing '{}'", stamp_path.display()))
+    }
+
+    fn cache_path(&self, path: &str) -> PathBuf {
+        self.cache_dir.join("http").join(cache_file_name(path))
+    }
+
+    fn read_cache(&self, path: &str) -> Result<Option<String>> {
+        let cache_path = self.cache_path(path);
+        if !cache_path.exists() {
+            return Ok(None);
+        }
+        let body = fs::read_to_string(&cache_path)
+            .with_context(|| format!("Error reading cached '{}'", cache_path.display()))?;
+        Ok(Some(body))
+    }
+
+    fn write_cache(&self, path: &str, body: &str) -> Result<()> {
+        let cache_path = self.cache_path(path);
+        fs::create_dir_all(cache_path.parent().unwrap())?;
+        fs::write(&cache_path, body)
+            .with_context(|| format!("Error writing cache '{}'", cache_path.display()))
+    }
+
+    fn remove_cache(&self, path: &str) -> Result<()> {
+        let cache_path = self.cache_path(path);
+        match fs::remove_file(&cache_path) {
+            Err(error) if error.kind() != ErrorKind::NotFound => {
+                Err(error).with_context(|| format!("Error removing cache '{}'", cache_path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The default directory for cached responses and other local state.
+pub fn default_cache_dir() -> Result<PathBuf> {
+    let dir = dirs::cache_dir().ok_or_else(|| anyhow!("Unable to find a cache directory"))?;
+    Ok(dir.join("aoc"))
+}
+
+/// How much longer to wait before another request may be sent.
+fn remaining_wait(last: SystemTime, now: SystemTime) -> Option<Duration> {
+    let elapsed = now.duration_since(last).unwrap_or_default();
+    MIN_REQUEST_INTERVAL.checked_sub(elapsed).filter(|wait| !wait.is_zero())
+}
+
+/// Turn a site path into a flat file name, e.g. "/2023/day/5" => "2023_day_5".
+fn cache_file_name(path: &str) -> String {
+    path.trim_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Refuse to request puzzle pages for a day that hasn't been released yet.
+fn check_unlocked(path: &str, now: SystemTime) -> Result<()> {
+    let Some((year, day)) = parse_puzzle_path(path) else {
+        return Ok(());
+    };
+    let now = now.duration_since(UNIX_EPOCH)?.as_secs();
+    if now < unlock_time(year, day) {
+        return Err(anyhow!("{year} day {day} has not unlocked yet"));
+    }
+    Ok(())
+}
+
+/// Parse the year and day out of a path like "/2023/day/5/input".
+fn parse_puzzle_path(path: &str) -> Option<(i64, u32)> {
+    let mut parts = path.trim_start_matches('/').split('/');
+    let year = parts.next()?.parse().ok()?;
+    if parts.next()? != "day" {
+        return None;
+    }
+    let day = parts.next()?.parse().ok()?;
+    Some((year, day))
+}
+
+/// Puzzles unlock at midnight EST (UTC-5), returned as a unix timestamp.
+fn unlock_time(year: i64, day: u32) -> u64 {
+    let days = days_from_civil(year, 12, day);
+    (days * 86400 + 5 * 3600) as u64
+}
+
+/// Days since 1970-01-01 for a date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(2023, 12, 1), 19692);
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        // 2023-12-01T05:00:00Z
+        assert_eq!(unlock_time(2023, 1), 1701406800);
+        assert_eq!(unlock_time(2023, 2), 1701406800 + 86400);
+    }
+
+    #[test]
+    fn test_check_unlocked() {
+        let unlock = UNIX_EPOCH + Duration::from_secs(1701406800);
+        assert!(check_unlocked("/2023/day/1", unlock).is_ok());
+        assert!(check_unlocked("/2023/day/1/input", unlock - Duration::from_secs(1)).is_err());
+        assert!(check_unlocked("/2023/day/2/answer", unlock).is_err());
+        assert!(check_unlocked("/2023/leaderboard", unlock).is_ok());
+    }
+
+    #[test]
+    fn test_cache() {
+        let dir = std::env::temp_dir().join(format!("aoc-http-cache-test-{}", std::process::id()));
+        let client = Client::new("session", &dir).unwrap();
+        assert_eq!(client.read_cache("/2023/day/5/input").unwrap(), None);
+        client.write_cache("/2023/day/5/input", "seeds: 79 14 55 13\n").unwrap();
+        assert_eq!(client.read_cache("/2023/day/5/input").unwrap().as_deref(), Some("seeds: 79 14 55 13\n"));
+        client.remove_cache("/2023/day/5/input").unwrap();
+        client.remove_cache("/2023/day/5/input").unwrap();
+        assert_eq!(client.read_cache("/2023/day/5/input").unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_file_name() {
+        assert_eq!(cache_file_name("/2023/day/5"), "2023_day_5");
+        assert_eq!(cache_file_name("/2023/day/5/input"), "2023_day_5_input");
+        assert_eq!(cache_file_name("/../etc"), "___etc");
+    }
+
+    #[test]
+    fn test_remaining_wait() {
+        let last = UNIX_EPOCH + Duration::from_secs(100);
+        assert_eq!(remaining_wait(last, last), Some(MIN_REQUEST_INTERVAL));
+        assert_eq!(remaining_wait(last, last + Duration::from_secs(2)), Some(MIN_REQUEST_INTERVAL - Duration::from_secs(2)));
+        assert_eq!(remaining_wait(last, last + MIN_REQUEST_INTERVAL), None);
+        assert_eq!(remaining_wait(last, last - Duration::from_secs(1)), Some(MIN_REQUEST_INTERVAL));
+    }
+}
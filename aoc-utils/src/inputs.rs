@@ -0,0 +1,148 @@
+//! Puzzle inputs: downloading them and keeping them on disk.
+//!
+//! The site asks that inputs aren't redistributed, so they can optionally be
+//! stored encrypted with a key derived from the session token. Encrypted
+//! inputs are saved next to where the plain file would be, with an `.enc`
+//! extension, and are decrypted transparently by [`read`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chacha20poly1305::aead::{Aead, Generate, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+use crate::http::Client;
+
+/// Marks the start of an encrypted input file, followed by the nonce.
+const MAGIC: &[u8] = b"aoc-enc1";
+const NONCE_LEN: usize = 24;
+
+/// The path of the encrypted version of an input file.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".enc");
+    path.with_file_name(name)
+}
+
+/// Read the stored input for a puzzle, decrypting it if needed. Returns
+/// `None` if the input hasn't been downloaded. If it's stored both in the
+/// clear and encrypted, the plain file is read, with a warning.
+pub fn read(config: &Config, year: u16, day: u8) -> Result<Option<String>> {
+    let path = config.input_path(year, day);
+    if path.exists() {
+        if encrypted_path(&path).exists() {
+            eprintln!(
+                "Warning: reading '{}' rather than '{}'; remove the one that's out of date",
+                path.display(),
+                encrypted_path(&path).display()
+            );
+        }
+        let input = fs::read_to_string(&path)
+            .with_context(|| format!("Error reading '{}'", path.display()))?;
+        return Ok(Some(input));
+    }
+
+    let path = encrypted_path(&path);
+    if path.exists() {
+        let data = fs::read(&path).with_context(|| format!("Error reading '{}'", path.display()))?;
+        let input = decrypt(&config.session()?, &data)
+            .with_context(|| format!("Error decrypting '{}'", path.display()))?;
+        return Ok(Some(input));
+    }
+
+    Ok(None)
+}
+
+/// Download the input for a puzzle and store it, encrypted if configured.
+/// An input that's encrypted isn't kept in the HTTP cache either.
+pub fn fetch(client: &Client, config: &Config, year: u16, day: u8) -> Result<String> {
+    let path = format!("/{year}/day/{day}/input");
+    let input = if config.encrypt_inputs() { client.get_uncached(&path)? } else { client.get_fresh(&path)? };
+
+    let path = config.input_path(year, day);
+    fs::create_dir_all(path.parent().unwrap())?;
+    if config.encrypt_inputs() {
+        let path = encrypted_path(&path);
+        fs::write(&path, encrypt(&config.session()?, &input)?)
+            .with_context(|| format!("Error writing '{}'", path.display()))?;
+    } else {
+        fs::write(&path, &input).with_context(|| format!("Error writing '{}'", path.display()))?;
+    }
+    Ok(input)
+}
+
+fn cipher(session: &str) -> XChaCha20Poly1305 {
+    let mut hasher = Sha256::new();
+    hasher.update(b"aoc-utils input encryption\0");
+    hasher.update(session.trim().as_bytes());
+    XChaCha20Poly1305::new(&hasher.finalize())
+}
+
+/// Encrypt an input with a key derived from the session token.
+pub fn encrypt(session: &str, input: &str) -> Result<Vec<u8>> {
+    let nonce = XNonce::generate();
+    let ciphertext = cipher(session)
+        .encrypt(&nonce, input.as_bytes())
+        .map_err(|_| anyhow!("Error encrypting the input"))?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypt an input encrypted by [`encrypt`] with the same session token.
+pub fn decrypt(session: &str, data: &[u8]) -> Result<String> {
+    let data = data
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| anyhow!("Not an encrypted input"))?;
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!("The encrypted input is truncated"));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let nonce = XNonce::try_from(nonce).unwrap();
+    let plaintext = cipher(session)
+        .decrypt(&nonce, ciphertext)
+        .map_err(|_| anyhow!("Unable to decrypt the input; was it downloaded with a different session?"))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_path() {
+        assert_eq!(
+            encrypted_path(Path::new("2023/day05/input.txt")),
+            PathBuf::from("2023/day05/input.txt.enc")
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let input = "seeds: 79 14 55 13\n";
+        let data = encrypt("session", input).unwrap();
+        assert!(data.starts_with(MAGIC));
+        assert!(!data.windows(5).any(|w| w == b"seeds"));
+        assert_eq!(decrypt("session", &data).unwrap(), input);
+        assert_eq!(decrypt(" session\n", &data).unwrap(), input);
+        assert_ne!(encrypt("session", input).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decrypt_errors() {
+        let data = encrypt("session", "input").unwrap();
+        assert!(decrypt("other session", &data).is_err());
+        assert!(decrypt("session", b"plain text").is_err());
+        assert!(decrypt("session", &data[..MAGIC.len() + 4]).is_err());
+
+        let mut tampered = data.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt("session", &tampered).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! A symbol table: each distinct string is stored once and named by a small
+//! copyable `Symbol`, for days that key maps by names from the input.
+
+use std::sync::Arc;
+
+use crate::hash::HashMap;
+
+/// An interned string, indexing its [`Interner`] in the order strings were
+/// first interned.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The symbol's index, from 0, for indexing a `Vec` kept alongside the
+    /// interner.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// The strings interned so far and their symbols. Each string is one
+/// allocation, shared by the list and the map.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    names: Vec<Arc<str>>,
+    symbols: HashMap<Arc<str>, Symbol>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// The symbol for `name`, copying it in only the first time it's seen.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.names.len()).expect("Too many strings to intern"));
+        let name: Arc<str> = name.into();
+        self.names.push(Arc::clone(&name));
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+
+    /// The symbol for `name`, if it has been interned.
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    /// The string a symbol from this interner stands for.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.index()]
+    }
+
+    /// The number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+        let seed = interner.intern("seed");
+        let soil = interner.intern("soil");
+        assert_eq!(interner.intern("seed"), seed);
+        assert_ne!(seed, soil);
+        assert_eq!((seed.index(), soil.index()), (0, 1));
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("soil"), Some(soil));
+        assert_eq!(interner.get("water"), None);
+        assert_eq!(interner.resolve(soil), "soil");
+    }
+}
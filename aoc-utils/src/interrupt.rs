@@ -0,0 +1,34 @@
+//! Handling Ctrl-C, so a long search can stop and report how far it got
+//! instead of dying silently.
+
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use thiserror::Error;
+
+/// The exit code after Ctrl-C, as a shell gives for SIGINT.
+pub const EXIT_CODE: u8 = 130;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// The error for a run stopped by Ctrl-C, once it has reported how far it
+/// got.
+#[derive(Debug, Error)]
+#[error("Interrupted")]
+pub struct Interrupted;
+
+/// Handle Ctrl-C. The first sets the flag `interrupted` returns, for
+/// searches to check, and calls `on_interrupt`. A second exits at once.
+pub fn install(on_interrupt: impl Fn() + Send + 'static) -> Result<(), ctrlc::Error> {
+    ctrlc::set_handler(move || {
+        if INTERRUPTED.swap(true, Ordering::SeqCst) {
+            process::exit(EXIT_CODE.into());
+        }
+        on_interrupt();
+    })
+}
+
+/// Whether Ctrl-C has been pressed.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,205 @@
+//! Skipping malformed lines instead of failing, for inputs with a stray
+//! blank line or a typo in a header.
+
+use std::fmt;
+
+use anyhow::anyhow;
+
+use crate::error::{AocError, Result};
+
+/// A line skipped in lenient mode, and why.
+#[derive(Debug)]
+pub struct SkippedLine {
+    pub line_number: usize,
+    pub error: AocError,
+}
+
+impl SkippedLine {
+    /// Write "{line} 3: message", or "{line} 3, column 5: message".
+    fn write(&self, f: &mut fmt::Formatter, line: &str) -> fmt::Result {
+        match &self.error {
+            AocError::Parse { column: Some(column), message, .. } => {
+                write!(f, "{line} {}, column {column}: {message}", self.line_number)
+            }
+            error => write!(f, "{line} {}: {error}", self.line_number),
+        }
+    }
+}
+
+/// Decides what happens to a line that doesn't parse. Strict, the default,
+/// fails with the line number; lenient records the error and skips the line.
+#[derive(Debug, Default)]
+pub struct LinePolicy {
+    lenient: bool,
+    line_number: usize,
+    skipped: Vec<SkippedLine>,
+}
+
+impl LinePolicy {
+    pub fn strict() -> LinePolicy {
+        LinePolicy::default()
+    }
+
+    pub fn lenient() -> LinePolicy {
+        LinePolicy { lenient: true, ..LinePolicy::default() }
+    }
+
+    pub fn new(lenient: bool) -> LinePolicy {
+        LinePolicy { lenient, ..LinePolicy::default() }
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Count a line read from the input, returning its number.
+    pub fn next_line(&mut self) -> usize {
+        self.line_number += 1;
+        self.line_number
+    }
+
+    /// The number of the last line read.
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// Handle the result of parsing the last line read. An error is returned
+    /// naming the line when strict, and recorded, returning `None`, when
+    /// lenient.
+    pub fn check<T>(&mut self, result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if self.lenient => {
+                self.skipped.push(SkippedLine { line_number: self.line_number, error });
+                Ok(None)
+            }
+            Err(error) => Err(error.at_line(self.line_number)),
+        }
+    }
+
+    /// Like `check`, for a line that is wrong however it is parsed.
+    pub fn reject(&mut self, message: impl Into<String>) -> Result<()> {
+        self.check::<()>(Err(AocError::parse(message))).map(|_| ())
+    }
+
+    pub fn skipped(&self) -> &[SkippedLine] {
+        &self.skipped
+    }
+
+    /// Print a warning to stderr for each skipped line, then how many there
+    /// were.
+    pub fn report(&self) {
+        for skipped in &self.skipped {
+            eprintln!("Warning: skipped {}", fmt::from_fn(|f| skipped.write(f, "line")));
+        }
+        if !self.skipped.is_empty() {
+            eprintln!("Skipped {} malformed line(s)", self.skipped.len());
+        }
+    }
+}
+
+/// Every error found in an input by reading it leniently: the lines that
+/// don't parse, and the error that stopped it being read, if any.
+#[derive(Debug)]
+pub struct Validation {
+    pub lines: Vec<SkippedLine>,
+    pub error: Option<AocError>,
+}
+
+/// Read an input with `read`, which is given a lenient policy, collecting
+/// every error rather than stopping at the first.
+pub fn validate(read: impl FnOnce(&mut LinePolicy) -> Result<()>) -> Validation {
+    let mut policy = LinePolicy::lenient();
+    let error = read(&mut policy).err();
+    Validation { lines: policy.skipped, error }
+}
+
+impl Validation {
+    pub fn is_valid(&self) -> bool {
+        self.lines.is_empty() && self.error.is_none()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.lines.len() + self.error.is_some() as usize
+    }
+
+    /// Print the errors and summary, failing if there were any errors.
+    pub fn print(&self) -> anyhow::Result<()> {
+        println!("{self}");
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err(anyhow!("The input is invalid"))
+        }
+    }
+}
+
+/// One error per line, then a summary.
+impl fmt::Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for line in &self.lines {
+            line.write(f, "Line")?;
+            writeln!(f)?;
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "{error}")?;
+        }
+        match self.error_count() {
+            0 => write!(f, "The input is valid"),
+            count => write!(f, "Found {count} error(s)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict() {
+        let mut policy = LinePolicy::strict();
+        assert_eq!(policy.next_line(), 1);
+        assert_eq!(policy.check(Ok(1)).unwrap(), Some(1));
+        policy.next_line();
+        let error = policy.check::<u32>(Err(AocError::parse("bad"))).unwrap_err();
+        assert_eq!(format!("{error:#}"), "Error on line 2: bad");
+        assert!(policy.skipped().is_empty());
+    }
+
+    #[test]
+    fn test_lenient() {
+        let mut policy = LinePolicy::lenient();
+        policy.next_line();
+        assert_eq!(policy.check::<u32>(Err(AocError::parse("bad"))).unwrap(), None);
+        policy.next_line();
+        assert_eq!(policy.check(Ok(2)).unwrap(), Some(2));
+        policy.next_line();
+        policy.reject("blank").unwrap();
+        let skipped: Vec<_> = policy.skipped().iter().map(|s| (s.line_number, s.error.to_string())).collect();
+        assert_eq!(skipped, [(1, "bad".to_string()), (3, "blank".to_string())]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let validation = validate(|_| Ok(()));
+        assert!(validation.is_valid());
+        assert_eq!(validation.to_string(), "The input is valid");
+
+        let validation = validate(|policy| {
+            for line in ["1", "x", "3", "y"] {
+                policy.next_line();
+                policy.check(line.parse::<u32>().map_err(AocError::from))?;
+            }
+            Err(AocError::missing("the last line"))
+        });
+        assert!(!validation.is_valid());
+        assert_eq!(validation.error_count(), 3);
+        assert_eq!(
+            validation.to_string(),
+            "Line 2: invalid digit found in string\n\
+            Line 4: invalid digit found in string\n\
+            The input is missing the last line\n\
+            Found 3 error(s)"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+//! Code shared between the Advent of Code solutions and tooling.
+
+#[cfg(feature = "fast-alloc")]
+pub mod alloc;
+#[cfg(feature = "native")]
+pub mod answers;
+pub mod arena;
+#[cfg(feature = "native")]
+pub mod bench;
+#[cfg(feature = "bench")]
+pub mod benches;
+#[cfg(feature = "native")]
+pub mod cache;
+pub mod cancel;
+pub mod check;
+#[cfg(feature = "native")]
+pub mod checkpoint;
+#[cfg(feature = "native")]
+pub mod compress;
+#[cfg(feature = "native")]
+pub mod config;
+pub mod error;
+#[cfg(feature = "native")]
+pub mod exit;
+pub mod hash;
+#[cfg(feature = "native")]
+pub mod html;
+#[cfg(feature = "native")]
+pub mod http;
+#[cfg(feature = "native")]
+pub mod inputs;
+pub mod intern;
+#[cfg(feature = "native")]
+pub mod interrupt;
+pub mod lenient;
+pub mod lines;
+pub mod minimize;
+#[cfg(feature = "native")]
+pub mod output;
+#[cfg(feature = "native")]
+pub mod progress;
+#[cfg(feature = "native")]
+pub mod puzzle;
+pub mod random;
+pub mod small;
+pub mod solver;
+#[cfg(feature = "native")]
+pub mod source;
+pub mod stats;
+#[cfg(feature = "native")]
+pub mod submit;
+#[cfg(feature = "native")]
+pub mod usage;
@@ -0,0 +1,244 @@
+//! Reading a solution's input line by line.
+
+use std::borrow::Cow;
+use std::io::BufRead;
+use std::str;
+
+use crate::error::{AocError, Result};
+
+/// The byte order mark some editors put at the start of UTF-8 files.
+const BOM: char = '\u{feff}';
+
+/// An iterator over the lines of a reader, like [`BufRead::lines`], whose
+/// errors say which line they happened on. Lines are normalized as by
+/// [`normalize_line`].
+///
+/// Bytes that aren't UTF-8 are replaced with U+FFFD, so a slightly corrupted
+/// input still parses as far as it can, unless [`Lines::strict_utf8`] is set.
+pub struct Lines<R> {
+    reader: R,
+    bytes: Vec<u8>,
+    line: String,
+    line_number: usize,
+    /// The byte offset of the next line in the input.
+    offset: usize,
+    strict_utf8: bool,
+}
+
+/// Read the lines of `reader`. An I/O error becomes an error naming the
+/// line.
+pub fn lines<R: BufRead>(reader: R) -> Lines<R> {
+    Lines { reader, bytes: Vec::new(), line: String::new(), line_number: 0, offset: 0, strict_utf8: false }
+}
+
+impl<R: BufRead> Lines<R> {
+    /// With `strict`, a line that isn't UTF-8 is an error giving the byte
+    /// offset of the first bad byte, rather than having it replaced.
+    pub fn strict_utf8(mut self, strict: bool) -> Lines<R> {
+        self.strict_utf8 = strict;
+        self
+    }
+
+    /// Read the next line into a buffer reused for every line, returning
+    /// `None` at the end of the input.
+    pub fn read_line(&mut self) -> Option<Result<&str>> {
+        self.bytes.clear();
+        let length = match self.reader.read_until(b'\n', &mut self.bytes) {
+            Ok(0) => return None,
+            Ok(length) => length,
+            Err(e) => return Some(Err(AocError::from(e).at_line(self.line_number + 1))),
+        };
+        self.line_number += 1;
+        let offset = self.offset;
+        self.offset += length;
+
+        self.line.clear();
+        match str::from_utf8(&self.bytes) {
+            Ok(line) => self.line.push_str(line),
+            Err(error) if self.strict_utf8 => {
+                let error = invalid_utf8(offset + error.valid_up_to()).at_column(error.valid_up_to() + 1);
+                return Some(Err(error.at_line(self.line_number)));
+            }
+            Err(_) => self.line.push_str(&String::from_utf8_lossy(&self.bytes)),
+        }
+        Some(Ok(normalize_line(&self.line, self.line_number == 1)))
+    }
+}
+
+impl<R: BufRead> ReadLine for Lines<R> {
+    fn read_line(&mut self) -> Option<Result<&str>> {
+        Lines::read_line(self)
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        self.read_line().map(|line| line.map(str::to_string))
+    }
+}
+
+/// Reading lines one at a time, each borrowed until the next is read, from
+/// a reader by [`Lines`] or from an input already in memory by [`StrLines`].
+pub trait ReadLine {
+    /// The next line, or `None` at the end of the input.
+    fn read_line(&mut self) -> Option<Result<&str>>;
+}
+
+/// An iterator over the lines of a whole input already in memory, such as a
+/// mapped file, borrowing each line rather than copying it. Lines are
+/// normalized as by [`normalize_line`]. It never fails, but gives `Result`s
+/// to stand in for [`Lines`].
+pub struct StrLines<'a> {
+    lines: str::Lines<'a>,
+    first: bool,
+}
+
+/// Iterate over the lines of `input`.
+pub fn str_lines(input: &str) -> StrLines<'_> {
+    StrLines { lines: input.lines(), first: true }
+}
+
+impl<'a> Iterator for StrLines<'a> {
+    type Item = Result<&'a str>;
+
+    fn next(&mut self) -> Option<Result<&'a str>> {
+        let line = self.lines.next()?;
+        let first = std::mem::replace(&mut self.first, false);
+        Some(Ok(normalize_line(line, first)))
+    }
+}
+
+impl ReadLine for StrLines<'_> {
+    fn read_line(&mut self) -> Option<Result<&str>> {
+        self.next()
+    }
+}
+
+/// Decode a whole input. Bytes that aren't UTF-8 are replaced with U+FFFD,
+/// or with `strict`, are an error giving their line, column and byte offset.
+pub fn decode(input: &[u8], strict: bool) -> Result<Cow<'_, str>> {
+    if strict {
+        check_utf8(input)?;
+    }
+    Ok(String::from_utf8_lossy(input))
+}
+
+/// Check that an input is UTF-8, failing with the line, column and byte
+/// offset of the first byte that isn't.
+pub fn check_utf8(input: &[u8]) -> Result<()> {
+    let Err(error) = str::from_utf8(input) else {
+        return Ok(());
+    };
+    let offset = error.valid_up_to();
+    let line_start = input[..offset].iter().rposition(|&byte| byte == b'\n').map_or(0, |newline| newline + 1);
+    let line_number = input[..line_start].iter().filter(|&&byte| byte == b'\n').count() + 1;
+    Err(invalid_utf8(offset).at_line(line_number).at_column(offset - line_start + 1))
+}
+
+fn invalid_utf8(offset: usize) -> AocError {
+    AocError::parse(format!("Invalid UTF-8 at byte offset {offset}"))
+}
+
+/// Remove what editors and browsers add to a line that isn't part of the
+/// puzzle: trailing whitespace, including the "\r" of a CRLF line ending,
+/// and, on the `first` line, a byte order mark.
+pub fn normalize_line(line: &str, first: bool) -> &str {
+    let line = if first { strip_bom(line) } else { line };
+    line.trim_end()
+}
+
+/// Remove the byte order mark from the start of an input, if it has one.
+pub fn strip_bom(input: &str) -> &str {
+    input.strip_prefix(BOM).unwrap_or(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let read: Vec<String> = lines("one\ntwo\r\n\nthree".as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(read, ["one", "two", "", "three"]);
+        assert_eq!(lines("".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_lines_normalized() {
+        let read: Vec<String> = lines("\u{feff}one\r\ntwo  \r\n \t\r\n\u{feff}three\r\n".as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(read, ["one", "two", "", "\u{feff}three"]);
+    }
+
+    #[test]
+    fn test_normalize_line() {
+        assert_eq!(normalize_line("\u{feff}seeds: 1 2 \r", true), "seeds: 1 2");
+        assert_eq!(normalize_line("\u{feff}seeds: 1 2", false), "\u{feff}seeds: 1 2");
+        assert_eq!(normalize_line("  indented", false), "  indented");
+        assert_eq!(strip_bom("\u{feff}\u{feff}x"), "\u{feff}x");
+    }
+
+    #[test]
+    fn test_lines_invalid_utf8() {
+        let input = &b"one\nt\xffo\nthree\n"[..];
+        let read: Vec<String> = lines(input).map(Result::unwrap).collect();
+        assert_eq!(read, ["one", "t\u{fffd}o", "three"]);
+
+        let mut read = lines(input).strict_utf8(true);
+        assert_eq!(read.next().unwrap().unwrap(), "one");
+        let error = read.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2, column 2: Invalid UTF-8 at byte offset 5");
+        assert_eq!(read.next().unwrap().unwrap(), "three");
+    }
+
+    #[test]
+    fn test_lines_error() {
+        struct Broken;
+
+        impl io::Read for Broken {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+
+        let error = lines(io::BufReader::new(Broken)).next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Error reading line 1");
+        assert!(matches!(error, AocError::Io { .. }));
+    }
+
+    #[test]
+    fn test_read_line() {
+        let mut read = lines("\u{feff}one \r\ntwo".as_bytes());
+        assert_eq!(read.read_line().unwrap().unwrap(), "one");
+        assert_eq!(read.read_line().unwrap().unwrap(), "two");
+        assert!(read.read_line().is_none());
+    }
+
+    #[test]
+    fn test_str_lines() {
+        let read: Vec<&str> = str_lines("\u{feff}one\r\ntwo  \n\n\u{feff}three").map(Result::unwrap).collect();
+        assert_eq!(read, ["one", "two", "", "\u{feff}three"]);
+        assert_eq!(str_lines("").count(), 0);
+        assert_eq!(str_lines("one\n").count(), 1);
+
+        let mut read = str_lines("one\ntwo");
+        assert_eq!(ReadLine::read_line(&mut read).unwrap().unwrap(), "one");
+        assert_eq!(ReadLine::read_line(&mut read).unwrap().unwrap(), "two");
+        assert!(ReadLine::read_line(&mut read).is_none());
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode(b"one\ntwo", true).unwrap(), "one\ntwo");
+        assert_eq!(decode(b"one\nt\xffo", false).unwrap(), "one\nt\u{fffd}o");
+        let error = decode(b"one\nt\xffo", true).unwrap_err();
+        assert_eq!(error.to_string(), "Error on line 2, column 2: Invalid UTF-8 at byte offset 5");
+        assert!(check_utf8(b"\xff").is_err());
+        assert!(check_utf8("\u{feff}\u{fffd}".as_bytes()).is_ok());
+    }
+}
@@ -0,0 +1,182 @@
+//! Shrink an input while it stays interesting, such as while it still makes
+//! a solver panic, to get a small reproducer.
+
+/// One way of cutting down an input, so the cuts can follow a day's
+/// structure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cut {
+    /// Whole sections, separated by blank lines.
+    Sections,
+    /// Single lines.
+    Lines,
+    /// The same word from every line that has it, like a race from both of
+    /// day 6's lines.
+    Columns,
+    /// Groups of this many words from a line, after any label ending in ':'
+    /// at its start, like day 5's pairs of seeds.
+    Words(usize),
+}
+
+/// A word's position: its line, and its index in the line, or `None` for
+/// the whole line.
+type Position = (usize, Option<usize>);
+
+/// The input as lines of whitespace separated words.
+#[derive(Clone, Debug, PartialEq)]
+struct Document {
+    lines: Vec<Vec<String>>,
+}
+
+impl Document {
+    fn new(input: &str) -> Document {
+        Document { lines: input.lines().map(|line| line.split_whitespace().map(String::from).collect()).collect() }
+    }
+
+    fn text(&self) -> String {
+        self.lines.iter().map(|words| words.join(" ") + "\n").collect()
+    }
+
+    /// The number of words at the start of a line that are its label, like
+    /// "Card 1:" or "seeds:".
+    fn label(words: &[String]) -> usize {
+        words.iter().position(|word| word.ends_with(':')).map_or(0, |i| i + 1)
+    }
+
+    /// The units `cut` can remove, each a list of positions.
+    fn units(&self, cut: Cut) -> Vec<Vec<Position>> {
+        match cut {
+            Cut::Sections => {
+                let mut sections: Vec<Vec<Position>> = vec![Vec::new()];
+                for (i, words) in self.lines.iter().enumerate() {
+                    sections.last_mut().unwrap().push((i, None));
+                    if words.is_empty() {
+                        sections.push(Vec::new());
+                    }
+                }
+                sections.retain(|section| !section.is_empty());
+                sections
+            }
+            Cut::Lines => (0..self.lines.len()).map(|i| vec![(i, None)]).collect(),
+            Cut::Columns => {
+                let labels: Vec<usize> = self.lines.iter().map(|words| Document::label(words)).collect();
+                let width = self.lines.iter().zip(&labels).map(|(words, label)| words.len() - label).max().unwrap_or(0);
+                (0..width)
+                    .map(|column| {
+                        self.lines
+                            .iter()
+                            .zip(&labels)
+                            .enumerate()
+                            .filter(|(_, (words, &label))| label + column < words.len())
+                            .map(|(i, (_, &label))| (i, Some(label + column)))
+                            .collect()
+                    })
+                    .collect()
+            }
+            Cut::Words(size) => self
+                .lines
+                .iter()
+                .enumerate()
+                .flat_map(|(i, words)| {
+                    let label = Document::label(words);
+                    (label..words.len()).step_by(size.max(1)).map(move |start| {
+                        (start..(start + size.max(1)).min(words.len())).map(|word| (i, Some(word))).collect()
+                    })
+                })
+                .collect(),
+        }
+    }
+
+    fn without(&self, positions: &[Position]) -> Document {
+        let lines = self
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| !positions.contains(&(i, None)))
+            .map(|(i, words)| {
+                words
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| !positions.contains(&(i, Some(j))))
+                    .map(|(_, word)| word.clone())
+                    .collect()
+            })
+            .collect();
+        Document { lines }
+    }
+}
+
+/// The input with its whitespace normalized as `minimize` does.
+pub fn normalize(input: &str) -> String {
+    Document::new(input).text()
+}
+
+/// Shrink `input` with each of the `cuts` in turn, keeping a removal only if
+/// the result is still `interesting`, until none of them can remove any
+/// more. Each cut first tries removing large runs of units, then smaller
+/// ones. Whitespace is normalized to single spaces, so the input itself
+/// should be interesting in that form.
+pub fn minimize(input: &str, cuts: &[Cut], interesting: &mut dyn FnMut(&str) -> bool) -> String {
+    let mut document = Document::new(input);
+    loop {
+        let before = document.clone();
+        for &cut in cuts {
+            document = minimize_with(document, cut, interesting);
+        }
+        if document == before {
+            return document.text();
+        }
+    }
+}
+
+fn minimize_with(mut document: Document, cut: Cut, interesting: &mut dyn FnMut(&str) -> bool) -> Document {
+    let mut size = document.units(cut).len().div_ceil(2);
+    loop {
+        let units = document.units(cut);
+        if units.is_empty() || size == 0 {
+            return document;
+        }
+        size = size.min(units.len());
+        let current = document.text();
+        let smaller = units.chunks(size).map(|chunk| document.without(&chunk.concat())).find(|candidate| {
+            let text = candidate.text();
+            text.trim() != current.trim() && interesting(&text)
+        });
+        match smaller {
+            Some(smaller) => document = smaller,
+            None if size == 1 => return document,
+            None => size = size.div_ceil(2),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        let document = Document::new("seeds: 1 2 3 4\n\na-to-b map:\n5 6 7\n");
+        assert_eq!(document.units(Cut::Sections), [vec![(0, None), (1, None)], vec![(2, None), (3, None)]]);
+        assert_eq!(document.units(Cut::Words(2))[..2], [vec![(0, Some(1)), (0, Some(2))], vec![(0, Some(3)), (0, Some(4))]]);
+        assert_eq!(document.without(&[(0, Some(1)), (2, None)]).text(), "seeds: 2 3 4\n\n5 6 7\n");
+
+        let document = Document::new("Time:  7  15\nDistance: 9  40\n");
+        assert_eq!(document.units(Cut::Columns), [vec![(0, Some(1)), (1, Some(1))], vec![(0, Some(2)), (1, Some(2))]]);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let input: String = (1..=100).map(|i| format!("{i}\n")).collect();
+        let mut tries = 0;
+        let minimized = minimize(&input, &[Cut::Lines], &mut |text| {
+            tries += 1;
+            text.lines().any(|line| line == "42") && text.lines().any(|line| line == "77")
+        });
+        assert_eq!(minimized, "42\n77\n");
+        assert!(tries < 100, "{tries}");
+
+        let minimized = minimize("Time: 1 2 3\nDistance: 4 5 6\n", &[Cut::Columns], &mut |text| text.contains('5'));
+        assert_eq!(minimized, "Time: 2\nDistance: 5\n");
+        assert_eq!(minimize("a b\n", &[Cut::Lines], &mut |_| false), "a b\n");
+    }
+}
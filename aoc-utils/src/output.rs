@@ -0,0 +1,332 @@
+//! Printing answers, labelled and colored for reading, or bare or as JSON
+//! for scripts.
+
+use std::env;
+use std::fmt::{self, Display};
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use anstyle::{AnsiColor, Style};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::exit;
+use crate::interrupt::Interrupted;
+use crate::solver::Answer;
+
+const ANSWER: Style = AnsiColor::Cyan.on_default().bold();
+const ERROR: Style = AnsiColor::Red.on_default().bold();
+
+/// When to color the output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// When writing to a terminal, and NO_COLOR isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether to color output to a stream, given whether it's a terminal.
+    pub fn enabled(self, terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => terminal && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// How to print the answers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Each answer after its label, with details between them.
+    #[default]
+    Human,
+    /// A JSON object for each answer, one per line, with its label, and
+    /// no details.
+    Json,
+}
+
+/// An answer as printed with `--format json`.
+#[derive(Serialize)]
+struct Labelled<'a> {
+    label: &'a str,
+    answer: Answer,
+}
+
+/// How a binary prints its results.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Output {
+    /// Print only the answers, one per line, without labels or details.
+    pub quiet: bool,
+    pub format: Format,
+    /// Print how long each phase took, to stderr.
+    pub show_time: bool,
+    pub color: ColorChoice,
+}
+
+impl Output {
+    pub fn new(quiet: bool) -> Output {
+        Output { quiet, ..Output::default() }
+    }
+
+    /// With `show_time`, `timings` prints how long each phase took.
+    pub fn show_time(mut self, show_time: bool) -> Output {
+        self.show_time = show_time;
+        self
+    }
+
+    pub fn color(mut self, color: ColorChoice) -> Output {
+        self.color = color;
+        self
+    }
+
+    pub fn format(mut self, format: Format) -> Output {
+        self.format = format;
+        self
+    }
+
+    /// Whether lines that aren't answers are printed: not when quiet, nor
+    /// as JSON.
+    pub fn details(&self) -> bool {
+        !self.quiet && self.format == Format::Human
+    }
+
+    /// Whether output to stdout is colored.
+    pub fn stdout_color(&self) -> bool {
+        self.color.enabled(io::stdout().is_terminal())
+    }
+
+    /// Whether output to stderr is colored.
+    pub fn stderr_color(&self) -> bool {
+        self.color.enabled(io::stderr().is_terminal())
+    }
+
+    /// Print the timings to stderr, if showing them.
+    pub fn timings(&self, timings: &Timings) {
+        if self.show_time {
+            let color = self.stderr_color();
+            eprintln!("{}", fmt::from_fn(|f| timings.write(f, color)));
+        }
+    }
+
+    /// Print an answer after its label, alone when quiet, or as JSON.
+    pub fn answer(&self, label: &str, answer: impl Display) {
+        println!("{}", self.format_answer(label, answer, self.stdout_color()));
+    }
+
+    /// Print a line that isn't an answer, if printing details.
+    pub fn detail(&self, line: impl Display) {
+        if self.details() {
+            println!("{line}");
+        }
+    }
+
+    /// Print an error to stderr as `main` returning it would, but in color,
+    /// and give the exit code for the result, as by [`exit::code`].
+    pub fn exit(&self, result: anyhow::Result<()>) -> ExitCode {
+        match result {
+            Ok(()) => ExitCode::from(exit::SUCCESS),
+            Err(error) if error.is::<Interrupted>() => {
+                eprintln!("{error}");
+                ExitCode::from(exit::code(&error))
+            }
+            Err(error) => {
+                let style = if self.stderr_color() { ERROR } else { Style::new() };
+                eprintln!("{style}Error:{style:#} {error:?}");
+                ExitCode::from(exit::code(&error))
+            }
+        }
+    }
+
+    fn format_answer(&self, label: &str, answer: impl Display, color: bool) -> String {
+        if self.format == Format::Json {
+            // Parsed back, so a number is a JSON number.
+            let answer: Answer = answer.to_string().parse().unwrap();
+            return serde_json::to_string(&Labelled { label, answer }).unwrap();
+        }
+        let style = if color { ANSWER } else { Style::new() };
+        if self.quiet {
+            format!("{style}{answer}{style:#}")
+        } else {
+            format!("{label}: {style}{answer}{style:#}")
+        }
+    }
+}
+
+/// The wall-clock time of each phase of a run, such as reading, parsing,
+/// and solving.
+#[derive(Clone, Debug, Default)]
+pub struct Timings {
+    phases: Vec<(String, Duration)>,
+}
+
+impl Timings {
+    pub fn new() -> Timings {
+        Timings::default()
+    }
+
+    pub fn record(&mut self, phase: impl Into<String>, time: Duration) {
+        self.phases.push((phase.into(), time));
+    }
+
+    /// Run `f`, recording how long it took as `phase`.
+    pub fn time<T>(&mut self, phase: impl Into<String>, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    pub fn phases(&self) -> &[(String, Duration)] {
+        &self.phases
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, time)| *time).sum()
+    }
+
+    /// Write "Read 1.20ms, parse 35.0µs, total 1.24ms", with each time
+    /// colored by how long it is if `color`.
+    fn write(&self, f: &mut fmt::Formatter, color: bool) -> fmt::Result {
+        let time = |time: Duration| {
+            let style = if color { duration_style(time) } else { Style::new() };
+            format!("{style}{}{style:#}", format_duration(time))
+        };
+        for (i, (phase, duration)) in self.phases.iter().enumerate() {
+            let phase = if i == 0 { capitalize(phase) } else { phase.clone() };
+            write!(f, "{phase} {}, ", time(*duration))?;
+        }
+        write!(f, "{} {}", if self.phases.is_empty() { "Total" } else { "total" }, time(self.total()))
+    }
+}
+
+impl fmt::Display for Timings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+/// Green under a millisecond, yellow under a second, and red otherwise.
+fn duration_style(time: Duration) -> Style {
+    let color = if time < Duration::from_millis(1) {
+        AnsiColor::Green
+    } else if time < Duration::from_secs(1) {
+        AnsiColor::Yellow
+    } else {
+        AnsiColor::Red
+    };
+    color.on_default()
+}
+
+fn capitalize(phase: &str) -> String {
+    let mut chars = phase.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// A duration to three significant figures in the largest unit it has a
+/// whole one of: "850ns", "12.3µs", "4.56ms", "1.23s".
+pub fn format_duration(time: Duration) -> String {
+    let nanos = time.as_nanos() as f64;
+    let (value, unit) = if nanos < 1e3 {
+        return format!("{nanos}ns");
+    } else if nanos < 1e6 {
+        (nanos / 1e3, "µs")
+    } else if nanos < 1e9 {
+        (nanos / 1e6, "ms")
+    } else {
+        (nanos / 1e9, "s")
+    };
+    let decimals = if value < 10.0 { 2 } else if value < 100.0 { 1 } else { 0 };
+    format!("{value:.decimals$}{unit}")
+}
+
+/// A size in bytes to three significant figures in the largest binary unit
+/// it has a whole one of: "512B", "12.3KiB", "4.56MiB", "1.23GiB".
+pub fn format_bytes(bytes: u64) -> String {
+    let units = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes}B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    let decimals = if value < 10.0 { 2 } else if value < 100.0 { 1 } else { 0 };
+    format!("{value:.decimals$}{}", units[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_answer() {
+        assert_eq!(Output::default().format_answer("Total score", 13, false), "Total score: 13");
+        assert_eq!(Output::new(true).format_answer("Total score", 13, false), "13");
+        assert_eq!(
+            Output::default().format_answer("Total score", 13, true),
+            "Total score: \u{1b}[1m\u{1b}[36m13\u{1b}[0m"
+        );
+
+        let json = Output::default().format(Format::Json);
+        assert_eq!(json.format_answer("Total score", 13, true), r#"{"label":"Total score","answer":13}"#);
+        assert_eq!(json.format_answer("Part 2", "-4", false), r#"{"label":"Part 2","answer":-4}"#);
+        assert_eq!(json.format_answer("Code", "ABC", false), r#"{"label":"Code","answer":"ABC"}"#);
+        assert!(!json.details() && !Output::new(true).details() && Output::default().details());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(850)), "850ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_345)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(4_561)), "4.56ms");
+        assert_eq!(format_duration(Duration::from_millis(250)), "250ms");
+        assert_eq!(format_duration(Duration::from_millis(1_234)), "1.23s");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(12_595), "12.3KiB");
+        assert_eq!(format_bytes(4_782_031), "4.56MiB");
+        assert_eq!(format_bytes(300 << 20), "300MiB");
+        assert_eq!(format_bytes(1_320_702_444), "1.23GiB");
+    }
+
+    #[test]
+    fn test_timings() {
+        let mut timings = Timings::new();
+        assert_eq!(timings.to_string(), "Total 0ns");
+        timings.record("read", Duration::from_micros(1_200));
+        timings.record("part 1", Duration::from_micros(35));
+        assert_eq!(timings.total(), Duration::from_micros(1_235));
+        assert_eq!(timings.to_string(), "Read 1.20ms, part 1 35.0µs, total 1.24ms");
+        assert_eq!(timings.time("part 2", || 7), 7);
+        assert_eq!(timings.phases().len(), 3);
+    }
+
+    #[test]
+    fn test_timings_color() {
+        let mut timings = Timings::new();
+        timings.record("parse", Duration::from_micros(5));
+        timings.record("solve", Duration::from_secs(2));
+        assert_eq!(
+            fmt::from_fn(|f| timings.write(f, true)).to_string(),
+            "Parse \u{1b}[32m5.00µs\u{1b}[0m, solve \u{1b}[31m2.00s\u{1b}[0m, total \u{1b}[31m2.00s\u{1b}[0m"
+        );
+        assert_eq!(duration_style(Duration::from_millis(5)), AnsiColor::Yellow.on_default());
+    }
+
+    #[test]
+    fn test_color_choice() {
+        assert!(ColorChoice::Always.enabled(false));
+        assert!(!ColorChoice::Never.enabled(true));
+        assert!(!ColorChoice::Auto.enabled(false));
+    }
+}
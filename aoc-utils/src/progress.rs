@@ -0,0 +1,46 @@
+//! Progress bars for long searches, so a brute force shows its throughput
+//! and how long is left. They're drawn on stderr, and hidden when it isn't a
+//! terminal.
+
+use std::time::Duration;
+
+pub use indicatif::{MultiProgress, ProgressBar};
+use indicatif::ProgressStyle;
+
+const TEMPLATE: &str = "{wide_bar} {percent}% {per_sec} ETA {eta}";
+const SPINNER_TEMPLATE: &str = "{spinner} {prefix}: {msg} {elapsed}";
+
+/// A bar for `total` steps.
+pub fn bar(total: u64) -> ProgressBar {
+    ProgressBar::new(total).with_style(ProgressStyle::with_template(TEMPLATE).unwrap())
+}
+
+/// A spinner for a task with no count of steps, showing `prefix`, then the
+/// message set on it.
+pub fn spinner(prefix: impl Into<String>) -> ProgressBar {
+    let spinner = ProgressBar::new_spinner()
+        .with_style(ProgressStyle::with_template(SPINNER_TEMPLATE).unwrap())
+        .with_prefix(prefix.into());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
+/// Run `f` with a callback that advances a bar of `total` steps by the count
+/// it's given, clearing the bar when `f` returns.
+pub fn with_bar<T>(total: u64, f: impl FnOnce(&(dyn Fn(u64) + Sync)) -> T) -> T {
+    let bar = bar(total);
+    let result = f(&|count| bar.inc(count));
+    bar.finish_and_clear();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_bar() {
+        let sum = with_bar(10, |on_progress| (0..10).inspect(|_| on_progress(1)).sum::<u64>());
+        assert_eq!(sum, 45);
+    }
+}
@@ -0,0 +1,124 @@
+//! Puzzle statements, downloaded once and kept as Markdown for reading
+//! offline.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::html;
+use crate::http::Client;
+
+/// Where the Markdown for a puzzle is cached.
+pub fn markdown_path(cache_dir: &Path, year: u16, day: u8) -> PathBuf {
+    cache_dir
+        .join("puzzles")
+        .join(year.to_string())
+        .join(format!("day{day:02}.md"))
+}
+
+/// Read the cached Markdown for a puzzle, if it has been downloaded before.
+pub fn read_cached(cache_dir: &Path, year: u16, day: u8) -> Result<Option<String>> {
+    let path = markdown_path(cache_dir, year, day);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let markdown = fs::read_to_string(&path)
+        .with_context(|| format!("Error reading '{}'", path.display()))?;
+    Ok(Some(markdown))
+}
+
+/// Download the puzzle page, convert its description to Markdown, and cache
+/// the result. `refresh` skips the HTTP cache, e.g. to pick up part 2.
+pub fn fetch(client: &Client, cache_dir: &Path, year: u16, day: u8, refresh: bool) -> Result<String> {
+    let page_path = format!("/{year}/day/{day}");
+    let page = if refresh {
+        client.get_fresh(&page_path)?
+    } else {
+        client.get(&page_path)?
+    };
+    let markdown = to_markdown(&page)
+        .ok_or_else(|| anyhow!("The page for {year} day {day} has no puzzle description"))?;
+
+    let path = markdown_path(cache_dir, year, day);
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(&path, &markdown).with_context(|| format!("Error writing '{}'", path.display()))?;
+    Ok(markdown)
+}
+
+/// An example from a puzzle description.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub part: u8,
+    pub input: String,
+    /// The highlighted answer for the example, if one could be found.
+    pub answer: Option<String>,
+}
+
+/// Find the example for each part on a puzzle page. The example input is the
+/// first `<pre>` block of the part, falling back to part 1's when part 2
+/// reuses it, and the answer is the last highlighted code in the part.
+pub fn examples(page: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = Vec::new();
+    for (i, article) in html::articles(page).into_iter().enumerate() {
+        let input = html::pre_blocks(article)
+            .into_iter()
+            .next()
+            .or_else(|| examples.first().map(|example| example.input.clone()));
+        let Some(input) = input else {
+            continue;
+        };
+        examples.push(Example {
+            part: i as u8 + 1,
+            input,
+            answer: html::emphasized_code(article).pop(),
+        });
+    }
+    examples
+}
+
+/// Convert the descriptions on a puzzle page (one article per part) to
+/// Markdown.
+fn to_markdown(page: &str) -> Option<String> {
+    let parts: Vec<String> = html::articles(page).into_iter().map(html::to_markdown).collect();
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        let page = "<html><body><main>\
+            <article class=\"day-desc\"><h2>--- Day 1 ---</h2><p>Part <em>one</em>.</p></article>\
+            <p>Your puzzle answer was <code>1</code>.</p>\
+            <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>Part two.</p></article>\
+            </main></body></html>";
+        assert_eq!(
+            to_markdown(page).unwrap(),
+            "## --- Day 1 ---\n\nPart *one*.\n\n## --- Part Two ---\n\nPart two.\n"
+        );
+        assert_eq!(to_markdown("<html>Not found</html>"), None);
+    }
+
+    #[test]
+    fn test_examples() {
+        let page = "<article><p>For example:</p><pre><code>1\n2\n</code></pre>\
+            <pre><code>step\n</code></pre><p>Total is <code><em>3</em></code>.</p></article>\
+            <p>Your puzzle answer was <code>1</code>.</p>\
+            <article><p>Now the total is <code><em>5</em></code>.</p></article>";
+        assert_eq!(
+            examples(page),
+            vec![
+                Example { part: 1, input: "1\n2\n".to_string(), answer: Some("3".to_string()) },
+                Example { part: 2, input: "1\n2\n".to_string(), answer: Some("5".to_string()) },
+            ]
+        );
+        assert!(examples("<article><p>No examples</p></article>").is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+//! A small seeded random number generator, so generated inputs are the same
+//! on every run and platform.
+
+use std::ops::Range;
+
+/// SplitMix64: fast and good enough for test inputs, but not for anything
+/// that needs to be unpredictable.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which mustn't be empty. Very large ranges are
+    /// slightly biased towards their start.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "Empty range {range:?}");
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// True one time in `n`.
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.range(0..n) == 0
+    }
+
+    /// A random item of `items`, which mustn't be empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len() as u64) as usize]
+    }
+
+    /// Put `items` in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i as u64 + 1) as usize);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(1);
+        let first: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+        assert_eq!(first, (0..3).map({
+            let mut rng = Rng::new(1);
+            move |_| rng.next_u64()
+        }).collect::<Vec<_>>());
+        assert_ne!(Rng::new(2).next_u64(), first[0]);
+
+        for _ in 0..1000 {
+            assert!((10..20).contains(&rng.range(10..20)));
+        }
+        assert_eq!(rng.range(u64::MAX - 1..u64::MAX), u64::MAX - 1);
+
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+}
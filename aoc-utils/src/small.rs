@@ -0,0 +1,29 @@
+//! Collections kept inline for the short, bounded lists puzzle lines split
+//! into, such as a card's numbers or a mapping's three values, so parsing a
+//! line doesn't allocate.
+
+pub use arrayvec::ArrayVec;
+pub use smallvec::SmallVec;
+
+/// Split `s` on ASCII whitespace into exactly `N` fields, or `None` if there
+/// are more or fewer.
+pub fn fields<const N: usize>(s: &str) -> Option<[&str; N]> {
+    let mut fields = ArrayVec::new();
+    for field in s.split_ascii_whitespace() {
+        fields.try_push(field).ok()?;
+    }
+    fields.into_inner().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fields() {
+        assert_eq!(fields(" 52  50\t48 "), Some(["52", "50", "48"]));
+        assert_eq!(fields::<3>("52 50"), None);
+        assert_eq!(fields::<3>("52 50 48 1"), None);
+        assert_eq!(fields::<0>(""), Some([]));
+    }
+}
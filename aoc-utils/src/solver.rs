@@ -0,0 +1,300 @@
+//! The common shape of a day's solution, so the tooling can run any day and
+//! treat its answers alike.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::cancel::Cancel;
+use crate::error::{AocError, Result};
+
+/// A puzzle answer: a number, or text for puzzles whose answer is a word or
+/// letters drawn in ASCII art. Serialized as a JSON number or string.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Unsigned(u64),
+    Signed(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Unsigned(answer) => write!(f, "{answer}"),
+            Answer::Signed(answer) => write!(f, "{answer}"),
+            Answer::Text(answer) => write!(f, "{answer}"),
+        }
+    }
+}
+
+/// Parses an answer as it would be submitted: a number if it is one, and
+/// text otherwise.
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Answer, Self::Err> {
+        let s = s.trim();
+        Ok(if let Ok(answer) = s.parse() {
+            Answer::Unsigned(answer)
+        } else if let Ok(answer) = s.parse() {
+            Answer::Signed(answer)
+        } else {
+            Answer::Text(s.to_string())
+        })
+    }
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(answer: $t) -> Answer {
+                Answer::Unsigned(answer.into())
+            }
+        }
+    )*};
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(answer: $t) -> Answer {
+                Answer::Signed(answer.into())
+            }
+        }
+    )*};
+}
+
+from_unsigned!(u8, u16, u32, u64);
+from_signed!(i8, i16, i32, i64);
+
+impl From<usize> for Answer {
+    fn from(answer: usize) -> Answer {
+        Answer::Unsigned(answer as u64)
+    }
+}
+
+impl TryFrom<u128> for Answer {
+    type Error = AocError;
+
+    fn try_from(answer: u128) -> Result<Answer> {
+        u64::try_from(answer)
+            .map(Answer::Unsigned)
+            .map_err(|_| AocError::overflow(format!("The answer {answer} is too big")))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(answer: String) -> Answer {
+        Answer::Text(answer)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(answer: &str) -> Answer {
+        Answer::Text(answer.to_string())
+    }
+}
+
+/// A day's solution. The input is parsed once, then each part is solved from
+/// the parsed input. A part that may take a while should check `cancel` as
+/// it goes, and stop with its error once cancelled.
+pub trait Solver {
+    /// The input as both parts need it.
+    type Parsed;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed>;
+
+    fn part1(&self, parsed: &Self::Parsed, cancel: &Cancel) -> Result<Answer>;
+
+    fn part2(&self, parsed: &Self::Parsed, cancel: &Cancel) -> Result<Answer>;
+}
+
+/// A part's answer, with how long the input took to parse and the part to
+/// solve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solved {
+    pub part: u8,
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// A `Solver` with its parsed type hidden, so solvers for different days can
+/// be kept together.
+pub trait DynSolver: Sync {
+    /// Parse the input and solve the part, 1 or 2.
+    fn solve(&self, input: &str, part: u8) -> Result<Answer>;
+
+    /// Parse the input and solve both parts.
+    fn solve_both(&self, input: &str) -> Result<[Answer; 2]>;
+
+    /// Parse the input once and solve each of `parts`, timing both.
+    fn solve_timed(&self, input: &str, parts: &[u8]) -> Result<Vec<Solved>> {
+        let mut solved = Vec::new();
+        self.solve_each(input, parts, &Cancel::new(), &mut |part| solved.push(part))?;
+        Ok(solved)
+    }
+
+    /// Like `solve_timed`, giving each part to `on_solved` as it's solved,
+    /// and stopping with `AocError::Cancelled` once `cancel` is cancelled.
+    fn solve_each(&self, input: &str, parts: &[u8], cancel: &Cancel, on_solved: &mut dyn FnMut(Solved)) -> Result<()>;
+}
+
+impl<S: Solver + Sync> DynSolver for S {
+    fn solve(&self, input: &str, part: u8) -> Result<Answer> {
+        let parsed = self.parse(input)?;
+        solve_part(self, &parsed, part, &Cancel::new())
+    }
+
+    fn solve_both(&self, input: &str) -> Result<[Answer; 2]> {
+        let parsed = self.parse(input)?;
+        let cancel = Cancel::new();
+        Ok([self.part1(&parsed, &cancel)?, self.part2(&parsed, &cancel)?])
+    }
+
+    fn solve_each(&self, input: &str, parts: &[u8], cancel: &Cancel, on_solved: &mut dyn FnMut(Solved)) -> Result<()> {
+        let start = Instant::now();
+        let parsed = self.parse(input)?;
+        let parse_time = start.elapsed();
+        for &part in parts {
+            cancel.check()?;
+            let start = Instant::now();
+            let answer = solve_part(self, &parsed, part, cancel)?;
+            on_solved(Solved { part, answer, parse_time, solve_time: start.elapsed() });
+        }
+        Ok(())
+    }
+}
+
+fn solve_part<S: Solver + ?Sized>(solver: &S, parsed: &S::Parsed, part: u8, cancel: &Cancel) -> Result<Answer> {
+    match part {
+        1 => solver.part1(parsed, cancel),
+        2 => solver.part2(parsed, cancel),
+        _ => Err(AocError::no_solution(format!("There is no part {part}"))),
+    }
+}
+
+/// Assert that a solver's answer for a part of an example is `expected`,
+/// compared as they'd be submitted.
+pub fn assert_example(solver: &dyn DynSolver, input: &str, part: u8, expected: impl fmt::Display) {
+    match solver.solve(input, part) {
+        Ok(answer) => assert_eq!(answer.to_string(), expected.to_string(), "Wrong answer for part {part} of the example"),
+        Err(error) => panic!("Error solving part {part} of the example: {error}"),
+    }
+}
+
+/// Generate tests that solve an example with a solver and check the answer
+/// to each part given. The input is a path relative to the crate, usually a
+/// fixture written by `aoc examples`.
+///
+/// ```ignore
+/// aoc_example_tests!(Day05, part1 = 35, part2 = 46, input = "fixtures/example.txt");
+/// ```
+///
+/// The tests are named `test_part1_example` and `test_part2_example`, so
+/// parts with different examples each need their own invocation.
+#[macro_export]
+macro_rules! aoc_example_tests {
+    ($solver:expr, $(part1 = $part1:expr,)? $(part2 = $part2:expr,)? input = $input:literal $(,)?) => {
+        $(
+            #[test]
+            fn test_part1_example() {
+                let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $input));
+                $crate::solver::assert_example(&$solver, input, 1, $part1);
+            }
+        )?
+        $(
+            #[test]
+            fn test_part2_example() {
+                let input = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $input));
+                $crate::solver::assert_example(&$solver, input, 2, $part2);
+            }
+        )?
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Answer::from(42u32).to_string(), "42");
+        assert_eq!(Answer::from(-7i64).to_string(), "-7");
+        assert_eq!(Answer::from("ABCD").to_string(), "ABCD");
+        assert_eq!(Answer::try_from(5u128).unwrap(), Answer::Unsigned(5));
+        assert!(Answer::try_from(u128::MAX).is_err());
+
+        assert_eq!(" 42\n".parse::<Answer>().unwrap(), Answer::Unsigned(42));
+        assert_eq!("-7".parse::<Answer>().unwrap(), Answer::Signed(-7));
+        assert_eq!("ABCD".parse::<Answer>().unwrap(), Answer::Text("ABCD".to_string()));
+    }
+
+    #[test]
+    fn test_answer_serde() {
+        let answers = vec![Answer::Unsigned(42), Answer::Signed(-7), Answer::from("ABCD")];
+        let json = serde_json::to_string(&answers).unwrap();
+        assert_eq!(json, r#"[42,-7,"ABCD"]"#);
+        assert_eq!(serde_json::from_str::<Vec<Answer>>(&json).unwrap(), answers);
+    }
+
+    struct Sum;
+
+    impl Solver for Sum {
+        type Parsed = Vec<i64>;
+
+        fn parse(&self, input: &str) -> Result<Vec<i64>> {
+            input.split_whitespace().map(|number| Ok(number.parse()?)).collect()
+        }
+
+        fn part1(&self, numbers: &Vec<i64>, _: &Cancel) -> Result<Answer> {
+            Ok(numbers.iter().sum::<i64>().into())
+        }
+
+        fn part2(&self, numbers: &Vec<i64>, cancel: &Cancel) -> Result<Answer> {
+            cancel.check()?;
+            Ok(numbers.len().into())
+        }
+    }
+
+    #[test]
+    fn test_assert_example() {
+        assert_example(&Sum, "1 2 3", 1, 6);
+        assert_example(&Sum, "1 2 3", 2, "3");
+    }
+
+    #[test]
+    #[should_panic(expected = "Wrong answer for part 1 of the example")]
+    fn test_assert_example_wrong() {
+        assert_example(&Sum, "1 2 3", 1, 7);
+    }
+
+    #[test]
+    fn test_dyn_solver() {
+        let solver: &dyn DynSolver = &Sum;
+        assert_eq!(solver.solve("1 2 -5", 1).unwrap(), Answer::Signed(-2));
+        assert_eq!(solver.solve("1 2 -5", 2).unwrap(), Answer::Unsigned(3));
+        assert_eq!(solver.solve_both("4").unwrap(), [Answer::Signed(4), Answer::Unsigned(1)]);
+        assert!(solver.solve("1", 3).is_err());
+        assert!(solver.solve("x", 1).is_err());
+
+        let solved = solver.solve_timed("1 2", &[2, 1]).unwrap();
+        let answers: Vec<_> = solved.iter().map(|solved| (solved.part, solved.answer.clone())).collect();
+        assert_eq!(answers, [(2, Answer::Unsigned(2)), (1, Answer::Signed(3))]);
+        assert_eq!(solved[0].parse_time, solved[1].parse_time);
+        assert!(solver.solve_timed("1", &[3]).is_err());
+
+        let cancel = Cancel::new();
+        let mut solved = Vec::new();
+        let result = solver.solve_each("1 2", &[1, 2], &cancel, &mut |part| {
+            solved.push(part.answer);
+            cancel.cancel();
+        });
+        assert!(matches!(result, Err(AocError::Cancelled)));
+        assert_eq!(solved, [Answer::Signed(3)]);
+    }
+}
@@ -0,0 +1,282 @@
+//! Where a solution reads its input from: stdin, a file, a URL, or the
+//! clipboard. Every source is decompressed as by [`crate::compress`].
+
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, Read};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use memmap2::Mmap;
+
+use crate::compress::{self, Compression};
+use crate::exit::MissingInput;
+use crate::http::USER_AGENT;
+use crate::lines::{self, Lines};
+
+/// An input source, parsed from a command line argument: "-" for stdin, a
+/// URL starting "http://" or "https://", "clipboard:" for the clipboard, and
+/// anything else is a file path.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum InputSource {
+    #[default]
+    Stdin,
+    File(PathBuf),
+    Url(String),
+    Clipboard,
+}
+
+/// The input option every day's binary takes, flattened into its arguments.
+#[derive(clap::Args, Clone, Debug)]
+#[command(about = None, long_about = None)]
+pub struct InputArg {
+    /// Where to read the input: a file, "-" for stdin, the default, a URL, or
+    /// "clipboard:".
+    #[arg(short = 'i', long = "input", id = "input", value_name = "SOURCE", default_value = "-")]
+    pub source: InputSource,
+}
+
+impl FromStr for InputSource {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<InputSource, Infallible> {
+        Ok(match s {
+            "-" => InputSource::Stdin,
+            "clipboard:" => InputSource::Clipboard,
+            _ if s.starts_with("http://") || s.starts_with("https://") => InputSource::Url(s.to_string()),
+            _ => InputSource::File(PathBuf::from(s)),
+        })
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Url(url) => write!(f, "{url}"),
+            InputSource::Clipboard => write!(f, "the clipboard"),
+        }
+    }
+}
+
+impl InputSource {
+    /// The file's path, if the source is a file.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            InputSource::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Open the source for reading, decompressing it if it is compressed.
+    pub fn open(&self) -> Result<Box<dyn BufRead>> {
+        let reader = match self {
+            InputSource::Stdin => compress::stdin(),
+            InputSource::File(path) if !path.exists() => {
+                let error = anyhow::Error::from(MissingInput::File(path.clone()));
+                return Err(error.context(format!("Error opening {}", self.quoted())));
+            }
+            InputSource::File(path) => compress::open(path),
+            InputSource::Url(url) => {
+                let agent: ureq::Agent = ureq::Agent::config_builder()
+                    .user_agent(USER_AGENT)
+                    .timeout_global(Some(Duration::from_secs(30)))
+                    .build()
+                    .into();
+                let response = agent.get(url).call().with_context(|| format!("Error requesting '{url}'"))?;
+                compress::decompress(io::BufReader::new(response.into_body().into_reader()), Some(Path::new(url)))
+            }
+            InputSource::Clipboard => read_clipboard().and_then(|input| compress::decompress(io::Cursor::new(input), None)),
+        };
+        reader.with_context(|| format!("Error opening {}", self.quoted()))
+    }
+
+    /// Read the lines of the source, as by [`lines::lines`].
+    pub fn lines(&self) -> Result<Lines<Box<dyn BufRead>>> {
+        Ok(lines::lines(self.open()?))
+    }
+
+    /// Read the whole source.
+    pub fn read(&self) -> Result<Vec<u8>> {
+        let mut input = Vec::new();
+        self.open()?
+            .read_to_end(&mut input)
+            .with_context(|| format!("Error reading {}", self.quoted()))?;
+        Ok(input)
+    }
+
+    /// Read the whole source as text, as by [`lines::decode`].
+    pub fn read_to_string(&self, strict_utf8: bool) -> Result<String> {
+        Ok(lines::decode(&self.read()?, strict_utf8)?.into_owned())
+    }
+
+    /// Map the source into memory rather than reading it, when it's a file
+    /// or stdin redirected from one, so even a huge input isn't copied.
+    /// Other sources, and compressed inputs, are read whole.
+    pub fn map(&self) -> Result<MappedInput> {
+        let mapped = match self {
+            InputSource::File(path) if path.exists() => {
+                let file = File::open(path).with_context(|| format!("Error opening {}", self.quoted()))?;
+                map_file(&file)
+            }
+            InputSource::Stdin => stdin_file().as_ref().and_then(map_file),
+            _ => None,
+        };
+        match mapped {
+            Some(mapped) if Compression::detect(&mapped, self.path()) == Compression::None => {
+                Ok(MappedInput::Mapped(mapped))
+            }
+            Some(mapped) => {
+                let mut input = Vec::new();
+                compress::decompress(&mapped[..], self.path())
+                    .and_then(|mut reader| reader.read_to_end(&mut input))
+                    .with_context(|| format!("Error reading {}", self.quoted()))?;
+                Ok(MappedInput::Read(input))
+            }
+            None => Ok(MappedInput::Read(self.read()?)),
+        }
+    }
+
+    /// The source for error messages: a path or URL in quotes, or "stdin".
+    fn quoted(&self) -> String {
+        match self {
+            InputSource::File(_) | InputSource::Url(_) => format!("'{self}'"),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// A whole input in memory, from [`InputSource::map`].
+pub enum MappedInput {
+    /// A file mapped in place.
+    Mapped(Mmap),
+    /// An input that couldn't be mapped, read or decompressed into memory.
+    Read(Vec<u8>),
+}
+
+impl Deref for MappedInput {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            MappedInput::Mapped(mapped) => mapped,
+            MappedInput::Read(input) => input,
+        }
+    }
+}
+
+impl MappedInput {
+    /// The input as text, as by [`lines::decode`], borrowed from the mapping
+    /// unless bytes that aren't UTF-8 had to be replaced.
+    pub fn text(&self, strict_utf8: bool) -> Result<Cow<'_, str>> {
+        Ok(lines::decode(self, strict_utf8)?)
+    }
+}
+
+/// Map a file into memory, or `None` if it isn't a regular file, as a pipe
+/// or terminal isn't, or can't be mapped.
+fn map_file(file: &File) -> Option<Mmap> {
+    if !file.metadata().ok()?.is_file() {
+        return None;
+    }
+    // SAFETY: The input must not be modified while it's mapped. It's only
+    // read here, and nothing else should be writing it.
+    unsafe { Mmap::map(file) }.ok()
+}
+
+/// Stdin as a file, to map it when it's redirected from one.
+#[cfg(unix)]
+fn stdin_file() -> Option<File> {
+    use std::os::fd::AsFd;
+
+    Some(File::from(io::stdin().as_fd().try_clone_to_owned().ok()?))
+}
+
+#[cfg(not(unix))]
+fn stdin_file() -> Option<File> {
+    None
+}
+
+/// The commands that print the clipboard, tried in turn.
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(windows)]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-out"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Read the clipboard with the first of the clipboard commands that works.
+fn read_clipboard() -> io::Result<Vec<u8>> {
+    for command in CLIPBOARD_COMMANDS {
+        match Command::new(command[0]).args(&command[1..]).output() {
+            Ok(output) if output.status.success() => return Ok(output.stdout),
+            Ok(_) => continue,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    let tried: Vec<&str> = CLIPBOARD_COMMANDS.iter().map(|command| command[0]).collect();
+    Err(io::Error::other(format!("No clipboard to read; tried {}", tried.join(", "))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("-".parse(), Ok(InputSource::Stdin));
+        assert_eq!("clipboard:".parse(), Ok(InputSource::Clipboard));
+        assert_eq!("https://example.com/input".parse(), Ok(InputSource::Url("https://example.com/input".to_string())));
+        assert_eq!("inputs/day05.txt".parse(), Ok(InputSource::File(PathBuf::from("inputs/day05.txt"))));
+        assert_eq!("clipboard".parse(), Ok(InputSource::File(PathBuf::from("clipboard"))));
+    }
+
+    #[test]
+    fn test_file() {
+        let path = std::env::temp_dir().join(format!("aoc-utils-source-{}.txt", std::process::id()));
+        std::fs::write(&path, "one\r\ntwo\n").unwrap();
+        let source = InputSource::File(path.clone());
+        assert_eq!(source.path(), Some(path.as_path()));
+        assert_eq!(source.read_to_string(true).unwrap(), "one\r\ntwo\n");
+        let lines: Vec<String> = source.lines().unwrap().map(Result::unwrap).collect();
+        assert_eq!(lines, ["one", "two"]);
+        let mapped = source.map().unwrap();
+        assert!(matches!(mapped, MappedInput::Mapped(_)));
+        assert_eq!(mapped.text(true).unwrap(), "one\r\ntwo\n");
+        drop(mapped);
+        std::fs::remove_file(&path).unwrap();
+
+        let error = source.read().unwrap_err();
+        assert_eq!(error.to_string(), format!("Error opening '{}'", path.display()));
+        assert!(source.map().is_err());
+        assert_eq!(crate::exit::code(&error), crate::exit::MISSING_INPUT);
+    }
+
+    #[test]
+    fn test_map_compressed() {
+        let path = std::env::temp_dir().join(format!("aoc-utils-source-{}.txt.gz", std::process::id()));
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"one\ntwo\n").unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+        let mapped = InputSource::File(path.clone()).map().unwrap();
+        assert!(matches!(mapped, MappedInput::Read(_)));
+        assert_eq!(&mapped[..], b"one\ntwo\n");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
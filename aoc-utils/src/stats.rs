@@ -0,0 +1,62 @@
+//! Summary statistics of a solve timed over and over.
+
+use std::time::Duration;
+
+/// The spread of a set of times.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    /// The sample standard deviation, zero for a single run.
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// The statistics of `times`, or `None` if there are none.
+    pub fn new(times: &[Duration]) -> Option<Stats> {
+        let mut seconds: Vec<f64> = times.iter().map(Duration::as_secs_f64).collect();
+        seconds.sort_by(f64::total_cmp);
+        let runs = seconds.len();
+        let min = *seconds.first()?;
+        let mean = seconds.iter().sum::<f64>() / runs as f64;
+        let median = if runs % 2 == 1 {
+            seconds[runs / 2]
+        } else {
+            (seconds[runs / 2 - 1] + seconds[runs / 2]) / 2.0
+        };
+        let variance = if runs > 1 {
+            seconds.iter().map(|time| (time - mean).powi(2)).sum::<f64>() / (runs - 1) as f64
+        } else {
+            0.0
+        };
+        Some(Stats {
+            runs,
+            min: Duration::from_secs_f64(min),
+            mean: Duration::from_secs_f64(mean),
+            median: Duration::from_secs_f64(median),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(Stats::new(&[]), None);
+        let times = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::new(&times).unwrap();
+        assert_eq!(stats.runs, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.stddev.as_micros(), 1290);
+
+        let stats = Stats::new(&[Duration::from_millis(7)]).unwrap();
+        assert_eq!((stats.median, stats.stddev), (Duration::from_millis(7), Duration::ZERO));
+    }
+}
@@ -0,0 +1,371 @@
+//! Answer submission: posting answers, understanding the site's reply, and
+//! keeping a local history of every submission.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::html;
+use crate::http::Client;
+
+/// The cooldown assumed when the site says an answer was too recent without
+/// saying how long to wait.
+const FALLBACK_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// The site's response to a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about the direction.
+    Incorrect,
+    /// An answer was submitted too recently; this one was not checked.
+    TooRecent,
+    /// The part was already solved, or isn't unlocked yet.
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Whether the site actually checked the answer.
+    pub fn was_checked(&self) -> bool {
+        !matches!(self, Verdict::TooRecent | Verdict::WrongLevel)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    /// Unix timestamp of the submission.
+    pub time: u64,
+}
+
+/// All the submissions for one puzzle, stored as JSON on disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(skip)]
+    path: PathBuf,
+    pub submissions: Vec<Submission>,
+    /// Unix timestamp before which the site won't accept another answer.
+    #[serde(default)]
+    pub cooldown_until: Option<u64>,
+}
+
+impl History {
+    /// Load the history for the puzzle from `dir`, or an empty history if
+    /// nothing has been submitted yet.
+    pub fn load(dir: &Path, year: u16, day: u8) -> Result<History> {
+        let path = dir.join(year.to_string()).join(format!("day{day:02}.json"));
+        let mut history: History = if path.exists() {
+            let text = fs::read_to_string(&path)
+                .with_context(|| format!("Error reading '{}'", path.display()))?;
+            serde_json::from_str(&text)
+                .with_context(|| format!("Error parsing '{}'", path.display()))?
+        } else {
+            History::default()
+        };
+        history.path = path;
+        Ok(history)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = serde_json::to_string_pretty(self)?;
+        fs::write(&self.path, text)
+            .with_context(|| format!("Error writing '{}'", self.path.display()))
+    }
+
+    pub fn record(&mut self, part: u8, answer: &str, verdict: Verdict) -> Result<()> {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        self.submissions.push(Submission {
+            part,
+            answer: answer.to_string(),
+            verdict,
+            time,
+        });
+        Ok(())
+    }
+
+    /// How long until another answer may be submitted, if in a cooldown.
+    pub fn cooldown_remaining(&self) -> Option<Duration> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+        self.cooldown_until
+            .filter(|&until| until > now)
+            .map(|until| Duration::from_secs(until - now))
+    }
+
+    /// Start a cooldown lasting `wait` from now.
+    pub fn set_cooldown(&mut self, wait: Duration) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        self.cooldown_until = Some(now + wait.as_secs());
+        Ok(())
+    }
+
+    /// The previous checked submission of the same answer for the part.
+    pub fn find(&self, part: u8, answer: &str) -> Option<&Submission> {
+        self.submissions
+            .iter()
+            .find(|s| s.part == part && s.answer == answer && s.verdict.was_checked())
+    }
+
+    /// The range the answer must be in, implied by previous "too low" and
+    /// "too high" verdicts. Returns (largest too low, smallest too high).
+    pub fn bounds(&self, part: u8) -> (Option<i128>, Option<i128>) {
+        let mut lower = None;
+        let mut upper = None;
+        for submission in self.submissions.iter().filter(|s| s.part == part) {
+            let Ok(answer) = submission.answer.parse::<i128>() else {
+                continue;
+            };
+            match submission.verdict {
+                Verdict::TooLow => lower = lower.max(Some(answer)),
+                Verdict::TooHigh => upper = Some(upper.map_or(answer, |upper| answer.min(upper))),
+                _ => {}
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Explain why the answer is provably wrong based on previous guesses.
+    pub fn check_bounds(&self, part: u8, answer: &str) -> Option<String> {
+        let answer: i128 = answer.parse().ok()?;
+        let (lower, upper) = self.bounds(part);
+        if let Some(lower) = lower.filter(|&lower| answer <= lower) {
+            return Some(format!("{answer} is not higher than {lower}, which was too low"));
+        }
+        if let Some(upper) = upper.filter(|&upper| answer >= upper) {
+            return Some(format!("{answer} is not lower than {upper}, which was too high"));
+        }
+        None
+    }
+
+    /// The accepted answer for the part, if it has been solved.
+    pub fn solution(&self, part: u8) -> Option<&str> {
+        self.submissions
+            .iter()
+            .find(|s| s.part == part && s.verdict == Verdict::Correct)
+            .map(|s| s.answer.as_str())
+    }
+}
+
+/// The default directory for submission histories.
+pub fn default_history_dir() -> Result<PathBuf> {
+    let dir = dirs::data_local_dir().ok_or_else(|| anyhow!("Unable to find a data directory"))?;
+    Ok(dir.join("aoc").join("submissions"))
+}
+
+/// Submit an answer for the part and return the site's verdict along with
+/// the text of its reply.
+pub fn submit(client: &Client, year: u16, day: u8, part: u8, answer: &str) -> Result<(Verdict, String)> {
+    let level = part.to_string();
+    let html = client.post_form(
+        &format!("/{year}/day/{day}/answer"),
+        &[("level", &level), ("answer", answer)],
+    )?;
+    let message = html::articles(&html)
+        .first()
+        .map_or_else(|| html::to_text(&html), |article| html::to_text(article));
+    let verdict = parse_verdict(&message)
+        .ok_or_else(|| anyhow!("Unrecognized response to the submission: '{message}'"))?;
+    Ok((verdict, message))
+}
+
+/// Work out the verdict from the text of the response.
+pub fn parse_verdict(message: &str) -> Option<Verdict> {
+    if message.contains("That's the right answer") {
+        Some(Verdict::Correct)
+    } else if message.contains("That's not the right answer") {
+        if message.contains("too high") {
+            Some(Verdict::TooHigh)
+        } else if message.contains("too low") {
+            Some(Verdict::TooLow)
+        } else {
+            Some(Verdict::Incorrect)
+        }
+    } else if message.contains("You gave an answer too recently") {
+        Some(Verdict::TooRecent)
+    } else if message.contains("You don't seem to be solving the right level") {
+        Some(Verdict::WrongLevel)
+    } else {
+        None
+    }
+}
+
+/// Parse how long the site asks to wait before the next submission, from
+/// either "You have 1m 4s left to wait" or "Please wait 5 minutes before
+/// trying again".
+pub fn parse_wait(message: &str) -> Option<Duration> {
+    if let Some(end) = message.find(" left to wait") {
+        let start = message[..end].rfind("You have ")? + "You have ".len();
+        let mut seconds = 0;
+        for part in message[start..end].split_whitespace() {
+            let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let number: u64 = number.parse().ok()?;
+            seconds += match unit {
+                "h" => number * 3600,
+                "m" => number * 60,
+                "s" => number,
+                _ => return None,
+            };
+        }
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let lower = message.to_lowercase();
+    let start = lower.find("please wait ")? + "please wait ".len();
+    let mut words = lower[start..].split_whitespace();
+    let count = match words.next()? {
+        "one" | "a" => 1,
+        number => number.parse().ok()?,
+    };
+    match words.next()?.trim_end_matches('s') {
+        "second" => Some(Duration::from_secs(count)),
+        "minute" => Some(Duration::from_secs(count * 60)),
+        "hour" => Some(Duration::from_secs(count * 3600)),
+        _ => None,
+    }
+}
+
+/// How long to wait after a submission with this reply before submitting
+/// again: the wait it asks for, or a minute if it's too recent but doesn't
+/// say how long.
+pub fn cooldown(verdict: Verdict, message: &str) -> Option<Duration> {
+    parse_wait(message).or((verdict == Verdict::TooRecent).then_some(FALLBACK_COOLDOWN))
+}
+
+/// Format a duration the way the site does, e.g. "4m 12s".
+pub fn format_wait(wait: Duration) -> String {
+    let seconds = wait.as_secs();
+    match (seconds / 60, seconds % 60) {
+        (0, s) => format!("{s}s"),
+        (m, 0) => format!("{m}m"),
+        (m, s) => format!("{m}m {s}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verdict() {
+        assert_eq!(
+            parse_verdict("That's the right answer! You are one gold star closer to restoring snow operations."),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            parse_verdict("That's not the right answer; your answer is too high. Please wait one minute before trying again."),
+            Some(Verdict::TooHigh)
+        );
+        assert_eq!(
+            parse_verdict("That's not the right answer; your answer is too low."),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            parse_verdict("That's not the right answer. If you're stuck, make sure you're using the full input data."),
+            Some(Verdict::Incorrect)
+        );
+        assert_eq!(
+            parse_verdict("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 32s left to wait."),
+            Some(Verdict::TooRecent)
+        );
+        assert_eq!(
+            parse_verdict("You don't seem to be solving the right level. Did you already complete it?"),
+            Some(Verdict::WrongLevel)
+        );
+        assert_eq!(parse_verdict("Something else"), None);
+    }
+
+    #[test]
+    fn test_parse_wait() {
+        assert_eq!(
+            parse_wait("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 32s left to wait."),
+            Some(Duration::from_secs(32))
+        );
+        assert_eq!(parse_wait("You have 1m 4s left to wait."), Some(Duration::from_secs(64)));
+        assert_eq!(parse_wait("You have 5m left to wait."), Some(Duration::from_secs(300)));
+        assert_eq!(
+            parse_wait("That's not the right answer. Please wait one minute before trying again."),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_wait("That's not the right answer; your answer is too low. please wait 5 minutes before trying again."),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(parse_wait("That's the right answer!"), None);
+    }
+
+    #[test]
+    fn test_cooldown_from_reply() {
+        assert_eq!(cooldown(Verdict::TooRecent, "You have 32s left to wait."), Some(Duration::from_secs(32)));
+        assert_eq!(cooldown(Verdict::TooRecent, "You gave an answer too recently."), Some(FALLBACK_COOLDOWN));
+        assert_eq!(
+            cooldown(Verdict::TooLow, "your answer is too low. Please wait one minute before trying again."),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(cooldown(Verdict::Correct, "That's the right answer!"), None);
+    }
+
+    #[test]
+    fn test_format_wait() {
+        assert_eq!(format_wait(Duration::from_secs(32)), "32s");
+        assert_eq!(format_wait(Duration::from_secs(60)), "1m");
+        assert_eq!(format_wait(Duration::from_secs(64)), "1m 4s");
+    }
+
+    #[test]
+    fn test_cooldown() {
+        let mut history = History::default();
+        assert_eq!(history.cooldown_remaining(), None);
+        history.set_cooldown(Duration::from_secs(60)).unwrap();
+        let remaining = history.cooldown_remaining().unwrap();
+        assert!(remaining <= Duration::from_secs(60) && remaining >= Duration::from_secs(59));
+        history.cooldown_until = Some(1);
+        assert_eq!(history.cooldown_remaining(), None);
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut history = History::default();
+        assert_eq!(history.bounds(1), (None, None));
+        history.record(1, "100", Verdict::TooHigh).unwrap();
+        history.record(1, "20", Verdict::TooLow).unwrap();
+        history.record(1, "50", Verdict::TooHigh).unwrap();
+        history.record(1, "10", Verdict::TooLow).unwrap();
+        history.record(1, "abc", Verdict::TooLow).unwrap();
+        history.record(2, "30", Verdict::TooLow).unwrap();
+        assert_eq!(history.bounds(1), (Some(20), Some(50)));
+        assert_eq!(history.bounds(2), (Some(30), None));
+
+        assert_eq!(history.check_bounds(1, "30"), None);
+        assert_eq!(history.check_bounds(1, "not a number"), None);
+        assert_eq!(
+            history.check_bounds(1, "20"),
+            Some("20 is not higher than 20, which was too low".to_string())
+        );
+        assert_eq!(
+            history.check_bounds(1, "60"),
+            Some("60 is not lower than 50, which was too high".to_string())
+        );
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = History::default();
+        history.record(1, "10", Verdict::TooHigh).unwrap();
+        history.record(1, "5", Verdict::TooRecent).unwrap();
+        history.record(1, "7", Verdict::Correct).unwrap();
+        assert_eq!(history.find(1, "10").unwrap().verdict, Verdict::TooHigh);
+        assert!(history.find(1, "5").is_none());
+        assert!(history.find(2, "10").is_none());
+        assert_eq!(history.solution(1), Some("7"));
+        assert_eq!(history.solution(2), None);
+    }
+}
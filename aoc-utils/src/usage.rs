@@ -0,0 +1,101 @@
+//! CPU time and peak memory, from `getrusage` where there is one.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::output::{format_bytes, format_duration};
+
+/// The CPU time spent and the most memory resident.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Time running user code.
+    pub user: Duration,
+    /// Time in the kernel on the process's behalf.
+    pub system: Duration,
+    /// The process's peak resident memory, in bytes.
+    pub max_rss: u64,
+}
+
+impl Usage {
+    /// The CPU time of the calling thread so far, or of the whole process
+    /// where threads aren't measured apart, with the process's peak memory.
+    /// `None` without `getrusage`.
+    pub fn current() -> Option<Usage> {
+        current()
+    }
+
+    /// The CPU time spent since `earlier`, with the peak memory as of now.
+    pub fn since(self, earlier: Usage) -> Usage {
+        Usage {
+            user: self.user.saturating_sub(earlier.user),
+            system: self.system.saturating_sub(earlier.system),
+            max_rss: self.max_rss,
+        }
+    }
+}
+
+/// "User 1.20ms, system 300µs, max RSS 5.60MiB".
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "User {}, system {}, max RSS {}",
+            format_duration(self.user),
+            format_duration(self.system),
+            format_bytes(self.max_rss)
+        )
+    }
+}
+
+#[cfg(unix)]
+fn current() -> Option<Usage> {
+    // Only Linux can give a single thread's usage, which keeps days solved in
+    // parallel apart.
+    #[cfg(target_os = "linux")]
+    let who = libc::RUSAGE_THREAD;
+    #[cfg(not(target_os = "linux"))]
+    let who = libc::RUSAGE_SELF;
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    // SAFETY: getrusage only writes to the struct it's given, and all zeroes
+    // is a valid one.
+    if unsafe { libc::getrusage(who, usage.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    let usage = unsafe { usage.assume_init() };
+    let time = |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+    // Apple's systems give bytes, the rest kilobytes.
+    let unit = if cfg!(target_vendor = "apple") { 1 } else { 1024 };
+    Some(Usage { user: time(usage.ru_utime), system: time(usage.ru_stime), max_rss: usage.ru_maxrss as u64 * unit })
+}
+
+#[cfg(not(unix))]
+fn current() -> Option<Usage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_since() {
+        let earlier = Usage { user: Duration::from_millis(5), system: Duration::from_millis(2), max_rss: 1 << 20 };
+        let later = Usage { user: Duration::from_millis(8), system: Duration::from_millis(2), max_rss: 3 << 20 };
+        let usage = later.since(earlier);
+        assert_eq!(usage, Usage { user: Duration::from_millis(3), system: Duration::ZERO, max_rss: 3 << 20 });
+        assert_eq!(usage.to_string(), "User 3.00ms, system 0ns, max RSS 3.00MiB");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_current() {
+        let before = Usage::current().unwrap();
+        let mut sum = 0u64;
+        for i in 0..10_000_000u64 {
+            sum = std::hint::black_box(sum.wrapping_add(i * i));
+        }
+        let usage = Usage::current().unwrap().since(before);
+        assert!(usage.user + usage.system > Duration::ZERO);
+        assert!(usage.max_rss > 0);
+    }
+}
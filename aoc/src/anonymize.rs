@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::source::InputSource;
+
+use crate::solvers;
+
+#[derive(Args)]
+pub struct AnonymizeArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Scramble the same way every time. Defaults to a new seed each run.
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Write the scrambled input here instead of printing it.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// The input to scramble, a file, "-" for stdin, a URL, or
+    /// "clipboard:". Defaults to the stored input.
+    input: Option<InputSource>,
+}
+
+/// Scramble an input so it can be shared, and print its answers so they can
+/// be shared with it.
+pub fn run(args: &AnonymizeArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let input = crate::input::read(config, year, args.day, args.input.as_ref(), false)?;
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    let anonymized = anonymize(year, args.day, &input, seed)?;
+
+    if let Some(solver) = solvers::find(year, args.day) {
+        for part in [1, 2] {
+            match solver.solve(&anonymized, part) {
+                Ok(answer) => eprintln!("Part {part}: {answer}"),
+                Err(error) => eprintln!("Part {part} failed: {error:#}"),
+            }
+        }
+    }
+    match &args.output {
+        Some(path) => fs::write(path, anonymized).map_err(|error| anyhow!("Error writing '{}': {error}", path.display())),
+        None => {
+            print!("{anonymized}");
+            Ok(())
+        }
+    }
+}
+
+/// Scramble the input with the day's anonymizer.
+fn anonymize(year: u16, day: u8, input: &str, seed: u64) -> Result<String> {
+    Ok(match (year, day) {
+        (2023, 1) => day01::anonymize::anonymize(input, seed),
+        (2023, 4) => day04::anonymize::anonymize(input, seed)?,
+        (2023, 5) => day05::anonymize::anonymize(input, seed)?.0,
+        (2023, 6) => day06::anonymize::anonymize(input, seed)?,
+        _ => return Err(anyhow!("There is no anonymizer for {year} day {day}")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anonymize() {
+        let input = "Card 1: 41 48 | 83 41\nCard 2: 13 32 | 61 30\n";
+        let anonymized = anonymize(2023, 4, input, 1).unwrap();
+        assert_ne!(anonymized, input);
+        assert_eq!(anonymized.lines().count(), 2);
+        assert!(anonymize(2023, 2, input, 1).is_err());
+    }
+}
@@ -0,0 +1,469 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use anstyle::{AnsiColor, Style};
+use anyhow::{anyhow, Result};
+use clap::{Args, Subcommand};
+
+use aoc_utils::bench::{self, Change, History, StageTimes};
+use aoc_utils::config::Config;
+use aoc_utils::output::{format_duration, ColorChoice, Output};
+use aoc_utils::solver::DynSolver;
+use aoc_utils::stats::Stats;
+
+use crate::{scaling, solvers};
+
+const REGRESSION: Style = AnsiColor::Red.on_default().bold();
+
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct BenchArgs {
+    #[command(subcommand)]
+    command: Option<BenchCommand>,
+    #[command(flatten)]
+    record: RecordArgs,
+}
+
+#[derive(Subcommand)]
+enum BenchCommand {
+    /// Compare two recorded runs, flagging the stages that got slower.
+    Compare(CompareArgs),
+    /// List the recorded runs.
+    List(HistoryArgs),
+    /// Time each day on generated inputs of growing size, failing if its
+    /// time grows faster than it should.
+    Scaling(ScalingArgs),
+}
+
+/// Where the runs are recorded.
+#[derive(Args)]
+struct HistoryArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    /// The directory of history files, one per year. Defaults to one in the
+    /// local data directory.
+    #[arg(long, value_name = "DIR")]
+    history: Option<PathBuf>,
+}
+
+impl HistoryArgs {
+    fn load(&self, config: &Config) -> Result<(u16, History)> {
+        let year = self.year.unwrap_or_else(|| config.year());
+        let dir = match &self.history {
+            Some(dir) => dir.clone(),
+            None => bench::default_history_dir()?,
+        };
+        Ok((year, History::load(&dir, year)?))
+    }
+}
+
+#[derive(Args)]
+struct RecordArgs {
+    #[command(flatten)]
+    history: HistoryArgs,
+    /// Defaults to every day of the year, one after another.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// How many times to solve each day, after a warmup run.
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// Print the times without recording them.
+    #[arg(long)]
+    no_save: bool,
+    /// Fail if a day's median total time is over its budget, given like
+    /// "day05<=50ms,day06<=1ms". These add to the budgets in aoc.toml.
+    #[arg(long, value_name = "BUDGETS", value_parser = parse_budgets)]
+    assert: Option<Budgets>,
+}
+
+/// The longest each day may take, by day.
+type Budgets = BTreeMap<u8, Duration>;
+
+/// Parse budgets like "day05<=50ms,6<=1ms".
+fn parse_budgets(budgets: &str) -> Result<Budgets, String> {
+    budgets
+        .split(',')
+        .map(|budget| {
+            let (day, duration) = budget
+                .split_once("<=")
+                .ok_or_else(|| format!("Budget '{budget}' must be like 'day05<=50ms'"))?;
+            Ok((parse_day(day)?, crate::run::parse_duration(duration.trim())?))
+        })
+        .collect()
+}
+
+/// Parse a day like "day05" or "5".
+fn parse_day(day: &str) -> Result<u8, String> {
+    let day = day.trim();
+    day.strip_prefix("day")
+        .unwrap_or(day)
+        .parse()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or_else(|| format!("'{day}' isn't a day from 1 to 25"))
+}
+
+/// The budgets in the config, with `extra` added over them.
+fn budgets(config: &Config, extra: Option<&Budgets>) -> Result<Budgets> {
+    let mut budgets = Budgets::new();
+    for (day, budget) in &config.budgets {
+        let day = parse_day(day).map_err(|error| anyhow!("Error in the budgets in aoc.toml: {error}"))?;
+        let budget = crate::run::parse_duration(budget)
+            .map_err(|error| anyhow!("Error in the budget for day {day} in aoc.toml: {error}"))?;
+        budgets.insert(day, budget);
+    }
+    budgets.extend(extra.into_iter().flatten());
+    Ok(budgets)
+}
+
+/// An error for each day whose median total time is over its budget.
+fn check_budgets(budgets: &Budgets, days: &BTreeMap<String, BTreeMap<String, StageTimes>>) -> Vec<String> {
+    budgets
+        .iter()
+        .filter_map(|(&day, &budget)| {
+            let total = days.get(&format!("day{day:02}"))?.get("total")?.median_ms;
+            let total = Duration::from_secs_f64(total / 1000.0);
+            (total > budget).then(|| {
+                format!("Day {day} took {}, over its {} budget", format_duration(total), format_duration(budget))
+            })
+        })
+        .collect()
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    #[command(flatten)]
+    history: HistoryArgs,
+    /// The earlier run: its number from `aoc bench list`, a negative number
+    /// counting back from the latest, or its revision.
+    #[arg(default_value = "-2", allow_hyphen_values = true)]
+    before: String,
+    /// The later run, as for `before`.
+    #[arg(default_value = "-1", allow_hyphen_values = true)]
+    after: String,
+    /// How much slower, in percent, a stage's median time can get before
+    /// it's a regression.
+    #[arg(long, default_value_t = 10.0)]
+    threshold: f64,
+}
+
+#[derive(Args)]
+struct ScalingArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    /// Defaults to every day of the year with an input generator.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// The sizes to generate, as multiples of each day's smallest.
+    #[arg(long, value_delimiter = ',', default_value = "1,10,100",
+          value_parser = clap::value_parser!(u32).range(1..))]
+    sizes: Vec<u32>,
+    /// How many timings to take the median of at each size.
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    /// How far over the expected power of the size the time may grow
+    /// between the two largest sizes.
+    #[arg(long, default_value_t = 0.5)]
+    tolerance: f64,
+}
+
+/// Benchmark the year's days from their stored inputs and record the times,
+/// or compare or list recorded runs.
+pub fn run(args: &BenchArgs, config: &Config, color: ColorChoice) -> Result<()> {
+    match &args.command {
+        None => record(&args.record, config),
+        Some(BenchCommand::Compare(compare)) => self::compare(compare, config, Output::default().color(color)),
+        Some(BenchCommand::List(history)) => list(history, config),
+        Some(BenchCommand::Scaling(scaling)) => self::scaling(scaling, config),
+    }
+}
+
+fn record(args: &RecordArgs, config: &Config) -> Result<()> {
+    let (year, mut history) = args.history.load(config)?;
+    let budgets = budgets(config, args.assert.as_ref())?;
+    let days: Vec<_> = solvers::year(year).filter(|(day, _)| args.day.is_none_or(|only| *day == only)).collect();
+    if days.is_empty() {
+        return Err(anyhow!("There are no solutions for {year} to benchmark"));
+    }
+
+    let mut times = BTreeMap::new();
+    for (day, solver) in days {
+        let input = crate::input::read(config, year, day, None, false)?;
+        let (_, stats) = crate::run::time_runs(solver, &input, &[1, 2], args.runs)?;
+        let stages = stats.into_iter().map(|(stage, stats)| (stage, StageTimes::from(stats))).collect();
+        times.insert(format!("day{day:02}"), stages);
+    }
+    print!("{}", medians(&times));
+    let over = check_budgets(&budgets, &times);
+
+    if !args.no_save {
+        let revision = revision(config);
+        history.record(&revision, args.runs, times)?;
+        history.save()?;
+        println!("Recorded as run {} ({revision})", history.runs.len());
+    }
+
+    if !over.is_empty() {
+        return Err(anyhow!("{} of {} budgets exceeded\n{}", over.len(), budgets.len(), over.join("\n")));
+    }
+    Ok(())
+}
+
+/// The code's `git describe`, or "unknown" outside a repository.
+fn revision(config: &Config) -> String {
+    Command::new("git")
+        .args(["describe", "--always", "--dirty"])
+        .current_dir(config.root())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map_or("unknown".to_string(), |output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A row for each day with the median time of each stage.
+fn medians(days: &BTreeMap<String, BTreeMap<String, StageTimes>>) -> String {
+    let median = |stages: &BTreeMap<String, StageTimes>, stage| {
+        stages.get(stage).map_or("-".to_string(), |times: &StageTimes| format_ms(times.median_ms))
+    };
+    let rows = days
+        .iter()
+        .map(|(day, stages)| {
+            let [parse, part1, part2, total] = ["parse", "part 1", "part 2", "total"].map(|stage| median(stages, stage));
+            vec![day.clone(), parse, part1, part2, total]
+        })
+        .collect();
+    table(vec!["Day", "Parse", "Part 1", "Part 2", "Total"], rows)
+}
+
+fn compare(args: &CompareArgs, config: &Config, output: Output) -> Result<()> {
+    let (_, history) = args.history.load(config)?;
+    let before = history.find(&args.before)?;
+    let after = history.find(&args.after)?;
+    println!("Median times, {} to {}:", before.revision, after.revision);
+    let changes = bench::compare(before, after);
+    let threshold = args.threshold / 100.0;
+    print!("{}", changes_table(&changes, threshold, output.stdout_color()));
+
+    let regressions = changes.iter().filter(|change| change.slowdown() > threshold).count();
+    if regressions > 0 {
+        return Err(anyhow!("{regressions} of {} stages got more than {}% slower", changes.len(), args.threshold));
+    }
+    Ok(())
+}
+
+/// A row for each stage with its times and how much they changed, with the
+/// regressions marked, and colored if `color`.
+fn changes_table(changes: &[Change], threshold: f64, color: bool) -> String {
+    let rows: Vec<Vec<String>> = changes
+        .iter()
+        .map(|change| {
+            let slowdown = change.slowdown();
+            let mut row = vec![
+                change.day.clone(),
+                change.stage.clone(),
+                format_ms(change.before_ms),
+                format_ms(change.after_ms),
+                format!("{:+.1}%", slowdown * 100.0),
+            ];
+            if slowdown > threshold {
+                row.push("slower".to_string());
+            }
+            row
+        })
+        .collect();
+    let table = table(vec!["Day", "Stage", "Before", "After", "Change"], rows);
+    if !color {
+        return table;
+    }
+    table
+        .lines()
+        .map(|line| match line.strip_suffix("slower") {
+            Some(_) => format!("{REGRESSION}{line}{REGRESSION:#}\n"),
+            None => format!("{line}\n"),
+        })
+        .collect()
+}
+
+fn scaling(args: &ScalingArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let mut sizes: Vec<usize> = args.sizes.iter().map(|&size| size as usize).collect();
+    sizes.sort();
+    sizes.dedup();
+    if sizes.len() < 2 {
+        return Err(anyhow!("Scaling needs at least 2 different sizes"));
+    }
+    let days: Vec<_> = solvers::year(year)
+        .filter(|(day, _)| args.day.is_none_or(|only| *day == only))
+        .filter_map(|(day, solver)| Some((day, solver, scaling::find(year, day)?)))
+        .collect();
+    if days.is_empty() {
+        return Err(match args.day {
+            Some(day) => anyhow!("There is no input generator for {year} day {day}"),
+            None => anyhow!("There are no input generators for {year}"),
+        });
+    }
+
+    let mut rows = Vec::new();
+    let mut worse = Vec::new();
+    let [smaller, larger] = [sizes[sizes.len() - 2], sizes[sizes.len() - 1]];
+    for (day, solver, scaling) in days {
+        let mut times = Vec::new();
+        for &size in &sizes {
+            let input = (scaling.input)(scaling.base * size);
+            times.push(solve_time(solver, &input, args.runs)?);
+        }
+        let seconds = |size| times[sizes.iter().position(|&other| other == size).unwrap()].as_secs_f64();
+        let growth = scaling::growth((smaller, seconds(smaller)), (larger, seconds(larger)));
+        let expected = scaling.complexity.exponent(scaling.base * smaller, scaling.base * larger);
+
+        let mut row = vec![format!("day{day:02}"), format!("{} {}", scaling.base, scaling.unit)];
+        row.push(scaling.complexity.to_string());
+        row.extend(times.iter().map(|&time| format_duration(time)));
+        row.push(format!("n^{growth:.2}"));
+        if growth > expected + args.tolerance {
+            row.push("worse".to_string());
+            worse.push(format!(
+                "Day {day} grew as n^{growth:.2} from {smaller}x to {larger}x, worse than {}",
+                scaling.complexity
+            ));
+        }
+        rows.push(row);
+    }
+    let mut header = vec!["Day".to_string(), "Base".to_string(), "Expected".to_string()];
+    header.extend(sizes.iter().map(|size| format!("{size}x")));
+    header.push("Growth".to_string());
+    let days = rows.len();
+    print!("{}", table(header.iter().map(String::as_str).collect(), rows));
+
+    if !worse.is_empty() {
+        return Err(anyhow!("{} of {} days scale worse than expected\n{}", worse.len(), days, worse.join("\n")));
+    }
+    Ok(())
+}
+
+/// The median time of a solve of both parts after a warmup. Solves are timed
+/// in batches of at least a millisecond, so quick ones aren't lost in the
+/// clock's noise.
+fn solve_time(solver: &dyn DynSolver, input: &str, runs: u32) -> Result<Duration> {
+    let start = Instant::now();
+    let mut batch = 0;
+    while batch == 0 || start.elapsed() < Duration::from_millis(1) {
+        solver.solve_timed(input, &[1, 2])?;
+        batch += 1;
+    }
+    let mut times = Vec::new();
+    for _ in 0..runs {
+        let start = Instant::now();
+        for _ in 0..batch {
+            solver.solve_timed(input, &[1, 2])?;
+        }
+        times.push(start.elapsed() / batch);
+    }
+    Ok(Stats::new(&times).unwrap().median)
+}
+
+fn list(args: &HistoryArgs, config: &Config) -> Result<()> {
+    let (year, history) = args.load(config)?;
+    if history.runs.is_empty() {
+        println!("No benchmark runs recorded for {year}");
+        return Ok(());
+    }
+    let rows = history
+        .runs
+        .iter()
+        .enumerate()
+        .map(|(i, run)| {
+            let days = run.days.keys().map(String::as_str).collect::<Vec<_>>().join(" ");
+            vec![(i + 1).to_string(), run.revision.clone(), run.time.to_string(), run.runs.to_string(), days]
+        })
+        .collect();
+    print!("{}", table(vec!["Run", "Revision", "Time", "Runs", "Days"], rows));
+    Ok(())
+}
+
+fn format_ms(ms: f64) -> String {
+    format_duration(Duration::from_secs_f64(ms / 1000.0))
+}
+
+/// Left-aligned columns separated by two spaces.
+pub fn table(header: Vec<&str>, rows: Vec<Vec<String>>) -> String {
+    let header: Vec<String> = header.into_iter().map(String::from).collect();
+    let mut widths: Vec<usize> = header.iter().map(String::len).collect();
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            match widths.get_mut(i) {
+                Some(width) => *width = (*width).max(cell.chars().count()),
+                None => widths.push(cell.chars().count()),
+            }
+        }
+    }
+    let mut table = String::new();
+    for row in std::iter::once(header).chain(rows) {
+        let line: Vec<String> = row.iter().zip(&widths).map(|(cell, width)| format!("{cell:<width$}")).collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_table() {
+        let change = |stage: &str, before_ms, after_ms| Change {
+            day: "day05".to_string(),
+            stage: stage.to_string(),
+            before_ms,
+            after_ms,
+        };
+        let changes = [change("parse", 1.0, 1.05), change("part 2", 2.0, 3.0)];
+        assert_eq!(
+            changes_table(&changes, 0.1, false),
+            "Day    Stage   Before  After   Change\n\
+             day05  parse   1.00ms  1.05ms  +5.0%\n\
+             day05  part 2  2.00ms  3.00ms  +50.0%  slower\n"
+        );
+        assert!(changes_table(&changes, 0.1, true).ends_with("slower\u{1b}[0m\n"));
+    }
+
+    #[test]
+    fn test_budgets() {
+        let budgets = parse_budgets("day05<=50ms, 6 <= 1ms").unwrap();
+        assert_eq!(budgets, BTreeMap::from([(5, Duration::from_millis(50)), (6, Duration::from_millis(1))]));
+        assert!(parse_budgets("day05=50ms").is_err());
+        assert!(parse_budgets("day26<=1s").is_err());
+        assert!(parse_budgets("day05<=soon").is_err());
+
+        let mut config = Config::default();
+        config.budgets.insert("day05".to_string(), "1s".to_string());
+        config.budgets.insert("day06".to_string(), "2ms".to_string());
+        let budgets = self::budgets(&config, Some(&parse_budgets("day06<=1ms").unwrap())).unwrap();
+        assert_eq!(budgets[&5], Duration::from_secs(1));
+        assert_eq!(budgets[&6], Duration::from_millis(1));
+        config.budgets.insert("tomorrow".to_string(), "1s".to_string());
+        assert!(self::budgets(&config, None).is_err());
+
+        let total = |median_ms| StageTimes { min_ms: 0.0, mean_ms: 0.0, median_ms, stddev_ms: 0.0 };
+        let days = BTreeMap::from([
+            ("day05".to_string(), BTreeMap::from([("total".to_string(), total(900.0))])),
+            ("day06".to_string(), BTreeMap::from([("total".to_string(), total(1.5))])),
+        ]);
+        assert_eq!(check_budgets(&budgets, &days), ["Day 6 took 1.50ms, over its 1.00ms budget"]);
+    }
+
+    #[test]
+    fn test_medians() {
+        let times = |median_ms| StageTimes { min_ms: 0.0, mean_ms: 0.0, median_ms, stddev_ms: 0.0 };
+        let days = BTreeMap::from([(
+            "day06".to_string(),
+            BTreeMap::from([("parse".to_string(), times(0.002)), ("total".to_string(), times(0.5))]),
+        )]);
+        assert_eq!(medians(&days), "Day    Parse   Part 1  Part 2  Total\nday06  2.00µs  -       -       500µs\n");
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use serde::Deserialize;
+
+use aoc_utils::config::Config;
+use aoc_utils::output::format_duration;
+use aoc_utils::stats::Stats;
+
+#[derive(Args)]
+pub struct BuildArgs {
+    /// Build with profile-guided optimization: build an instrumented binary,
+    /// profile it solving the year's stored inputs, and build again using the
+    /// profiles, comparing the times before and after.
+    #[arg(long)]
+    pgo: bool,
+    /// The year to profile and time. Defaults to the configured year.
+    #[arg(short, long, requires = "pgo")]
+    year: Option<u16>,
+    /// How many times to solve the year when profiling, and when timing each
+    /// build.
+    #[arg(long, default_value_t = 5, requires = "pgo", value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+}
+
+/// The part of an `aoc run --format json` record that's timed.
+#[derive(Deserialize)]
+struct Timed {
+    day: u8,
+    parse_ms: f64,
+    solve_ms: f64,
+}
+
+/// Build a release `aoc`, optionally with profile-guided optimization. The
+/// builds go in `target/pgo`, one directory each, so they don't rebuild each
+/// other.
+pub fn run(args: &BuildArgs, config: &Config) -> Result<()> {
+    let root = config.root();
+    let dir = root.join("target").join("pgo");
+    let plain = cargo_build(root, &dir.join("plain"), None)?;
+    if !args.pgo {
+        println!("Built {}", plain.display());
+        return Ok(());
+    }
+
+    let year = args.year.unwrap_or_else(|| config.year());
+    let profiles = dir.join("profiles");
+    remove_dir(&profiles)?;
+    let instrumented = cargo_build(root, &dir.join("instrumented"), Some(flag("-Cprofile-generate=", &profiles)))?;
+    eprintln!("Profiling {year}");
+    for _ in 0..args.runs {
+        solve_year(root, &instrumented, year)?;
+    }
+    let merged = dir.join("merged.profdata");
+    let status = Command::new(llvm_profdata())
+        .arg("merge")
+        .arg("-o")
+        .arg(&merged)
+        .arg(&profiles)
+        .status()
+        .context("Error running llvm-profdata, which `rustup component add llvm-tools` installs")?;
+    if !status.success() {
+        return Err(anyhow!("llvm-profdata failed to merge the profiles: {status}"));
+    }
+    // Cargo doesn't notice the profiles changing, so build from scratch.
+    remove_dir(&dir.join("optimized"))?;
+    let optimized = cargo_build(root, &dir.join("optimized"), Some(flag("-Cprofile-use=", &merged)))?;
+
+    eprintln!("Timing both builds");
+    let before = time_year(root, &plain, year, args.runs)?;
+    let after = time_year(root, &optimized, year, args.runs)?;
+    print!("{}", comparison(&before, &after));
+    println!("Built {}", optimized.display());
+    Ok(())
+}
+
+/// Remove a directory and everything in it, if it's there.
+fn remove_dir(dir: &Path) -> Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(error) if error.kind() != ErrorKind::NotFound => {
+            Err(error).with_context(|| format!("Error removing '{}'", dir.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A `rustc` flag followed by a path.
+fn flag(flag: &str, path: &Path) -> OsString {
+    let mut flag = OsString::from(flag);
+    flag.push(path);
+    flag
+}
+
+/// Build `aoc` in release mode into `target_dir` with an extra `rustc` flag,
+/// returning the binary's path.
+fn cargo_build(root: &Path, target_dir: &Path, rustflag: Option<OsString>) -> Result<PathBuf> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command.args(["build", "--release", "-p", "aoc", "--target-dir"]).arg(target_dir).current_dir(root);
+    if let Some(rustflag) = rustflag {
+        // Keep any flags already set, such as a target CPU.
+        let mut rustflags = std::env::var_os("RUSTFLAGS").unwrap_or_default();
+        if !rustflags.is_empty() {
+            rustflags.push(" ");
+        }
+        rustflags.push(rustflag);
+        command.env("RUSTFLAGS", rustflags);
+    }
+    eprintln!("Building into {}", target_dir.display());
+    let status = command.status().context("Error running cargo")?;
+    if !status.success() {
+        return Err(anyhow!("Building into '{}' failed: {status}", target_dir.display()));
+    }
+    Ok(target_dir.join("release").join(format!("aoc{}", std::env::consts::EXE_SUFFIX)))
+}
+
+/// The `llvm-profdata` matching the toolchain's LLVM, from the `llvm-tools`
+/// component, or else whichever is on the path.
+fn llvm_profdata() -> PathBuf {
+    let sysroot = Command::new("rustc")
+        .args(["--print", "sysroot"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()));
+    let bundled = sysroot.and_then(|sysroot| {
+        let tools = fs::read_dir(sysroot.join("lib").join("rustlib")).ok()?;
+        tools
+            .filter_map(|entry| Some(entry.ok()?.path().join("bin").join("llvm-profdata")))
+            .find(|path| path.with_extension(std::env::consts::EXE_EXTENSION).is_file())
+    });
+    bundled.unwrap_or_else(|| "llvm-profdata".into())
+}
+
+/// Solve every day of the year with a build of `aoc`, never from cached
+/// answers, returning what it printed with `--format json`.
+fn solve_year(root: &Path, aoc: &Path, year: u16) -> Result<String> {
+    let output = Command::new(aoc)
+        .args(["run", "--year", &year.to_string(), "--format", "json", "--no-cache"])
+        .current_dir(root)
+        .output()
+        .with_context(|| format!("Error running '{}'", aoc.display()))?;
+    if !output.status.success() {
+        return Err(anyhow!("Solving {year} failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// The median time to parse and solve each day over `runs` runs of the
+/// year.
+fn time_year(root: &Path, aoc: &Path, year: u16, runs: u32) -> Result<BTreeMap<u8, Duration>> {
+    let mut times: BTreeMap<u8, Vec<Duration>> = BTreeMap::new();
+    for _ in 0..runs {
+        for (day, time) in day_times(&solve_year(root, aoc, year)?)? {
+            times.entry(day).or_default().push(time);
+        }
+    }
+    Ok(times.into_iter().map(|(day, times)| (day, Stats::new(&times).unwrap().median)).collect())
+}
+
+/// Each day's parse time plus its parts' solve times, from the records of a
+/// run.
+fn day_times(records: &str) -> Result<BTreeMap<u8, Duration>> {
+    let mut days = BTreeMap::new();
+    for line in records.lines() {
+        let record: Timed = serde_json::from_str(line).with_context(|| format!("Error reading the record '{line}'"))?;
+        let parse = Duration::from_secs_f64(record.parse_ms / 1000.0);
+        *days.entry(record.day).or_insert(parse) += Duration::from_secs_f64(record.solve_ms / 1000.0);
+    }
+    Ok(days)
+}
+
+/// A row for each day with its times before and after, and the year's
+/// total.
+fn comparison(before: &BTreeMap<u8, Duration>, after: &BTreeMap<u8, Duration>) -> String {
+    let row = |name: String, before: Duration, after: Duration| {
+        let change = (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+        vec![name, format_duration(before), format_duration(after), format!("{change:+.1}%")]
+    };
+    let mut rows: Vec<Vec<String>> = before
+        .iter()
+        .filter_map(|(&day, &time)| Some(row(format!("day{day:02}"), time, *after.get(&day)?)))
+        .collect();
+    rows.push(row("total".to_string(), before.values().sum(), after.values().sum()));
+    crate::bench::table(vec!["Day", "Before", "After", "Change"], rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_times() {
+        let records = r#"{"year":2023,"day":6,"part":1,"answer":288,"read_ms":0.5,"parse_ms":1.0,"solve_ms":2.0}
+{"year":2023,"day":6,"part":2,"answer":71503,"read_ms":0.5,"parse_ms":1.0,"solve_ms":3.0}
+"#;
+        let days = day_times(records).unwrap();
+        assert_eq!(days, BTreeMap::from([(6, Duration::from_millis(6))]));
+        let after = BTreeMap::from([(6, Duration::from_millis(3))]);
+        assert_eq!(
+            comparison(&days, &after),
+            "Day    Before  After   Change\n\
+             day06  6.00ms  3.00ms  -50.0%\n\
+             total  6.00ms  3.00ms  -50.0%\n"
+        );
+    }
+}
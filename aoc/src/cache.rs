@@ -0,0 +1,35 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use aoc_utils::cache::{self, AnswerCache};
+
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Remove the cached answers, so every day is solved again.
+    Clear(ClearArgs),
+}
+
+#[derive(Args)]
+struct ClearArgs {
+    /// Remove only this year's answers. Defaults to every year's.
+    #[arg(short, long)]
+    year: Option<u16>,
+}
+
+/// Manage the answers `aoc run` and `aoc verify` cache for each input.
+pub fn run(args: &CacheArgs) -> Result<()> {
+    match &args.command {
+        CacheCommand::Clear(clear) => {
+            // Any build's answers are cleared, so the stamp doesn't matter.
+            let removed = AnswerCache::new(&cache::default_dir()?, "").clear(clear.year)?;
+            println!("Removed {removed} cached answer{}", if removed == 1 { "" } else { "s" });
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::puzzle::{self, Example};
+
+#[derive(Args)]
+pub struct ExamplesArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Only generate the test for this part.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// The crate to write the tests to. Defaults to `<year>/day<day>` in the
+    /// repository.
+    #[arg(long)]
+    dir: Option<PathBuf>,
+    /// Overwrite existing example tests.
+    #[arg(long)]
+    force: bool,
+    /// Download the puzzle again, e.g. after part 1 is solved.
+    #[arg(long)]
+    refresh: bool,
+}
+
+pub fn run(args: &ExamplesArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let dir = args
+        .dir
+        .clone()
+        .unwrap_or_else(|| config.root().join(format!("{year}/day{:02}", args.day)));
+    let test_path = dir.join("tests").join("example.rs");
+    if test_path.exists() && !args.force {
+        return Err(anyhow!("'{}' already exists; use --force to overwrite it", test_path.display()));
+    }
+    let krate = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| anyhow!("Unable to find the crate name from '{}'", dir.display()))?;
+
+    let client = crate::client(config)?;
+    let page_path = format!("/{year}/day/{}", args.day);
+    let page = if args.refresh {
+        client.get_fresh(&page_path)?
+    } else {
+        client.get(&page_path)?
+    };
+    let examples: Vec<Example> = puzzle::examples(&page)
+        .into_iter()
+        .filter(|example| args.part.is_none_or(|part| part == example.part))
+        .collect();
+    if examples.is_empty() {
+        return Err(anyhow!("No examples found for {year} day {}", args.day));
+    }
+
+    let fixtures_dir = dir.join("fixtures");
+    fs::create_dir_all(&fixtures_dir)?;
+    for example in &examples {
+        let path = fixtures_dir.join(fixture_name(example));
+        fs::write(&path, &example.input).with_context(|| format!("Error writing '{}'", path.display()))?;
+        println!("Wrote {}", path.display());
+    }
+
+    fs::create_dir_all(test_path.parent().unwrap())?;
+    fs::write(&test_path, generate_tests(krate, &examples))
+        .with_context(|| format!("Error writing '{}'", test_path.display()))?;
+    println!("Wrote {}", test_path.display());
+
+    Ok(())
+}
+
+fn fixture_name(example: &Example) -> String {
+    format!("example-part{}.txt", example.part)
+}
+
+/// Generate tests that solve each example with the crate's `Solver`, named
+/// after the crate in camel case like `Day05`, with `aoc_example_tests!`.
+fn generate_tests(krate: &str, examples: &[Example]) -> String {
+    let krate = krate.replace('-', "_");
+    let solver: String = krate.split('_').map(capitalize).collect();
+    let mut source = format!(
+        "//! Example tests generated by `aoc examples` from the puzzle description.\n\nuse {krate}::{solver};\n\n"
+    );
+    for example in examples {
+        let part = example.part;
+        let fixture = fixture_name(example);
+        match &example.answer {
+            Some(answer) => writeln!(
+                source,
+                "aoc_utils::aoc_example_tests!({solver}, part{part} = {}, input = \"fixtures/{fixture}\");",
+                answer_literal(answer)
+            )
+            .unwrap(),
+            None => writeln!(
+                source,
+                "// Part {part}'s answer wasn't found in the puzzle; its example is fixtures/{fixture}."
+            )
+            .unwrap(),
+        }
+    }
+    source
+}
+
+/// An answer as a Rust literal: a number as it is, and anything else quoted.
+fn answer_literal(answer: &str) -> String {
+    if answer.parse::<u64>().is_ok() || answer.parse::<i64>().is_ok() {
+        answer.to_string()
+    } else {
+        format!("{answer:?}")
+    }
+}
+
+fn capitalize(name: &str) -> String {
+    let mut chars = name.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_tests() {
+        let examples = vec![
+            Example { part: 1, input: "1\n".to_string(), answer: Some("35".to_string()) },
+            Example { part: 2, input: "1\n".to_string(), answer: None },
+        ];
+        assert_eq!(generate_tests("day05", &examples), "\
+//! Example tests generated by `aoc examples` from the puzzle description.
+
+use day05::Day05;
+
+aoc_utils::aoc_example_tests!(Day05, part1 = 35, input = \"fixtures/example-part1.txt\");
+// Part 2's answer wasn't found in the puzzle; its example is fixtures/example-part2.txt.
+");
+
+        let examples = vec![Example { part: 2, input: "1\n".to_string(), answer: Some("AB\"C".to_string()) }];
+        assert!(generate_tests("my-day", &examples)
+            .contains("use my_day::MyDay;\n\naoc_utils::aoc_example_tests!(MyDay, part2 = \"AB\\\"C\","));
+        assert_eq!(answer_literal("-7"), "-7");
+    }
+}
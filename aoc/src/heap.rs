@@ -0,0 +1,46 @@
+use std::fmt;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use aoc_utils::output::format_bytes;
+use aoc_utils::solver::{DynSolver, Solved};
+
+/// What a solve allocated on the heap.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct HeapUsage {
+    /// How many allocations were made.
+    pub allocations: u64,
+    /// The bytes allocated, summed over every allocation.
+    pub allocated_bytes: u64,
+    /// The most bytes live at once.
+    pub peak_bytes: u64,
+}
+
+/// "3210 allocations of 1.23MiB in total, 456KiB at peak".
+impl fmt::Display for HeapUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocations of {} in total, {} at peak",
+            self.allocations,
+            format_bytes(self.allocated_bytes),
+            format_bytes(self.peak_bytes)
+        )
+    }
+}
+
+/// Solve with dhat counting the heap, which needs its allocator to be the
+/// global one. The input was allocated before, so isn't counted.
+pub fn profile(solver: &dyn DynSolver, input: &str, parts: &[u8]) -> Result<(Vec<Solved>, HeapUsage)> {
+    // Testing mode keeps dhat from writing `dhat-heap.json` when dropped.
+    let _profiler = dhat::Profiler::builder().testing().build();
+    let solved = solver.solve_timed(input, parts)?;
+    let stats = dhat::HeapStats::get();
+    let usage = HeapUsage {
+        allocations: stats.total_blocks,
+        allocated_bytes: stats.total_bytes,
+        peak_bytes: stats.max_bytes as u64,
+    };
+    Ok((solved, usage))
+}
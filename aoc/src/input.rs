@@ -0,0 +1,52 @@
+use anyhow::Result;
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::exit::MissingInput;
+use aoc_utils::inputs;
+use aoc_utils::source::InputSource;
+
+#[derive(Args)]
+pub struct InputArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Store the input encrypted, even if not configured to.
+    #[arg(long)]
+    encrypt: bool,
+    /// Download the input again even if it's already stored.
+    #[arg(long)]
+    refresh: bool,
+}
+
+/// Print the input for a puzzle, downloading it first if needed.
+pub fn run(args: &InputArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    if !args.refresh {
+        if let Some(input) = inputs::read(config, year, args.day)? {
+            print!("{input}");
+            return Ok(());
+        }
+    }
+
+    let mut config = config.clone();
+    if args.encrypt {
+        config.encrypt_inputs = Some(true);
+    }
+    let client = crate::client(&config)?;
+    let input = inputs::fetch(&client, &config, year, args.day)?;
+    print!("{input}");
+    Ok(())
+}
+
+/// Read a puzzle input from `source`, or from the stored input if there is no
+/// source. A gzip or zstd compressed input is decompressed. Bytes that aren't
+/// UTF-8 are replaced, or with `strict_utf8`, are an error.
+pub fn read(config: &Config, year: u16, day: u8, source: Option<&InputSource>, strict_utf8: bool) -> Result<String> {
+    match source {
+        Some(source) => source.read_to_string(strict_utf8),
+        None => inputs::read(config, year, day)?.ok_or_else(|| MissingInput::Stored { year, day }.into()),
+    }
+}
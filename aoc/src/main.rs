@@ -0,0 +1,127 @@
+use std::num::NonZeroUsize;
+use std::process::ExitCode;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use aoc_utils::config::Config;
+use aoc_utils::http::{self, Client};
+use aoc_utils::output::{ColorChoice, Output};
+
+mod anonymize;
+mod bench;
+mod build;
+mod cache;
+mod examples;
+#[cfg(feature = "dhat-heap")]
+mod heap;
+mod input;
+mod minimize;
+mod playground;
+#[cfg(feature = "profiling")]
+mod profile;
+mod puzzle;
+mod run;
+mod scaling;
+mod solvers;
+mod submit;
+mod tui;
+mod validate;
+mod verify;
+
+#[cfg(feature = "dhat-heap")]
+#[global_allocator]
+static ALLOC: dhat::Alloc = dhat::Alloc;
+
+// Counting the heap needs dhat's allocator, so it wins over mimalloc.
+#[cfg(all(feature = "fast-alloc", not(feature = "dhat-heap")))]
+#[global_allocator]
+static ALLOC: aoc_utils::alloc::MiMalloc = aoc_utils::alloc::MiMalloc;
+
+/// Advent of Code tooling.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// When to color the output.
+    #[arg(long, global = true, value_enum, default_value_t, value_name = "WHEN")]
+    color: ColorChoice,
+    /// How many threads to solve with, both for days solved in parallel and
+    /// within a day's solver. Defaults to `threads` in aoc.toml or
+    /// AOC_THREADS, or else one per CPU.
+    #[arg(long, global = true, value_name = "N")]
+    threads: Option<NonZeroUsize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Scramble an input so it can be shared, keeping what the answers
+    /// depend on.
+    Anonymize(anonymize::AnonymizeArgs),
+    /// Benchmark every day and record the times, or compare recorded runs.
+    Bench(bench::BenchArgs),
+    /// Build a release binary, optionally with profile-guided optimization.
+    Build(build::BuildArgs),
+    /// Clear the answers cached for each input.
+    Cache(cache::CacheArgs),
+    /// Write the puzzle's examples as fixtures and generate tests for them.
+    Examples(examples::ExamplesArgs),
+    /// Print the puzzle input, downloading it if needed.
+    Input(input::InputArgs),
+    /// Shrink an input while solving it still misbehaves, for a small
+    /// reproducer.
+    Minimize(minimize::MinimizeArgs),
+    /// Build the in-browser playground, and optionally serve it.
+    Playground(playground::PlaygroundArgs),
+    /// Write a flamegraph of a solve. Needs the `profiling` feature.
+    #[cfg(feature = "profiling")]
+    Profile(profile::ProfileArgs),
+    /// Print the puzzle description as Markdown.
+    Puzzle(puzzle::PuzzleArgs),
+    /// Solve a puzzle with its day's solver.
+    Run(run::RunArgs),
+    /// Submit an answer for a puzzle part.
+    Submit(submit::SubmitArgs),
+    /// Show a live table of the year's days as they are solved.
+    Tui(tui::TuiArgs),
+    /// Check a puzzle input, reporting every error in it rather than the first.
+    Validate(validate::ValidateArgs),
+    /// Check every day's answers for its stored input against the expected
+    /// ones.
+    Verify(verify::VerifyArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    Output::default().color(cli.color).exit(run(cli))
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let config = Config::load()?;
+    if let Some(threads) = cli.threads.or(config.threads) {
+        rayon::ThreadPoolBuilder::new().num_threads(threads.get()).build_global()?;
+    }
+    match cli.command {
+        Command::Anonymize(args) => anonymize::run(&args, &config),
+        Command::Bench(args) => bench::run(&args, &config, cli.color),
+        Command::Build(args) => build::run(&args, &config),
+        Command::Cache(args) => cache::run(&args),
+        Command::Examples(args) => examples::run(&args, &config),
+        Command::Input(args) => input::run(&args, &config),
+        Command::Minimize(args) => minimize::run(&args, &config),
+        Command::Playground(args) => playground::run(&args, &config),
+        #[cfg(feature = "profiling")]
+        Command::Profile(args) => profile::run(&args, &config),
+        Command::Puzzle(args) => puzzle::run(&args, &config),
+        Command::Run(args) => run::run(&args, &config, cli.color),
+        Command::Submit(args) => submit::run(&args, &config),
+        Command::Tui(args) => tui::run(&args, &config),
+        Command::Validate(args) => validate::run(&args, &config),
+        Command::Verify(args) => verify::run(&args, &config),
+    }
+}
+
+/// Create a client using the configured session token.
+fn client(config: &Config) -> Result<Client> {
+    Client::new(&config.session()?, &http::default_cache_dir()?)
+}
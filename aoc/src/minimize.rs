@@ -0,0 +1,212 @@
+use std::fmt;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::minimize::{self, Cut};
+use aoc_utils::solver::DynSolver;
+use aoc_utils::source::InputSource;
+
+use crate::solvers;
+
+#[derive(Args)]
+pub struct MinimizeArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Only solve this part. Defaults to both, and the input is kept while
+    /// either part misbehaves.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// The misbehavior to keep: "panics", "fails" for any error or panic,
+    /// "error:TEXT" for an error mentioning TEXT, or "answer:VALUE".
+    #[arg(long, default_value = "fails")]
+    predicate: Predicate,
+    /// Write the minimized input here instead of printing it.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// The input to minimize, a file, "-" for stdin, a URL, or
+    /// "clipboard:". Defaults to the stored input.
+    input: Option<InputSource>,
+}
+
+/// What a solve has to do for an input to be kept.
+#[derive(Clone, Debug, PartialEq)]
+enum Predicate {
+    Panics,
+    Fails,
+    Error(String),
+    Answer(String),
+}
+
+impl FromStr for Predicate {
+    type Err = String;
+
+    fn from_str(predicate: &str) -> Result<Predicate, String> {
+        match predicate.split_once(':') {
+            _ if predicate == "panics" => Ok(Predicate::Panics),
+            _ if predicate == "fails" => Ok(Predicate::Fails),
+            Some(("error", text)) => Ok(Predicate::Error(text.to_string())),
+            Some(("answer", value)) => Ok(Predicate::Answer(value.to_string())),
+            _ => Err(format!("'{predicate}' isn't one of panics, fails, error:TEXT, or answer:VALUE")),
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Predicate::Panics => write!(f, "panics"),
+            Predicate::Fails => write!(f, "fails"),
+            Predicate::Error(text) => write!(f, "fails with '{text}'"),
+            Predicate::Answer(value) => write!(f, "gives {value}"),
+        }
+    }
+}
+
+/// How solving a part went, with panics caught.
+enum Outcome {
+    Panicked,
+    Failed(String),
+    Solved(String),
+}
+
+impl Predicate {
+    fn holds(&self, outcome: &Outcome) -> bool {
+        match (self, outcome) {
+            (Predicate::Panics | Predicate::Fails, Outcome::Panicked) => true,
+            (Predicate::Fails, Outcome::Failed(_)) => true,
+            (Predicate::Error(text), Outcome::Failed(message)) => message.contains(text.as_str()),
+            (Predicate::Answer(value), Outcome::Solved(answer)) => answer == value,
+            _ => false,
+        }
+    }
+}
+
+/// The ways each day's input can be cut down without losing its shape.
+fn cuts(year: u16, day: u8) -> &'static [Cut] {
+    match (year, day) {
+        (2023, 4) => &[Cut::Lines, Cut::Words(1)],
+        (2023, 5) => &[Cut::Sections, Cut::Lines, Cut::Words(2)],
+        (2023, 6) => &[Cut::Columns],
+        _ => &[Cut::Lines],
+    }
+}
+
+/// Shrink an input while solving it still misbehaves the same way, and print
+/// or write the result.
+pub fn run(args: &MinimizeArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let solver = solvers::find(year, args.day).ok_or_else(|| anyhow!("There is no solution for {year} day {}", args.day))?;
+    let input = crate::input::read(config, year, args.day, args.input.as_ref(), false)?;
+    let parts = args.part.map_or(vec![1, 2], |part| vec![part]);
+
+    let minimized = minimize(solver, &input, cuts(year, args.day), &parts, &args.predicate)?;
+    eprintln!("Minimized {} lines to {}", input.lines().count(), minimized.lines().count());
+    match &args.output {
+        Some(path) => fs::write(path, minimized).map_err(|error| anyhow!("Error writing '{}': {error}", path.display())),
+        None => {
+            print!("{minimized}");
+            Ok(())
+        }
+    }
+}
+
+fn minimize(solver: &dyn DynSolver, input: &str, cuts: &[Cut], parts: &[u8], predicate: &Predicate) -> Result<String> {
+    // Keep the expected panics from printing while the input shrinks.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut interesting =
+        |text: &str| parts.iter().any(|&part| predicate.holds(&outcome(solver, text, part)));
+    let result = if interesting(&minimize::normalize(input)) {
+        Ok(minimize::minimize(input, cuts, &mut interesting))
+    } else {
+        Err(anyhow!("The input doesn't satisfy the predicate: solving it never {predicate}"))
+    };
+    panic::set_hook(hook);
+    result
+}
+
+fn outcome(solver: &dyn DynSolver, input: &str, part: u8) -> Outcome {
+    match panic::catch_unwind(AssertUnwindSafe(|| solver.solve(input, part))) {
+        Err(_) => Outcome::Panicked,
+        Ok(Err(error)) => Outcome::Failed(format!("{error:#}")),
+        Ok(Ok(answer)) => Outcome::Solved(answer.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::cancel::Cancel;
+    use aoc_utils::error::Result;
+    use aoc_utils::solver::{Answer, Solver};
+
+    use super::*;
+
+    /// Panics on any line containing "boom", and sums the numbers otherwise.
+    struct Fragile;
+
+    impl Solver for Fragile {
+        type Parsed = Vec<u64>;
+
+        fn parse(&self, input: &str) -> Result<Vec<u64>> {
+            assert!(!input.contains("boom"), "boom");
+            input.lines().map(|line| Ok(line.parse()?)).collect()
+        }
+
+        fn part1(&self, numbers: &Vec<u64>, _: &Cancel) -> Result<Answer> {
+            Ok(numbers.iter().sum::<u64>().into())
+        }
+
+        fn part2(&self, numbers: &Vec<u64>, cancel: &Cancel) -> Result<Answer> {
+            self.part1(numbers, cancel)
+        }
+    }
+
+    #[test]
+    fn test_predicate() {
+        assert_eq!("panics".parse(), Ok(Predicate::Panics));
+        assert_eq!("error:too big".parse(), Ok(Predicate::Error("too big".to_string())));
+        assert_eq!("answer:42".parse(), Ok(Predicate::Answer("42".to_string())));
+        assert!("sometimes".parse::<Predicate>().is_err());
+        assert_eq!(Predicate::Error("x".to_string()).to_string(), "fails with 'x'");
+    }
+
+    #[test]
+    fn test_minimize() {
+        let input = "1\n2\nboom\n3\n4\n";
+        let minimized = minimize(&Fragile, input, &[Cut::Lines], &[1], &Predicate::Panics).unwrap();
+        assert_eq!(minimized, "boom\n");
+
+        let minimized = minimize(&Fragile, "1\n2\n3\nx\n", &[Cut::Lines], &[1], &Predicate::Error("invalid digit".to_string()));
+        assert_eq!(minimized.unwrap(), "x\n");
+
+        let minimized = minimize(&Fragile, "0\n5\n0\n0\n", &[Cut::Lines], &[1], &Predicate::Answer("5".to_string()));
+        assert_eq!(minimized.unwrap(), "5\n");
+
+        let error = minimize(&Fragile, "1\n2\n", &[Cut::Lines], &[1], &Predicate::Panics).unwrap_err();
+        assert_eq!(error.to_string(), "The input doesn't satisfy the predicate: solving it never panics");
+    }
+
+    /// A day 5 almanac with a broken mapping shrinks to its seeds and the
+    /// bad number.
+    #[test]
+    fn test_minimize_almanac() {
+        let input = "seeds: 79 14 55 13\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nsoil-to-location map:\n0 15 x\n37 52 2\n";
+        let minimized = minimize(
+            &day05::Day05,
+            input,
+            cuts(2023, 5),
+            &[1],
+            &Predicate::Error("'x'".to_string()),
+        );
+        assert_eq!(minimized.unwrap(), "seeds:\nx\n");
+    }
+}
@@ -0,0 +1,126 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+
+#[derive(Args)]
+pub struct PlaygroundArgs {
+    /// Serve the playground on this port of localhost once it's built.
+    #[arg(long, value_name = "PORT")]
+    serve: Option<u16>,
+}
+
+/// Build the solvers for WebAssembly into `playground/pkg`, for the page in
+/// `playground/`, and optionally serve it.
+pub fn run(args: &PlaygroundArgs, config: &Config) -> Result<()> {
+    let root = config.root();
+    let wasm = build_wasm(root)?;
+    let playground = root.join("playground");
+    let pkg = playground.join("pkg");
+    let status = Command::new("wasm-bindgen")
+        .args(["--target", "web", "--no-typescript", "--out-dir"])
+        .arg(&pkg)
+        .arg(&wasm)
+        .status()
+        .context("Error running wasm-bindgen, which `cargo install wasm-bindgen-cli` installs")?;
+    if !status.success() {
+        return Err(anyhow!("wasm-bindgen failed: {status}"));
+    }
+    println!("Built {}", pkg.display());
+    if let Some(port) = args.serve {
+        serve(&playground, port)?;
+    }
+    Ok(())
+}
+
+/// Build `aoc-wasm` in release mode for `wasm32-unknown-unknown`, returning
+/// the module's path.
+fn build_wasm(root: &Path) -> Result<PathBuf> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let status = Command::new(cargo)
+        .args(["build", "--release", "-p", "aoc-wasm", "--target", "wasm32-unknown-unknown"])
+        .current_dir(root)
+        .status()
+        .context("Error running cargo")?;
+    if !status.success() {
+        return Err(anyhow!(
+            "Building for WebAssembly failed: {status}; `rustup target add wasm32-unknown-unknown` adds the target"
+        ));
+    }
+    Ok(root.join("target").join("wasm32-unknown-unknown").join("release").join("aoc_wasm.wasm"))
+}
+
+/// Serve the files in `dir` on localhost until interrupted, one request at
+/// a time, which is plenty for one browser.
+fn serve(dir: &Path, port: u16) -> Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).with_context(|| format!("Error listening on port {port}"))?;
+    println!("Serving http://localhost:{port}/");
+    for stream in listener.incoming() {
+        if let Err(error) = stream.map_err(anyhow::Error::from).and_then(|stream| respond(dir, stream)) {
+            eprintln!("Error serving a request: {error:#}");
+        }
+    }
+    Ok(())
+}
+
+/// Answer a GET for a file in `dir`.
+fn respond(dir: &Path, mut stream: TcpStream) -> Result<()> {
+    let mut request = String::new();
+    BufReader::new(&stream).read_line(&mut request)?;
+    let path = request.split_whitespace().nth(1).unwrap_or("/");
+    let (status, content_type, body) = match resolve(dir, path).and_then(|file| Some((fs::read(&file).ok()?, file))) {
+        Some((body, file)) => ("200 OK", content_type(&file), body),
+        None => ("404 Not Found", "text/plain", b"Not found\n".to_vec()),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    Ok(())
+}
+
+/// The file a request's path names in `dir`, with "/" meaning the page, or
+/// `None` if it would be outside `dir`.
+fn resolve(dir: &Path, path: &str) -> Option<PathBuf> {
+    let path = path.split(['?', '#']).next().unwrap_or_default().trim_start_matches('/');
+    let path = Path::new(if path.is_empty() { "index.html" } else { path });
+    path.components().all(|component| matches!(component, Component::Normal(_))).then(|| dir.join(path))
+}
+
+/// The content type for a file, by its extension. Browsers only compile a
+/// module streamed as `application/wasm`.
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "text/javascript",
+        Some("wasm") => "application/wasm",
+        Some("css") => "text/css",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let dir = Path::new("playground");
+        assert_eq!(resolve(dir, "/"), Some(dir.join("index.html")));
+        assert_eq!(resolve(dir, "/?day=5"), Some(dir.join("index.html")));
+        assert_eq!(resolve(dir, "/pkg/aoc_wasm.js"), Some(dir.join("pkg/aoc_wasm.js")));
+        assert_eq!(resolve(dir, "/../Cargo.toml"), None);
+        assert_eq!(resolve(dir, "/pkg/../../.env"), None);
+        assert_eq!(content_type(&dir.join("pkg/aoc_wasm_bg.wasm")), "application/wasm");
+        assert_eq!(content_type(&dir.join("index.html")), "text/html; charset=utf-8");
+        assert_eq!(content_type(&dir.join("README")), "application/octet-stream");
+    }
+}
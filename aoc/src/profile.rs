@@ -0,0 +1,87 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::source::InputSource;
+
+use crate::solvers;
+
+#[derive(Args)]
+pub struct ProfileArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Profile only this part. Defaults to both.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// How many stack samples to take a second.
+    #[arg(long, default_value_t = 997)]
+    frequency: i32,
+    /// Solve over and over for at least this long, like "500ms" or "5s", so
+    /// a quick solve still gets enough samples.
+    #[arg(long, default_value = "1s", value_parser = crate::run::parse_duration)]
+    duration: Duration,
+    /// Where to write the flamegraph. Defaults to a new SVG in `profiles/`.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// The input to solve, a file, "-" for stdin, a URL, or "clipboard:".
+    /// Defaults to the stored input.
+    input: Option<InputSource>,
+}
+
+/// Solve a day while sampling its stacks, and write the samples as a
+/// flamegraph SVG.
+pub fn run(args: &ProfileArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let day = args.day;
+    let solver = solvers::find(year, day).ok_or_else(|| anyhow!("There is no solution for {year} day {day}"))?;
+    let input = crate::input::read(config, year, day, args.input.as_ref(), false)?;
+    let parts = args.part.map_or(vec![1, 2], |part| vec![part]);
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(args.frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    let start = Instant::now();
+    let mut solves = 0;
+    while solves == 0 || start.elapsed() < args.duration {
+        solver.solve_timed(&input, &parts)?;
+        solves += 1;
+    }
+    let report = guard.report().build()?;
+
+    let path = match &args.output {
+        Some(path) => path.clone(),
+        None => default_path(config.root(), year, day, SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs()),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(&path).with_context(|| format!("Error creating '{}'", path.display()))?;
+    report.flamegraph(file)?;
+    let samples: isize = report.data.values().sum();
+    println!("Wrote {} from {samples} samples of {solves} solves", path.display());
+    Ok(())
+}
+
+/// A path in `profiles/` unique to the run, like
+/// "profiles/2023-day05-1700000000.svg".
+fn default_path(root: &Path, year: u16, day: u8, time: u64) -> PathBuf {
+    root.join("profiles").join(format!("{year}-day{day:02}-{time}.svg"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(default_path(Path::new("/repo"), 2023, 5, 17), PathBuf::from("/repo/profiles/2023-day05-17.svg"));
+    }
+}
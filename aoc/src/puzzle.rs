@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::http;
+use aoc_utils::puzzle;
+
+#[derive(Args)]
+pub struct PuzzleArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Download the puzzle again, e.g. after part 1 is solved.
+    #[arg(long)]
+    refresh: bool,
+}
+
+pub fn run(args: &PuzzleArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let cache_dir = http::default_cache_dir()?;
+    if !args.refresh {
+        if let Some(markdown) = puzzle::read_cached(&cache_dir, year, args.day)? {
+            print!("{markdown}");
+            return Ok(());
+        }
+    }
+
+    let client = crate::client(config)?;
+    let markdown = puzzle::fetch(&client, &cache_dir, year, args.day, args.refresh)?;
+    print!("{markdown}");
+    Ok(())
+}
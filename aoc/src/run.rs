@@ -0,0 +1,783 @@
+use std::fmt;
+use std::process;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use clap::{Args, ValueEnum};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use aoc_utils::answers;
+use aoc_utils::bench::StageTimes;
+use aoc_utils::cache::AnswerCache;
+use aoc_utils::cancel::Cancel;
+use aoc_utils::config::Config;
+use aoc_utils::error::AocError;
+use aoc_utils::exit::{Failed, Mismatch};
+use aoc_utils::interrupt;
+use aoc_utils::output::{format_bytes, format_duration, ColorChoice, Output, Timings};
+use aoc_utils::progress::{self, MultiProgress};
+use aoc_utils::solver::{Answer, DynSolver, Solved};
+use aoc_utils::source::InputSource;
+use aoc_utils::stats::Stats;
+use aoc_utils::usage::Usage;
+
+use crate::solvers;
+
+#[derive(Clone, Copy, Default, ValueEnum)]
+pub enum Format {
+    /// "Part 1: answer" for each part, or a table of the days when running
+    /// a whole year.
+    #[default]
+    Human,
+    /// A JSON object for each part, one per line, with the answer and the
+    /// time taken in milliseconds.
+    Json,
+}
+
+/// A part's result as printed with `--format json`.
+#[derive(Serialize)]
+struct Record<'a> {
+    year: u16,
+    day: u8,
+    part: u8,
+    answer: &'a Answer,
+    read_ms: f64,
+    parse_ms: f64,
+    solve_ms: f64,
+    /// The day's CPU and memory use, left out where it isn't known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_rss_bytes: Option<u64>,
+    /// Whether the answer came from the cache, so nothing was timed.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    cached: bool,
+}
+
+/// A stage's times as printed by `--bench` with `--format json`.
+#[derive(Serialize)]
+struct BenchRecord<'a> {
+    year: u16,
+    day: u8,
+    stage: &'a str,
+    runs: usize,
+    #[serde(flatten)]
+    times: StageTimes,
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    /// Defaults to every day of the year, solved in parallel.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+    /// Solve only this part. Defaults to both.
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// The input to solve, a file, "-" for stdin, a URL, or
+    /// "clipboard:". Defaults to the stored input.
+    #[arg(requires = "day")]
+    input: Option<InputSource>,
+    /// Fail on input that isn't UTF-8, giving the offset of the first bad
+    /// byte, instead of replacing the bad bytes.
+    #[arg(long)]
+    strict_utf8: bool,
+    /// How to print the answers.
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Print only the answers, one per line, for scripts.
+    #[arg(short, long, conflicts_with = "format")]
+    quiet: bool,
+    /// Print how long reading, parsing, and solving each part took, and the
+    /// CPU time and peak memory of the solve.
+    #[arg(long)]
+    time: bool,
+    /// Give up on a day's solve after this long, like "500ms", "30s", or
+    /// "2m", reporting any part already solved.
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    timeout: Option<Duration>,
+    /// Solve even if the answers for this input are cached, without
+    /// caching the new ones.
+    #[arg(long)]
+    no_cache: bool,
+    /// Solve this many times after a warmup run, and print the minimum,
+    /// mean, median, and standard deviation of each stage's time.
+    #[arg(long, value_name = "N", requires = "day", conflicts_with_all = ["quiet", "timeout"],
+          value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+    /// Count the heap allocations of the solve, and the most bytes live at
+    /// once. Needs the `dhat-heap` feature.
+    #[cfg(feature = "dhat-heap")]
+    #[arg(long, requires = "day", conflicts_with_all = ["quiet", "timeout", "bench"])]
+    heap_profile: bool,
+}
+
+/// A day's heap usage as printed by `--heap-profile` with `--format json`.
+#[cfg(feature = "dhat-heap")]
+#[derive(Serialize)]
+struct HeapRecord {
+    year: u16,
+    day: u8,
+    #[serde(flatten)]
+    heap: crate::heap::HeapUsage,
+}
+
+/// The error for a solve that took longer than `--timeout`.
+#[derive(Debug)]
+struct TimedOut {
+    elapsed: Duration,
+    solved: Vec<Solved>,
+}
+
+/// "Timed out after 5.00s", then any answers found by then.
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Timed out after {}", format_duration(self.elapsed))?;
+        for solved in &self.solved {
+            write!(f, ", part {} was {}", solved.part, solved.answer)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for TimedOut {}
+
+/// A day's answers, how long its input took to read, and what the solve
+/// used where that's known.
+struct DayRun {
+    day: u8,
+    read_time: Duration,
+    solved: Vec<Solved>,
+    usage: Option<Usage>,
+    /// Whether the answers came from the cache instead of a solve.
+    cached: bool,
+}
+
+/// Solve a puzzle with its day's solver, printing each part's answer, or
+/// every day of the year if no day is given. Answers for a stored input are
+/// checked against the expected ones.
+pub fn run(args: &RunArgs, config: &Config, color: ColorChoice) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let output = Output::new(args.quiet).show_time(args.time).color(color);
+    let Some(day) = args.day else {
+        return run_all(args, config, year, output);
+    };
+    let solver = solvers::find(year, day).ok_or_else(|| anyhow!("There is no solution for {year} day {day}"))?;
+    let start = Instant::now();
+    interrupt::install(move || {
+        eprintln!("Interrupted solving {year} day {day} after {}", format_duration(start.elapsed()));
+        process::exit(interrupt::EXIT_CODE.into());
+    })?;
+    if let Some(runs) = args.bench {
+        return bench(args, config, year, day, solver, runs, output);
+    }
+    #[cfg(feature = "dhat-heap")]
+    if args.heap_profile {
+        return heap_profile(args, config, year, day, solver, output);
+    }
+    let run = solve_day(args, config, year, day, solver)?;
+    for solved in &run.solved {
+        match args.format {
+            Format::Human => output.answer(&format!("Part {}", solved.part), &solved.answer),
+            Format::Json => println!("{}", serde_json::to_string(&record(year, &run, solved))?),
+        }
+    }
+    if run.cached {
+        if args.time {
+            eprintln!("The answers were cached, so weren't timed; pass --no-cache to solve");
+        }
+    } else {
+        output.timings(&timings(run.read_time, &run.solved));
+    }
+    if let Some(usage) = run.usage.filter(|_| args.time) {
+        eprintln!("{usage}");
+    }
+    if args.input.is_none() {
+        check(config, year, &run)?;
+    }
+    Ok(())
+}
+
+/// Solve every day of the year on a thread pool, with a spinner for each,
+/// then print the answers in day order.
+fn run_all(args: &RunArgs, config: &Config, year: u16, output: Output) -> Result<()> {
+    let days: Vec<_> = solvers::year(year).collect();
+    if days.is_empty() {
+        return Err(anyhow!("There are no solutions for {year}"));
+    }
+
+    // Nothing else is printed until every day is done, so the spinners
+    // aren't drawn over.
+    let spinners = MultiProgress::new();
+    let days: Vec<_> = days
+        .into_iter()
+        .map(|(day, solver)| (day, solver, spinners.add(progress::spinner(format!("Day {day:>2}")))))
+        .collect();
+
+    // On Ctrl-C, show the days solved so far and exit.
+    let finished = Arc::new(Mutex::new(Vec::new()));
+    let numbers: Vec<u8> = days.iter().map(|(day, _, _)| *day).collect();
+    interrupt::install({
+        let finished = Arc::clone(&finished);
+        let spinners = spinners.clone();
+        move || {
+            let _ = spinners.clear();
+            let mut runs = finished.lock().unwrap();
+            for &day in &numbers {
+                if !runs.iter().any(|(finished, _)| *finished == day) {
+                    runs.push((day, Err(anyhow!("interrupted"))));
+                }
+            }
+            runs.sort_by_key(|(day, _)| *day);
+            print!("{}", table(&runs));
+            process::exit(interrupt::EXIT_CODE.into());
+        }
+    })?;
+
+    days.par_iter().for_each(|(day, solver, spinner)| {
+        spinner.set_message("solving");
+        let run = solve_day(args, config, year, *day, *solver);
+        spinner.finish_with_message(if run.is_ok() { "done" } else { "failed" });
+        finished.lock().unwrap().push((*day, run));
+    });
+    spinners.clear()?;
+    let mut runs = std::mem::take(&mut *finished.lock().unwrap());
+    runs.sort_by_key(|(day, _)| *day);
+
+    match args.format {
+        Format::Human if !output.quiet => print!("{}", table(&runs)),
+        Format::Human => {
+            for solved in runs.iter().filter_map(|(_, run)| run.as_ref().ok()).flat_map(|run| &run.solved) {
+                output.answer("", &solved.answer);
+            }
+        }
+        Format::Json => {
+            for run in runs.iter().filter_map(|(_, run)| run.as_ref().ok()) {
+                for solved in &run.solved {
+                    println!("{}", serde_json::to_string(&record(year, run, solved))?);
+                }
+            }
+        }
+    }
+
+    let mut timings = Timings::new();
+    for run in runs.iter().filter_map(|(_, run)| run.as_ref().ok()).filter(|run| !run.cached) {
+        timings.record(format!("day {}", run.day), self::timings(run.read_time, &run.solved).total());
+    }
+    output.timings(&timings);
+
+    let mismatches: Vec<(u8, anyhow::Error)> = runs
+        .iter()
+        .filter_map(|(day, run)| Some((*day, check(config, year, run.as_ref().ok()?).err()?)))
+        .collect();
+    let mut failures: Vec<(u8, &anyhow::Error)> = runs
+        .iter()
+        .filter_map(|(day, run)| Some((*day, run.as_ref().err()?)))
+        .chain(mismatches.iter().map(|(day, error)| (*day, error)))
+        .collect();
+    if !failures.is_empty() {
+        failures.sort_by_key(|(day, _)| *day);
+        let errors: Vec<String> = failures.iter().map(|(day, error)| format!("Day {day}: {error:#}")).collect();
+        let message = format!("{} of {} days failed\n{}", failures.len(), runs.len(), errors.join("\n"));
+        return Err(Failed::new(message, failures.iter().map(|(_, error)| *error)).into());
+    }
+    Ok(())
+}
+
+/// Solve a day `runs` times after a warmup run, printing its answers and
+/// the statistics of each stage's time. The input is read once.
+fn bench(
+    args: &RunArgs,
+    config: &Config,
+    year: u16,
+    day: u8,
+    solver: &dyn DynSolver,
+    runs: u32,
+    output: Output,
+) -> Result<()> {
+    let input = crate::input::read(config, year, day, args.input.as_ref(), args.strict_utf8)?;
+    let parts = args.part.map_or(vec![1, 2], |part| vec![part]);
+    let (warmup, stats) = time_runs(solver, &input, &parts, runs)?;
+
+    match args.format {
+        Format::Human => {
+            for solved in &warmup {
+                output.answer(&format!("Part {}", solved.part), &solved.answer);
+            }
+            print!("{}", bench_table(runs, &stats));
+        }
+        Format::Json => {
+            for (stage, stats) in &stats {
+                let record = BenchRecord { year, day, stage, runs: stats.runs, times: (*stats).into() };
+                println!("{}", serde_json::to_string(&record)?);
+            }
+        }
+    }
+    if args.input.is_none() {
+        check(config, year, &DayRun { day, read_time: Duration::ZERO, solved: warmup, usage: None, cached: false })?;
+    }
+    Ok(())
+}
+
+/// Solve a day while counting its heap allocations, printing its answers
+/// and the totals.
+#[cfg(feature = "dhat-heap")]
+fn heap_profile(
+    args: &RunArgs,
+    config: &Config,
+    year: u16,
+    day: u8,
+    solver: &dyn DynSolver,
+    output: Output,
+) -> Result<()> {
+    let input = crate::input::read(config, year, day, args.input.as_ref(), args.strict_utf8)?;
+    let parts = args.part.map_or(vec![1, 2], |part| vec![part]);
+    let (solved, heap) = crate::heap::profile(solver, &input, &parts)?;
+    match args.format {
+        Format::Human => {
+            for solved in &solved {
+                output.answer(&format!("Part {}", solved.part), &solved.answer);
+            }
+            output.detail(format_args!("Heap: {heap}"));
+        }
+        Format::Json => println!("{}", serde_json::to_string(&HeapRecord { year, day, heap })?),
+    }
+    if args.input.is_none() {
+        check(config, year, &DayRun { day, read_time: Duration::ZERO, solved, usage: None, cached: false })?;
+    }
+    Ok(())
+}
+
+/// Each stage's name and the statistics of its time.
+pub type StageStats = Vec<(String, Stats)>;
+
+/// Solve `runs` times after a warmup run, returning the warmup's answers and
+/// the statistics of each stage's time.
+pub fn time_runs(solver: &dyn DynSolver, input: &str, parts: &[u8], runs: u32) -> Result<(Vec<Solved>, StageStats)> {
+    let warmup = solver.solve_timed(input, parts)?;
+    let mut times: Vec<Vec<Solved>> = Vec::new();
+    for _ in 0..runs {
+        times.push(solver.solve_timed(input, parts)?);
+    }
+    Ok((warmup, bench_stats(parts, &times)))
+}
+
+/// The statistics of parsing, each part, and the total, over the runs.
+fn bench_stats(parts: &[u8], runs: &[Vec<Solved>]) -> StageStats {
+    let stage = |time: &dyn Fn(&[Solved]) -> Duration| {
+        Stats::new(&runs.iter().map(|run| time(run)).collect::<Vec<_>>()).unwrap()
+    };
+    let mut stats = vec![("parse".to_string(), stage(&|run| run[0].parse_time))];
+    for (i, part) in parts.iter().enumerate() {
+        stats.push((format!("part {part}"), stage(&|run| run[i].solve_time)));
+    }
+    stats.push((
+        "total".to_string(),
+        stage(&|run| run[0].parse_time + run.iter().map(|solved| solved.solve_time).sum::<Duration>()),
+    ));
+    stats
+}
+
+/// A row for each stage with its statistics.
+fn bench_table(runs: u32, stats: &[(String, Stats)]) -> String {
+    let header = ["Stage", "Min", "Mean", "Median", "Std dev"].map(String::from);
+    let rows: Vec<[String; 5]> = stats
+        .iter()
+        .map(|(stage, stats)| {
+            let [min, mean, median, stddev] = [stats.min, stats.mean, stats.median, stats.stddev].map(format_duration);
+            [stage.clone(), min, mean, median, stddev]
+        })
+        .collect();
+    let mut widths = header.clone().map(|cell| cell.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = format!("{runs} runs after a warmup:\n");
+    for row in std::iter::once(header).chain(rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| if i == 0 { format!("{cell:<width$}") } else { format!("{cell:>width$}") })
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// Check a day's answers against the expected ones, as by
+/// [`answers::expected`], failing with [`Mismatch`] for the first that
+/// differs.
+fn check(config: &Config, year: u16, run: &DayRun) -> Result<()> {
+    let expected = answers::expected(config, year, run.day)?;
+    for solved in &run.solved {
+        let answer = solved.answer.to_string();
+        match &expected[usize::from(solved.part - 1)] {
+            Some(expected) if *expected != answer => {
+                return Err(Mismatch { part: solved.part, answer, expected: expected.clone() }.into());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Read a day's input and solve the parts asked for, or look up their
+/// answers if they're all cached.
+fn solve_day(
+    args: &RunArgs,
+    config: &Config,
+    year: u16,
+    day: u8,
+    solver: &'static dyn DynSolver,
+) -> Result<DayRun> {
+    let start = Instant::now();
+    let input = crate::input::read(config, year, day, args.input.as_ref(), args.strict_utf8)?;
+    let read_time = start.elapsed();
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    let cache = if args.no_cache { None } else { AnswerCache::open().ok() };
+    let hash = AnswerCache::hash(&input);
+    if let Some(cache) = &cache {
+        let cached: Option<Vec<Solved>> = parts
+            .iter()
+            .map(|&part| {
+                let answer = cache.get(year, day, part, &hash)?;
+                Some(Solved { part, answer, parse_time: Duration::ZERO, solve_time: Duration::ZERO })
+            })
+            .collect();
+        if let Some(solved) = cached {
+            return Ok(DayRun { day, read_time, solved, usage: None, cached: true });
+        }
+    }
+    let (solved, usage) = match args.timeout {
+        Some(timeout) => solve_with_timeout(solver, input, parts, timeout)?,
+        None => {
+            let (solved, usage) = measure(|| solver.solve_timed(&input, &parts));
+            (solved?, usage)
+        }
+    };
+    if let Some(cache) = &cache {
+        for solved in &solved {
+            // The cache only saves time, so failing to write it isn't an
+            // error.
+            let _ = cache.put(year, day, solved.part, &hash, &solved.answer);
+        }
+    }
+    Ok(DayRun { day, read_time, solved, usage, cached: false })
+}
+
+/// Run `f`, with the CPU time it took and the peak memory after, as by
+/// [`Usage::current`].
+fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<Usage>) {
+    let before = Usage::current();
+    let result = f();
+    let usage = before.zip(Usage::current()).map(|(before, after)| after.since(before));
+    (result, usage)
+}
+
+/// Solve on another thread, giving up after `timeout`. The solver is
+/// cancelled then, but not waited for, so one that never checks for
+/// cancelling can't hang the run.
+fn solve_with_timeout(
+    solver: &'static dyn DynSolver,
+    input: String,
+    parts: Vec<u8>,
+    timeout: Duration,
+) -> Result<(Vec<Solved>, Option<Usage>)> {
+    let start = Instant::now();
+    let cancel = Cancel::with_timeout(timeout);
+    let (sender, receiver) = mpsc::channel();
+    // Set by the solving thread before it hangs up.
+    let usage = Arc::new(Mutex::new(None));
+    thread::spawn({
+        let cancel = cancel.clone();
+        let usage = Arc::clone(&usage);
+        move || {
+            let (result, measured) = measure(|| {
+                solver.solve_each(&input, &parts, &cancel, &mut |solved| {
+                    let _ = sender.send(Ok(solved));
+                })
+            });
+            *usage.lock().unwrap() = measured;
+            if let Err(error) = result {
+                let _ = sender.send(Err(error));
+            }
+        }
+    });
+
+    let mut solved = Vec::new();
+    loop {
+        match receiver.recv_timeout(timeout.saturating_sub(start.elapsed())) {
+            Ok(Ok(part)) => solved.push(part),
+            Ok(Err(AocError::Cancelled)) | Err(RecvTimeoutError::Timeout) => {
+                cancel.cancel();
+                return Err(TimedOut { elapsed: start.elapsed(), solved }.into());
+            }
+            Ok(Err(error)) => return Err(error.into()),
+            Err(RecvTimeoutError::Disconnected) => return Ok((solved, usage.lock().unwrap().take())),
+        }
+    }
+}
+
+/// Parse a duration: a number with a unit of "us", "ms", "s", or "m", or a
+/// number of seconds.
+pub fn parse_duration(timeout: &str) -> Result<Duration, String> {
+    let (number, unit) = match timeout.find(|c: char| c.is_alphabetic()) {
+        Some(i) => timeout.split_at(i),
+        None => (timeout, "s"),
+    };
+    let number: f64 = number.trim().parse().map_err(|_| format!("invalid duration '{timeout}'"))?;
+    let seconds = match unit {
+        "us" | "µs" => number / 1e6,
+        "ms" => number / 1000.0,
+        "s" => number,
+        "m" => number * 60.0,
+        _ => return Err(format!("unknown unit '{unit}' in '{timeout}'; use us, ms, s, or m")),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("invalid duration '{timeout}'"))
+}
+
+/// A row for each day with its answers and the total time, or its error.
+fn table(runs: &[(u8, Result<DayRun>)]) -> String {
+    let rows: Vec<[String; 7]> = runs
+        .iter()
+        .map(|(day, run)| match run {
+            Ok(run) => {
+                let answer = |part| {
+                    let solved = run.solved.iter().find(|solved| solved.part == part);
+                    solved.map_or("-".to_string(), |solved| solved.answer.to_string())
+                };
+                let total = match run.cached {
+                    true => "cached".to_string(),
+                    false => format_duration(timings(run.read_time, &run.solved).total()),
+                };
+                let (user, system, max_rss) = match run.usage {
+                    Some(usage) => {
+                        (format_duration(usage.user), format_duration(usage.system), format_bytes(usage.max_rss))
+                    }
+                    None => ("-".to_string(), "-".to_string(), "-".to_string()),
+                };
+                [day.to_string(), answer(1), answer(2), total, user, system, max_rss]
+            }
+            Err(error) => {
+                let mut row = <[String; 7]>::default();
+                row[0] = day.to_string();
+                row[1] = format!("error: {error:#}");
+                row
+            }
+        })
+        .collect();
+
+    let header = ["Day", "Part 1", "Part 2", "Time", "User", "System", "Max RSS"].map(String::from);
+    let mut widths = header.clone().map(|cell| cell.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for row in std::iter::once(header).chain(rows) {
+        // The answers are left-aligned, the numbers right.
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (cell, width))| match i {
+                1 | 2 => format!("{cell:<width$}"),
+                _ => format!("{cell:>width$}"),
+            })
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// The time to read the input, parse it, and solve each part.
+fn timings(read_time: Duration, solved: &[Solved]) -> Timings {
+    let mut timings = Timings::new();
+    timings.record("read", read_time);
+    if let Some(first) = solved.first() {
+        timings.record("parse", first.parse_time);
+    }
+    for solved in solved {
+        timings.record(format!("part {}", solved.part), solved.solve_time);
+    }
+    timings
+}
+
+fn record<'a>(year: u16, run: &DayRun, solved: &'a Solved) -> Record<'a> {
+    Record {
+        year,
+        day: run.day,
+        part: solved.part,
+        answer: &solved.answer,
+        read_ms: run.read_time.as_secs_f64() * 1000.0,
+        parse_ms: solved.parse_time.as_secs_f64() * 1000.0,
+        solve_ms: solved.solve_time.as_secs_f64() * 1000.0,
+        user_ms: run.usage.map(|usage| usage.user.as_secs_f64() * 1000.0),
+        system_ms: run.usage.map(|usage| usage.system.as_secs_f64() * 1000.0),
+        max_rss_bytes: run.usage.map(|usage| usage.max_rss),
+        cached: run.cached,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(part: u8, answer: u64) -> Solved {
+        Solved {
+            part,
+            answer: Answer::Unsigned(answer),
+            parse_time: Duration::from_micros(1500),
+            solve_time: Duration::from_millis(2),
+        }
+    }
+
+    fn usage() -> Usage {
+        Usage { user: Duration::from_millis(3), system: Duration::from_micros(500), max_rss: 5 << 20 }
+    }
+
+    #[test]
+    fn test_record() {
+        let mut run =
+            DayRun { day: 6, read_time: Duration::from_millis(3), solved: vec![solved(2, 71503)], usage: None, cached: false };
+        assert_eq!(
+            serde_json::to_string(&record(2023, &run, &run.solved[0])).unwrap(),
+            r#"{"year":2023,"day":6,"part":2,"answer":71503,"read_ms":3.0,"parse_ms":1.5,"solve_ms":2.0}"#
+        );
+        run.usage = Some(usage());
+        assert!(serde_json::to_string(&record(2023, &run, &run.solved[0]))
+            .unwrap()
+            .ends_with(r#""solve_ms":2.0,"user_ms":3.0,"system_ms":0.5,"max_rss_bytes":5242880}"#));
+        run.usage = None;
+        run.cached = true;
+        assert!(serde_json::to_string(&record(2023, &run, &run.solved[0])).unwrap().ends_with(r#""solve_ms":2.0,"cached":true}"#));
+        assert_eq!(
+            timings(run.read_time, &run.solved).to_string(),
+            "Read 3.00ms, parse 1.50ms, part 2 2.00ms, total 6.50ms"
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        let runs: Vec<Vec<Solved>> = (1..=3)
+            .map(|i| {
+                let mut run = vec![solved(1, 13), solved(2, 30)];
+                run[1].solve_time = Duration::from_millis(i);
+                run
+            })
+            .collect();
+        let stats = bench_stats(&[1, 2], &runs);
+        let stages: Vec<&str> = stats.iter().map(|(stage, _)| stage.as_str()).collect();
+        assert_eq!(stages, ["parse", "part 1", "part 2", "total"]);
+        assert_eq!(stats[2].1.mean, Duration::from_millis(2));
+        assert_eq!(stats[3].1.min, Duration::from_micros(4500));
+        assert_eq!(
+            bench_table(3, &stats),
+            "3 runs after a warmup:\n\
+             Stage      Min    Mean  Median  Std dev\n\
+             parse   1.50ms  1.50ms  1.50ms      0ns\n\
+             part 1  2.00ms  2.00ms  2.00ms      0ns\n\
+             part 2  1.00ms  2.00ms  2.00ms   1.00ms\n\
+             total   4.50ms  5.50ms  5.50ms   1.00ms\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("250us"), Ok(Duration::from_micros(250)));
+        assert_eq!(parse_duration("250µs"), Ok(Duration::from_micros(250)));
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("5h").is_err());
+        assert!(parse_duration("-1s").is_err());
+        assert!(parse_duration("soon").is_err());
+    }
+
+    /// A solver whose part 2 never finishes.
+    struct Stuck;
+
+    impl aoc_utils::solver::Solver for Stuck {
+        type Parsed = ();
+
+        fn parse(&self, _: &str) -> aoc_utils::error::Result<()> {
+            Ok(())
+        }
+
+        fn part1(&self, _: &(), _: &Cancel) -> aoc_utils::error::Result<Answer> {
+            Ok(Answer::Unsigned(1))
+        }
+
+        fn part2(&self, _: &(), _: &Cancel) -> aoc_utils::error::Result<Answer> {
+            loop {
+                thread::park();
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_with_timeout() {
+        let error = solve_with_timeout(&Stuck, String::new(), vec![1, 2], Duration::from_millis(50)).unwrap_err();
+        let timed_out = error.downcast_ref::<TimedOut>().unwrap();
+        assert_eq!(timed_out.solved.len(), 1);
+        assert!(error.to_string().ends_with(", part 1 was 1"));
+
+        let (solved, usage) = solve_with_timeout(&Stuck, String::new(), vec![1], Duration::from_secs(60)).unwrap();
+        assert_eq!(solved[0].answer, Answer::Unsigned(1));
+        assert_eq!(usage.is_some(), cfg!(unix));
+    }
+
+    #[test]
+    fn test_table() {
+        let runs = [
+            (4, Err(anyhow!("No input stored for 2023 day 4"))),
+            (5, Ok(DayRun { day: 5, read_time: Duration::ZERO, solved: vec![solved(1, 35)], usage: None, cached: false })),
+            (
+                6,
+                Ok(DayRun {
+                    day: 6,
+                    read_time: Duration::ZERO,
+                    solved: vec![solved(1, 288), solved(2, 71503)],
+                    usage: Some(usage()),
+                    cached: false,
+                }),
+            ),
+            (
+                7,
+                Ok(DayRun {
+                    day: 7,
+                    read_time: Duration::ZERO,
+                    solved: vec![solved(1, 6440), solved(2, 5905)],
+                    usage: None,
+                    cached: true,
+                }),
+            ),
+        ];
+        assert_eq!(
+            table(&runs),
+            "Day  Part 1                                 Part 2    Time    User  System  Max RSS\n\
+            \x20 4  error: No input stored for 2023 day 4\n\
+            \x20 5  35                                     -       3.50ms       -       -        -\n\
+            \x20 6  288                                    71503   5.50ms  3.00ms   500µs  5.00MiB\n\
+            \x20 7  6440                                   5905    cached       -       -        -\n"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Generated inputs of any size for each day there is a generator for, and
+//! how the day's solve time should grow with the size.
+
+use std::fmt;
+
+/// The seed for every generated input, so sizes differ only in size.
+const SEED: u64 = 12345;
+
+/// How a solve's time should grow with the size of its input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Complexity {
+    Constant,
+    Linear,
+    Quadratic,
+}
+
+impl Complexity {
+    /// The time for a size of `n`, up to a constant factor.
+    fn cost(self, n: f64) -> f64 {
+        match self {
+            Complexity::Constant => 1.0,
+            Complexity::Linear => n,
+            Complexity::Quadratic => n * n,
+        }
+    }
+
+    /// The power of the size that the time should grow as from `from` to
+    /// `to`: 0 for constant, 1 for linear, and 2 for quadratic.
+    pub fn exponent(self, from: usize, to: usize) -> f64 {
+        let (from, to) = (from as f64, to as f64);
+        (self.cost(to) / self.cost(from)).ln() / (to / from).ln()
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Complexity::Constant => "O(1)",
+            Complexity::Linear => "O(n)",
+            Complexity::Quadratic => "O(n²)",
+        })
+    }
+}
+
+/// A day's inputs of growing size.
+pub struct Scaling {
+    /// What the size counts, as in "1000 lines".
+    pub unit: &'static str,
+    /// The size at 1x.
+    pub base: usize,
+    /// The input of a size.
+    pub input: fn(usize) -> String,
+    pub complexity: Complexity,
+}
+
+const SCALINGS: &[(u16, u8, Scaling)] = &[
+    (
+        2023,
+        1,
+        Scaling {
+            unit: "lines",
+            base: 1_000,
+            input: |lines| day01::generate::document(SEED, lines, 30),
+            complexity: Complexity::Linear,
+        },
+    ),
+    (
+        2023,
+        4,
+        Scaling {
+            unit: "cards",
+            base: 1_000,
+            input: |cards| day04::generate::cards(SEED, cards as u32, 5, 8),
+            complexity: Complexity::Linear,
+        },
+    ),
+    // The seed ranges and mappings grow together. Part 1's lookups are
+    // binary searches, but part 2 splits each range against every mapping.
+    (
+        2023,
+        5,
+        Scaling {
+            unit: "seed ranges and mappings",
+            base: 20,
+            input: |size| day05::generate::almanac(SEED, size, 7, size, 1 << 32),
+            complexity: Complexity::Quadratic,
+        },
+    ),
+    // Part 2 reads the races as one, so there's only one, but its time
+    // grows; trying each hold time would make it linear.
+    (
+        2023,
+        6,
+        Scaling {
+            unit: "race time",
+            base: 1_000_000,
+            input: |max_time| day06::generate::races(SEED, 1, max_time as u64),
+            complexity: Complexity::Constant,
+        },
+    ),
+];
+
+/// How a day's inputs can be scaled, if there is a generator for them.
+pub fn find(year: u16, day: u8) -> Option<&'static Scaling> {
+    SCALINGS
+        .iter()
+        .find(|&&(scaling_year, scaling_day, _)| (scaling_year, scaling_day) == (year, day))
+        .map(|(_, _, scaling)| scaling)
+}
+
+/// The power of the size that times grew as from `(size, seconds)` to
+/// another.
+pub fn growth((from, from_time): (usize, f64), (to, to_time): (usize, f64)) -> f64 {
+    (to_time / from_time).ln() / (to as f64 / from as f64).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solvers;
+
+    use super::*;
+
+    #[test]
+    fn test_exponent() {
+        assert_eq!(Complexity::Constant.exponent(10, 100), 0.0);
+        assert!((Complexity::Linear.exponent(10, 100) - 1.0).abs() < 1e-9);
+        assert!((Complexity::Quadratic.exponent(10, 100) - 2.0).abs() < 1e-9);
+        assert!((growth((100, 0.002), (1_000, 0.2)) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inputs_solve() {
+        for &(year, day, ref scaling) in SCALINGS {
+            let input = (scaling.input)(scaling.base);
+            solvers::find(year, day).unwrap().solve_both(&input).unwrap();
+        }
+    }
+}
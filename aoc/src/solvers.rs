@@ -0,0 +1,49 @@
+//! The solver for each day there is one for.
+
+use aoc_utils::solver::DynSolver;
+
+const SOLVERS: &[(u16, u8, &dyn DynSolver)] = &[
+    (2023, 1, &day01::Day01),
+    (2023, 4, &day04::Day04),
+    (2023, 5, &day05::Day05),
+    (2023, 6, &day06::Day06),
+];
+
+/// The solver for a day, if there is one.
+pub fn find(year: u16, day: u8) -> Option<&'static dyn DynSolver> {
+    SOLVERS
+        .iter()
+        .find(|&&(solver_year, solver_day, _)| (solver_year, solver_day) == (year, day))
+        .map(|&(_, _, solver)| solver)
+}
+
+/// The days with a solver in a year, in order, and their solvers.
+pub fn year(year: u16) -> impl Iterator<Item = (u8, &'static dyn DynSolver)> {
+    SOLVERS
+        .iter()
+        .filter(move |&&(solver_year, _, _)| solver_year == year)
+        .map(|&(_, day, solver)| (day, solver))
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_utils::solver::Answer;
+
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let solver = find(2023, 6).unwrap();
+        let answers = solver.solve_both("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+        assert_eq!(answers, [Answer::Unsigned(288), Answer::Unsigned(71503)]);
+        assert!(find(2023, 2).is_none());
+        assert!(find(2022, 1).is_none());
+    }
+
+    #[test]
+    fn test_year() {
+        let days: Vec<u8> = year(2023).map(|(day, _)| day).collect();
+        assert_eq!(days, [1, 4, 5, 6]);
+        assert_eq!(year(2022).count(), 0);
+    }
+}
@@ -0,0 +1,120 @@
+use std::io::{self, IsTerminal, Write};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::submit::{self, History, Verdict};
+
+/// How many times `--wait` submits before giving up on a cooldown that
+/// keeps not ending.
+const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Args)]
+pub struct SubmitArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+    /// Sleep through any cooldown and retry instead of giving up, up to 5
+    /// attempts.
+    #[arg(long)]
+    wait: bool,
+    /// Don't ask before submitting an answer previous guesses show is wrong;
+    /// it is only warned about.
+    #[arg(long)]
+    force: bool,
+    /// The answer, or "-" to read it from the first line of stdin.
+    answer: String,
+}
+
+pub fn run(args: &SubmitArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let answer = read_answer(&args.answer)?;
+    let answer = answer.trim();
+    if answer.is_empty() {
+        return Err(anyhow!("The answer is empty"));
+    }
+
+    let history_dir = submit::default_history_dir()?;
+    let mut history = History::load(&history_dir, year, args.day)?;
+    if let Some(solution) = history.solution(args.part) {
+        println!("Part {} is already solved with the answer {solution}", args.part);
+        return Ok(());
+    }
+    if let Some(previous) = history.find(args.part, answer) {
+        return Err(anyhow!("The answer {answer} was already submitted: {:?}", previous.verdict));
+    }
+    if let Some(warning) = history.check_bounds(args.part, answer) {
+        println!("Warning: {warning}");
+        let interactive = args.answer != "-" && io::stdin().is_terminal();
+        if interactive && !args.force && !confirm("Submit anyway?")? {
+            return Err(anyhow!("Not submitted"));
+        }
+    }
+
+    let client = crate::client(config)?;
+    let mut attempts = 0;
+    let (verdict, message) = loop {
+        if let Some(wait) = history.cooldown_remaining() {
+            if !args.wait {
+                return Err(anyhow!(
+                    "Submissions are on cooldown for another {}; try again later or use --wait",
+                    submit::format_wait(wait)
+                ));
+            }
+            println!("Waiting {} for the cooldown to end...", submit::format_wait(wait));
+            thread::sleep(wait);
+        }
+
+        let (verdict, message) = submit::submit(&client, year, args.day, args.part, answer)?;
+        attempts += 1;
+        history.record(args.part, answer, verdict)?;
+        if let Some(wait) = submit::cooldown(verdict, &message) {
+            history.set_cooldown(wait)?;
+        }
+        history.save()?;
+
+        if verdict != Verdict::TooRecent || !args.wait {
+            break (verdict, message);
+        }
+        if attempts == MAX_ATTEMPTS {
+            return Err(anyhow!("Still submitted too recently after {attempts} attempts: {message}"));
+        }
+    };
+
+    println!("{message}");
+    match verdict {
+        Verdict::Correct => println!("Correct!"),
+        Verdict::TooHigh => println!("Wrong: too high"),
+        Verdict::TooLow => println!("Wrong: too low"),
+        Verdict::Incorrect => println!("Wrong"),
+        Verdict::TooRecent => println!("Not checked: submitted too recently"),
+        Verdict::WrongLevel => println!("Not checked: wrong level"),
+    }
+
+    Ok(())
+}
+
+/// Ask a yes or no question on the terminal, defaulting to no.
+fn confirm(question: &str) -> Result<bool> {
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut reply = String::new();
+    io::stdin().read_line(&mut reply)?;
+    Ok(matches!(reply.trim(), "y" | "Y" | "yes"))
+}
+
+/// The answer argument, or the first line of stdin if it is "-".
+fn read_answer(answer: &str) -> Result<String> {
+    if answer != "-" {
+        return Ok(answer.to_string());
+    }
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line)
+}
@@ -0,0 +1,226 @@
+//! A live table of a year's days, each solved on its own thread, with their
+//! answers checked against the expected ones.
+
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::Constraint;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::widgets::{Block, Cell, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+use aoc_utils::answers;
+use aoc_utils::config::Config;
+use aoc_utils::output::format_duration;
+use aoc_utils::solver::{Answer, DynSolver, Solved};
+
+use crate::solvers;
+
+#[derive(Args)]
+pub struct TuiArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+}
+
+/// Whether an answer matches the expected one for the part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Verified {
+    Correct,
+    Wrong,
+    Unknown,
+}
+
+impl Verified {
+    fn check(expected: Option<&str>, answer: &Answer) -> Verified {
+        match expected {
+            Some(expected) if expected == answer.to_string() => Verified::Correct,
+            Some(_) => Verified::Wrong,
+            None => Verified::Unknown,
+        }
+    }
+
+    fn mark(self) -> &'static str {
+        match self {
+            Verified::Correct => "✓",
+            Verified::Wrong => "✗",
+            Verified::Unknown => "?",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Verified::Correct => Color::Green,
+            Verified::Wrong => Color::Red,
+            Verified::Unknown => Color::Reset,
+        }
+    }
+}
+
+enum Status {
+    Running(Instant),
+    Solved(Vec<(Solved, Verified)>),
+    Failed(String),
+}
+
+struct Day {
+    day: u8,
+    status: Status,
+}
+
+impl Day {
+    /// The day's cells: its number, each part's answer, and the parse and
+    /// solve times.
+    fn cells(&self) -> [String; 5] {
+        let day = self.day.to_string();
+        match &self.status {
+            Status::Running(start) => {
+                [day, format!("running {}", format_duration(start.elapsed())), String::new(), String::new(), String::new()]
+            }
+            Status::Failed(error) => [day, error.clone(), String::new(), String::new(), String::new()],
+            Status::Solved(parts) => {
+                let answer = |part: u8| {
+                    parts
+                        .iter()
+                        .find(|(solved, _)| solved.part == part)
+                        .map_or_else(String::new, |(solved, verified)| format!("{} {}", solved.answer, verified.mark()))
+                };
+                let parse = parts.first().map_or(Duration::ZERO, |(solved, _)| solved.parse_time);
+                let solve = parts.iter().map(|(solved, _)| solved.solve_time).sum();
+                [day, answer(1), answer(2), format_duration(parse), format_duration(solve)]
+            }
+        }
+    }
+
+    fn row(&self) -> Row<'static> {
+        let [day, part1, part2, parse, solve] = self.cells();
+        let part_style = |part: u8| match &self.status {
+            Status::Running(_) => Style::new().dim(),
+            Status::Failed(_) => Style::new().red(),
+            Status::Solved(parts) => {
+                let verified = parts.iter().find(|(solved, _)| solved.part == part).map(|(_, verified)| *verified);
+                Style::new().fg(verified.unwrap_or(Verified::Unknown).color())
+            }
+        };
+        Row::new([
+            Cell::from(day),
+            Cell::from(part1).style(part_style(1)),
+            Cell::from(part2).style(part_style(2)),
+            Cell::from(parse),
+            Cell::from(solve),
+        ])
+    }
+}
+
+/// Solve every day of the year on background threads, showing their progress
+/// until q or Esc is pressed.
+pub fn run(args: &TuiArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let (sender, receiver) = mpsc::channel();
+    let mut days = Vec::new();
+    for (day, solver) in solvers::year(year) {
+        let sender = sender.clone();
+        let config = config.clone();
+        thread::spawn(move || {
+            let result = solve(&config, year, day, solver).map_err(|e| format!("{e:#}"));
+            // The table may have been closed already.
+            let _ = sender.send((day, result));
+        });
+        days.push(Day { day, status: Status::Running(Instant::now()) });
+    }
+    if days.is_empty() {
+        return Err(anyhow!("There are no solutions for {year}"));
+    }
+
+    let mut terminal = ratatui::init();
+    let result = show(&mut terminal, year, &mut days, &receiver);
+    ratatui::restore();
+    result
+}
+
+type DayResult = (u8, Result<Vec<(Solved, Verified)>, String>);
+
+/// Redraw the table as days finish, until the user quits.
+fn show(terminal: &mut DefaultTerminal, year: u16, days: &mut [Day], receiver: &Receiver<DayResult>) -> Result<()> {
+    loop {
+        for (number, result) in receiver.try_iter() {
+            if let Some(day) = days.iter_mut().find(|day| day.day == number) {
+                day.status = match result {
+                    Ok(parts) => Status::Solved(parts),
+                    Err(error) => Status::Failed(error),
+                };
+            }
+        }
+        terminal.draw(|frame| draw(frame, year, days))?;
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, year: u16, days: &[Day]) {
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(8),
+        Constraint::Length(8),
+    ];
+    let table = Table::new(days.iter().map(Day::row), widths)
+        .header(Row::new(["Day", "Part 1", "Part 2", "Parse", "Solve"]).bold())
+        .block(Block::bordered().title(format!(" Advent of Code {year} ")).title_bottom(" q to quit "));
+    frame.render_widget(table, frame.area());
+}
+
+/// Solve both parts of a day's stored input, checking each answer against
+/// the expected one.
+fn solve(config: &Config, year: u16, day: u8, solver: &dyn DynSolver) -> Result<Vec<(Solved, Verified)>> {
+    let input = crate::input::read(config, year, day, None, false)?;
+    let expected = answers::expected(config, year, day)?;
+    Ok(solver
+        .solve_timed(&input, &[1, 2])?
+        .into_iter()
+        .map(|solved| {
+            let verified = Verified::check(expected[usize::from(solved.part - 1)].as_deref(), &solved.answer);
+            (solved, verified)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verified() {
+        assert_eq!(Verified::check(Some("288"), &Answer::Unsigned(288)), Verified::Correct);
+        assert_eq!(Verified::check(Some("288"), &Answer::Unsigned(289)), Verified::Wrong);
+        assert_eq!(Verified::check(None, &Answer::Unsigned(288)), Verified::Unknown);
+    }
+
+    #[test]
+    fn test_cells() {
+        let solved = |part, answer| Solved {
+            part,
+            answer: Answer::Unsigned(answer),
+            parse_time: Duration::from_micros(20),
+            solve_time: Duration::from_micros(5),
+        };
+        let day = Day {
+            day: 6,
+            status: Status::Solved(vec![(solved(1, 288), Verified::Correct), (solved(2, 71503), Verified::Unknown)]),
+        };
+        assert_eq!(day.cells(), ["6", "288 ✓", "71503 ?", "20.0µs", "10.0µs"]);
+
+        let day = Day { day: 5, status: Status::Failed("No input stored for 2023 day 5".to_string()) };
+        assert_eq!(day.cells()[1], "No input stored for 2023 day 5");
+    }
+}
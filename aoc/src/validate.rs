@@ -0,0 +1,51 @@
+use std::io::{self, BufRead};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+
+use aoc_utils::config::Config;
+use aoc_utils::lenient::Validation;
+use aoc_utils::source::InputSource;
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// The input to check, a file, "-" for stdin, a URL, or
+    /// "clipboard:". Defaults to the stored input.
+    input: Option<InputSource>,
+}
+
+/// Check a puzzle input, reporting every error in it.
+pub fn run(args: &ValidateArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let input = crate::input::read(config, year, args.day, args.input.as_ref(), false)?;
+    validate(year, args.day, io::Cursor::new(input))?.print()
+}
+
+/// Check the input with the day's parser.
+fn validate(year: u16, day: u8, reader: impl BufRead) -> Result<Validation> {
+    Ok(match (year, day) {
+        (2023, 1) => day01::validate(reader, &day01::CalibrationParser::numerals()),
+        (2023, 4) => day04::validate(reader),
+        (2023, 5) => day05::validate(reader, false),
+        (2023, 6) => day06::validate(reader),
+        _ => return Err(anyhow!("There is no solution for {year} day {day} to validate with")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let validation = validate(2023, 4, "Card 1: 1 | 1\nCard 2 1 | 1\n\nCard 3: 1 | x\n".as_bytes()).unwrap();
+        assert_eq!(validation.lines.iter().map(|line| line.line_number).collect::<Vec<_>>(), [2, 3, 4]);
+        assert!(validate(2023, 6, "Time: 7\nDistance: 9\n".as_bytes()).unwrap().is_valid());
+        assert!(validate(2023, 2, "".as_bytes()).is_err());
+    }
+}
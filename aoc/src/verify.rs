@@ -0,0 +1,179 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rayon::prelude::*;
+
+use aoc_utils::answers;
+use aoc_utils::cache::AnswerCache;
+use aoc_utils::config::Config;
+use aoc_utils::exit::{Failed, Mismatch};
+use aoc_utils::solver::{Answer, DynSolver};
+
+use crate::solvers;
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Defaults to the configured year.
+    #[arg(short, long)]
+    year: Option<u16>,
+    /// Solve every day even if its answers are cached, without caching the
+    /// new ones.
+    #[arg(long)]
+    no_cache: bool,
+}
+
+/// A part's answer and the expected one, if known.
+struct Check {
+    part: u8,
+    answer: String,
+    expected: Option<String>,
+}
+
+impl Check {
+    fn result(&self) -> &'static str {
+        match &self.expected {
+            Some(expected) if *expected == self.answer => "pass",
+            Some(_) => "FAIL",
+            None => "unknown",
+        }
+    }
+}
+
+/// Solve every day of the year from its stored input, in parallel, and
+/// check the answers against the expected ones, printing a table of which
+/// passed.
+pub fn run(args: &VerifyArgs, config: &Config) -> Result<()> {
+    let year = args.year.unwrap_or_else(|| config.year());
+    let days: Vec<_> = solvers::year(year).collect();
+    if days.is_empty() {
+        return Err(anyhow!("There are no solutions for {year}"));
+    }
+    let cache = if args.no_cache { None } else { AnswerCache::open().ok() };
+    let results: Vec<(u8, Result<Vec<Check>>)> = days
+        .par_iter()
+        .map(|&(day, solver)| (day, verify(config, cache.as_ref(), year, day, solver)))
+        .collect();
+    print!("{}", table(&results));
+
+    let parts = results.iter().map(|(_, result)| result.as_ref().map_or(1, Vec::len)).sum::<usize>();
+    let mut failures = Vec::new();
+    for (day, result) in results {
+        match result {
+            Ok(checks) => failures.extend(checks.into_iter().filter(|check| check.result() == "FAIL").map(|check| {
+                let mismatch = Mismatch { part: check.part, answer: check.answer, expected: check.expected.unwrap() };
+                anyhow::Error::from(mismatch).context(format!("Day {day}"))
+            })),
+            Err(error) => failures.push(error.context(format!("Day {day}"))),
+        }
+    }
+    if !failures.is_empty() {
+        let errors: Vec<String> = failures.iter().map(|error| format!("{error:#}")).collect();
+        let message = format!("{} of {parts} checks failed\n{}", failures.len(), errors.join("\n"));
+        return Err(Failed::new(message, &failures).into());
+    }
+    Ok(())
+}
+
+/// Solve a day's stored input, unless both answers are cached, and pair
+/// each answer with the expected one.
+fn verify(
+    config: &Config,
+    cache: Option<&AnswerCache>,
+    year: u16,
+    day: u8,
+    solver: &dyn DynSolver,
+) -> Result<Vec<Check>> {
+    let input = crate::input::read(config, year, day, None, false)?;
+    let expected = answers::expected(config, year, day)?;
+    let hash = AnswerCache::hash(&input);
+    let cached = cache.and_then(|cache| Some([cache.get(year, day, 1, &hash)?, cache.get(year, day, 2, &hash)?]));
+    let answers: [Answer; 2] = match cached {
+        Some(answers) => answers,
+        None => {
+            let answers = solver.solve_both(&input)?;
+            if let Some(cache) = cache {
+                for (part, answer) in [1, 2].into_iter().zip(&answers) {
+                    // As with `aoc run`, failing to cache isn't an error.
+                    let _ = cache.put(year, day, part, &hash, answer);
+                }
+            }
+            answers
+        }
+    };
+    Ok(answers
+        .into_iter()
+        .zip([1, 2])
+        .map(|(answer, part)| Check {
+            part,
+            answer: answer.to_string(),
+            expected: expected[usize::from(part - 1)].clone(),
+        })
+        .collect())
+}
+
+/// A row for each part with its answer, the expected one, and whether they
+/// match, or a day's error.
+fn table(results: &[(u8, Result<Vec<Check>>)]) -> String {
+    let mut rows: Vec<[String; 5]> = Vec::new();
+    for (day, result) in results {
+        match result {
+            Ok(checks) => rows.extend(checks.iter().map(|check| {
+                [
+                    day.to_string(),
+                    check.part.to_string(),
+                    check.answer.clone(),
+                    check.expected.clone().unwrap_or_else(|| "-".to_string()),
+                    check.result().to_string(),
+                ]
+            })),
+            Err(error) => rows.push([day.to_string(), String::new(), String::new(), String::new(), format!("error: {error:#}")]),
+        }
+    }
+
+    let header = ["Day", "Part", "Answer", "Expected", "Result"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for row in std::iter::once(header.map(String::from)).chain(rows) {
+        let [day, part, answer, expected, result] = row;
+        let line = format!(
+            "{day:>0$}  {part:>1$}  {answer:<2$}  {expected:<3$}  {result}",
+            widths[0], widths[1], widths[2], widths[3]
+        );
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let check = |part, answer: &str, expected: Option<&str>| Check {
+            part,
+            answer: answer.to_string(),
+            expected: expected.map(String::from),
+        };
+        let results = vec![
+            (4, Ok(vec![check(1, "13", Some("13")), check(2, "31", Some("30"))])),
+            (5, Err(anyhow!("No input stored for 2023 day 5"))),
+            (6, Ok(vec![check(1, "288", None)])),
+        ];
+        assert_eq!(
+            table(&results),
+            "\
+Day  Part  Answer  Expected  Result
+  4     1  13      13        pass
+  4     2  31      30        FAIL
+  5                          error: No input stored for 2023 day 5
+  6     1  288     -         unknown
+"
+        );
+    }
+}
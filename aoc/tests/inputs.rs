@@ -0,0 +1,86 @@
+//! Runs `aoc run` end to end on every stored input of a day with a solution,
+//! checking the answers against `answers/<year>.toml`. Inputs aren't
+//! committed everywhere, so a checkout without them has nothing to check.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+use aoc_utils::answers::Answers;
+use aoc_utils::config::Config;
+use aoc_utils::inputs;
+
+/// The years with a directory of solutions in the repository.
+fn years(root: &Path) -> Vec<u16> {
+    let mut years: Vec<u16> = fs::read_dir(root)
+        .unwrap()
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+    years.sort();
+    years
+}
+
+/// The days of a year with a solution crate and a stored input.
+fn days(config: &Config, year: u16) -> Vec<u8> {
+    (1..=25)
+        .filter(|&day| config.root().join(format!("{year}/day{day:02}/Cargo.toml")).exists())
+        .filter(|&day| {
+            let path = config.input_path(year, day);
+            path.exists() || inputs::encrypted_path(&path).exists()
+        })
+        .collect()
+}
+
+/// Solve a day with `aoc run`, returning each part's answer, or what went
+/// wrong.
+fn run(config: &Config, year: u16, day: u8) -> Result<Vec<(u8, String)>, String> {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--year", &year.to_string(), "--day", &day.to_string(), "--format", "json", "--no-cache"])
+        .current_dir(config.root())
+        .env("RUST_BACKTRACE", "0")
+        .output()
+        .map_err(|error| error.to_string())?;
+    if !output.status.success() {
+        return Err(format!("{}: {}", output.status, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            let record: Value = serde_json::from_str(line).map_err(|error| format!("{error} in '{line}'"))?;
+            let part = record["part"].as_u64().ok_or_else(|| format!("No part in '{line}'"))?;
+            let answer = match &record["answer"] {
+                Value::String(answer) => answer.clone(),
+                answer => answer.to_string(),
+            };
+            Ok((part as u8, answer))
+        })
+        .collect()
+}
+
+#[test]
+fn test_stored_inputs() {
+    let config = Config::load().unwrap();
+    let mut failures = Vec::new();
+    for year in years(config.root()) {
+        let answers = Answers::load(&config.answers_path(year)).unwrap();
+        for day in days(&config, year) {
+            match run(&config, year, day) {
+                Ok(solved) => {
+                    let parts: Vec<u8> = solved.iter().map(|(part, _)| *part).collect();
+                    if parts != [1, 2] {
+                        failures.push(format!("{year} day {day}: solved parts {parts:?}"));
+                    }
+                    for (part, answer) in solved {
+                        if answers.get(day, part).is_some_and(|expected| expected != answer) {
+                            failures.push(format!("{year} day {day} part {part}: {answer} isn't the expected answer"));
+                        }
+                    }
+                }
+                Err(error) => failures.push(format!("{year} day {day}: {error}")),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
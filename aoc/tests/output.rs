@@ -0,0 +1,84 @@
+//! Snapshots of `aoc run`'s output for each day's examples, so a change to
+//! its wording or format is reviewed rather than silently breaking scripts
+//! that read it. Update them with `cargo insta review`.
+
+use std::path::Path;
+use std::process::Command;
+
+use insta::assert_snapshot;
+
+/// Run `aoc run` on an example fixture, returning what it printed.
+fn run(day: u8, fixture: &str, args: &[&str]) -> String {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let input = root.join(format!("2023/day{day:02}/fixtures/{fixture}"));
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--year", "2023", "--day", &day.to_string(), "--color", "never", "--no-cache"])
+        .args(args)
+        .arg(input)
+        .current_dir(root)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Snapshot the human and JSON output for a day's example, with the times
+/// and memory, which vary from run to run, redacted.
+macro_rules! snapshot_day {
+    ($name:ident, $day:expr, $fixture:expr $(, $arg:expr)*) => {
+        #[test]
+        fn $name() {
+            assert_snapshot!(concat!(stringify!($name), "_human"), run($day, $fixture, &[$($arg),*]));
+            let filters = vec![
+                (r#""(\w+)_ms":[-+.0-9e]+"#, r#""${1}_ms":"[ms]""#),
+                (r#""max_rss_bytes":\d+"#, r#""max_rss_bytes":"[bytes]""#),
+            ];
+            insta::with_settings!({ filters => filters }, {
+                assert_snapshot!(
+                    concat!(stringify!($name), "_json"),
+                    run($day, $fixture, &["--format", "json", $($arg),*])
+                );
+            });
+        }
+    };
+}
+
+snapshot_day!(test_day01_part1, 1, "example-part1.txt", "--part", "1");
+snapshot_day!(test_day01_part2, 1, "example-part2.txt", "--part", "2");
+snapshot_day!(test_day04, 4, "example.txt");
+snapshot_day!(test_day05, 5, "example.txt");
+snapshot_day!(test_day06, 6, "example.txt");
+
+#[test]
+fn test_quiet() {
+    assert_snapshot!("test_quiet", run(6, "example.txt", &["--quiet"]));
+}
+
+/// A second run of the same input is answered from the cache, until it's
+/// cleared. The cache is kept out of the user's by pointing XDG_CACHE_HOME,
+/// which only Linux reads, at a temporary directory.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_cache() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let cache = std::env::temp_dir().join(format!("aoc-output-cache-{}", std::process::id()));
+    let aoc = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+            .args(args)
+            .current_dir(root)
+            .env("XDG_CACHE_HOME", &cache)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let run = ["run", "--year", "2023", "--day", "6", "--format", "json", "2023/day06/fixtures/example.txt"];
+    assert!(!aoc(&run).contains("cached"));
+    let cached = aoc(&run);
+    assert_eq!(cached.matches(r#""cached":true"#).count(), 2);
+    assert!(cached.contains(r#""answer":71503"#));
+    assert!(!aoc(&[&run[..], &["--no-cache"]].concat()).contains("cached"));
+    assert_eq!(aoc(&["cache", "clear"]), "Removed 2 cached answers\n");
+    assert!(!aoc(&run).contains("cached"));
+    std::fs::remove_dir_all(cache).unwrap();
+}
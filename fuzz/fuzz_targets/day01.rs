@@ -0,0 +1,28 @@
+//! Day 1's calibration parsers on arbitrary bytes, which needn't be UTF-8.
+
+#![no_main]
+
+use std::sync::LazyLock;
+
+use aoc_utils::solver::DynSolver;
+use day01::{CalibrationParser, Day01};
+use libfuzzer_sys::fuzz_target;
+
+/// Built once, as building the automatons is slower than a run.
+static PARSERS: LazyLock<[CalibrationParser; 2]> =
+    LazyLock::new(|| [CalibrationParser::numerals(), CalibrationParser::english()]);
+
+fuzz_target!(|data: &[u8]| {
+    for parser in PARSERS.iter() {
+        let _ = day01::sum_calibration_values(data, parser, false);
+        let _ = day01::sum_calibration_values(data, parser, true);
+        let _ = day01::validate(data, parser);
+    }
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Day01.solve_both(input);
+        for line in input.lines() {
+            assert_eq!(day01::find_first_digit(line), day01::find_first_digit_naive(line));
+            assert_eq!(day01::find_last_digit(line), day01::find_last_digit_naive(line));
+        }
+    }
+});
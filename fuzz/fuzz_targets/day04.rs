@@ -0,0 +1,19 @@
+//! Day 4's card parsers on arbitrary input.
+
+#![no_main]
+
+use aoc_utils::solver::DynSolver;
+use day04::{Card, Day04};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = day04::validate(data);
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Day04.solve_both(input);
+        for line in input.lines() {
+            let _ = day04::process_card(line);
+            let _ = day04::process_card_hashset(line);
+            let _ = line.parse::<Card>();
+        }
+    }
+});
@@ -0,0 +1,21 @@
+//! Day 5's almanac parser on arbitrary input, with and without overlapping
+//! mappings allowed.
+
+#![no_main]
+
+use aoc_utils::solver::DynSolver;
+use day05::{Day05, Mapping, Mappings};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = day05::validate(data, false);
+    let _ = day05::validate(data, true);
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Day05.solve_both(input);
+        let _ = input.parse::<Mappings>();
+        for line in input.lines() {
+            let _ = day05::read_seeds(line);
+            let _ = line.parse::<Mapping>();
+        }
+    }
+});
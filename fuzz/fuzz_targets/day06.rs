@@ -0,0 +1,14 @@
+//! Day 6's race parser and solvers on arbitrary input.
+
+#![no_main]
+
+use aoc_utils::solver::DynSolver;
+use day06::Day06;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = day06::validate(data);
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = Day06.solve_both(input);
+    }
+});
@@ -0,0 +1,151 @@
+// The page: solves whatever is in the text area, a moment after it or the
+// day or part last changed, on a worker.
+
+const DEBOUNCE_MS = 250;
+
+const $ = (id) => document.getElementById(id);
+const year = $('year');
+const day = $('day');
+const part = $('part');
+const input = $('input');
+const results = $('results');
+const status = $('status');
+
+let worker;
+let nextId = 0;
+let pending = new Map();
+
+/** Start a worker, replacing one stuck on a solve no longer wanted. */
+function startWorker() {
+  worker?.terminate();
+  for (const { reject } of pending.values()) {
+    reject(new Error('Cancelled'));
+  }
+  pending = new Map();
+  worker = new Worker('worker.js', { type: 'module' });
+  worker.onmessage = ({ data }) => {
+    pending.get(data.id)?.resolve(data);
+    pending.delete(data.id);
+  };
+  worker.onerror = (event) => {
+    status.textContent = `The solvers failed to load: ${event.message}`;
+    status.className = 'error';
+  };
+}
+
+/** Send the worker a request, resolving with its reply. */
+function ask(request) {
+  const id = nextId++;
+  return new Promise((resolve, reject) => {
+    pending.set(id, { resolve, reject });
+    worker.postMessage({ id, ...request });
+  });
+}
+
+function formatMs(ms) {
+  return ms < 1 ? `${(ms * 1000).toFixed(0)}µs` : `${ms.toFixed(2)}ms`;
+}
+
+function row(cells, className) {
+  const tr = document.createElement('tr');
+  for (const [text, cellClass] of cells) {
+    const td = document.createElement('td');
+    td.textContent = text;
+    td.className = cellClass ?? '';
+    tr.append(td);
+  }
+  tr.className = className ?? '';
+  return tr;
+}
+
+function show(solved) {
+  results.replaceChildren(
+    ...solved.map((result) =>
+      result.error === undefined
+        ? row([
+            [result.part],
+            [result.answer, 'answer'],
+            [formatMs(result.parseMs), 'time'],
+            [formatMs(result.solveMs), 'time'],
+          ])
+        : row([[result.part], [result.error]], 'error'),
+    ),
+  );
+}
+
+async function loadDays() {
+  const { days } = await ask({ type: 'days', year: Number(year.value) });
+  const selected = day.value;
+  day.replaceChildren(
+    ...days.map((number) => {
+      const option = document.createElement('option');
+      option.value = number;
+      option.textContent = number;
+      return option;
+    }),
+  );
+  if (days.map(String).includes(selected)) {
+    day.value = selected;
+  }
+  status.textContent = days.length ? '' : `There are no solutions for ${year.value}`;
+  status.className = '';
+}
+
+let latest = 0;
+let timer;
+let busy = false;
+
+/** Solve the current input, dropping any solve still running for an older one. */
+async function solveNow() {
+  if (!input.value.trim() || !day.value) {
+    results.replaceChildren();
+    return;
+  }
+  if (busy) {
+    startWorker();
+  }
+  const request = ++latest;
+  busy = true;
+  status.textContent = 'Solving…';
+  try {
+    const { results: solved } = await ask({
+      year: Number(year.value),
+      day: Number(day.value),
+      parts: part.value === 'both' ? [1, 2] : [Number(part.value)],
+      input: input.value,
+    });
+    if (request === latest) {
+      show(solved);
+      status.textContent = '';
+    }
+  } catch {
+    // Replaced by a newer solve.
+  } finally {
+    if (request === latest) {
+      busy = false;
+    }
+  }
+}
+
+function solveSoon() {
+  clearTimeout(timer);
+  timer = setTimeout(solveNow, DEBOUNCE_MS);
+}
+
+input.addEventListener('input', solveSoon);
+day.addEventListener('change', solveNow);
+part.addEventListener('change', solveNow);
+year.addEventListener('change', async () => {
+  await loadDays();
+  solveNow();
+});
+$('file').addEventListener('change', async (event) => {
+  const [file] = event.target.files;
+  if (file) {
+    input.value = await file.text();
+    solveNow();
+  }
+});
+
+startWorker();
+loadDays();
@@ -0,0 +1,29 @@
+// Solves on its own thread, so a slow solve doesn't freeze the page. Each
+// message is answered with the same id, so the page can ignore stale ones.
+import init, { days, solve } from './pkg/aoc_wasm.js';
+
+const ready = init();
+
+self.onmessage = async ({ data }) => {
+  await ready;
+  if (data.type === 'days') {
+    self.postMessage({ id: data.id, days: Array.from(days(data.year)) });
+    return;
+  }
+  const results = data.parts.map((part) => {
+    try {
+      const solution = solve(data.year, data.day, part, data.input);
+      const result = {
+        part,
+        answer: solution.answer,
+        parseMs: solution.parse_ms,
+        solveMs: solution.solve_ms,
+      };
+      solution.free();
+      return result;
+    } catch (error) {
+      return { part, error: error.message };
+    }
+  });
+  self.postMessage({ id: data.id, results });
+};
@@ -0,0 +1,75 @@
+//! The solvers built for WebAssembly, with `solve(year, day, part, input)`
+//! for JavaScript, so a web page or Node script can run them without a Rust
+//! toolchain.
+
+use wasm_bindgen::prelude::*;
+
+use aoc_utils::solver::Solved;
+
+// The same table `aoc` uses, so a new day only needs adding there.
+#[path = "../../aoc/src/solvers.rs"]
+mod solvers;
+
+/// A part's answer, and how long parsing the input and solving the part
+/// took, in milliseconds.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+    pub part: u8,
+    pub answer: String,
+    pub parse_ms: f64,
+    pub solve_ms: f64,
+}
+
+impl From<Solved> for Solution {
+    fn from(solved: Solved) -> Solution {
+        Solution {
+            part: solved.part,
+            answer: solved.answer.to_string(),
+            parse_ms: solved.parse_time.as_secs_f64() * 1000.0,
+            solve_ms: solved.solve_time.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Solve a part of a puzzle, 1 or 2, throwing an `Error` with the message
+/// if there's no solver for the day or the input is bad.
+#[wasm_bindgen]
+pub fn solve(year: u16, day: u8, part: u8, input: &str) -> Result<Solution, JsError> {
+    solve_part(year, day, part, input).map_err(|error| JsError::new(&error))
+}
+
+/// The days of a year there's a solver for, in order.
+#[wasm_bindgen]
+pub fn days(year: u16) -> Vec<u8> {
+    solvers::year(year).map(|(day, _)| day).collect()
+}
+
+/// `solve`, with the error as its message, since a `JsError` can only be
+/// made in JavaScript.
+fn solve_part(year: u16, day: u8, part: u8, input: &str) -> Result<Solution, String> {
+    let solver = solvers::find(year, day).ok_or_else(|| format!("There is no solution for {year} day {day}"))?;
+    let mut solved = solver.solve_timed(input, &[part]).map_err(|error| error.to_string())?;
+    Ok(solved.remove(0).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_part() {
+        let races = "Time:      7  15   30\nDistance:  9  40  200\n";
+        let solution = solve_part(2023, 6, 2, races).unwrap();
+        assert_eq!((solution.part, solution.answer.as_str()), (2, "71503"));
+        assert!(solution.parse_ms >= 0.0 && solution.solve_ms >= 0.0);
+        assert_eq!(solve_part(2023, 2, 1, "").unwrap_err(), "There is no solution for 2023 day 2");
+        assert!(solve_part(2023, 6, 3, races).unwrap_err().contains("no part 3"));
+        assert!(solve_part(2023, 6, 1, "Time: 7\n").is_err());
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(days(2023), [1, 4, 5, 6]);
+    }
+}